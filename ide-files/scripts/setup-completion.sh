@@ -46,12 +46,41 @@ _ide-files() {
     typeset -A opt_args
 
     _arguments \
-        '--ide[Specify IDE to detect]:ide:(goland pycharm idea vscode vs webstorm phpstorm rubymine clion vim nano)' \
+        '--ide[Specify IDE to detect]:ide:(goland pycharm idea vscode vs webstorm phpstorm rubymine clion androidstudio datagrip rider rustrover appcode dataspell vim nano onivim helix kakoune ed sublime cursor vscode-insiders)' \
         '--list-ides[List all supported IDEs]' \
         '--auto[Auto-detect any supported IDE]' \
-        '--format[Output format]:format:(json plain paths)' \
+        '--all[Detect every running IDE and print a JSON array]' \
+        '--format[Output format]:format:(json ndjson csv plain paths name summary fzf keyvalue count-by-ext by-language by-language-json)' \
+        '--json[Print the count-by-ext and by-language aggregates as JSON]' \
+        '--sort[Order of open files]:order:(default path name tab)' \
         '--active[Only return the currently active file]' \
+        '--no-focus[Do not use window focus or tmux panes to pick the IDE or terminal editor]' \
+        '--redact[Replace home directory and file/project names with placeholders]' \
+        '--search-network-fs[Let the JetBrains project search descend into network mounts]' \
+        '--cache[Reuse the last result while it is younger than this]:duration:' \
+        '--watch[Keep running and print the result again whenever it changes]' \
+        '--user[Inspect the editors of another user]:user:_users' \
+        '--all-users[Consider the editor processes of every user]' \
+        '--since[Only keep files modified within DURATION]:duration:' \
+        '--since-strict[With --since, also drop files whose modification time cannot be read]' \
+        '--status-file[Read the active file from a status file written by an editor plugin]:file:_files' \
+        '--export[Print a workspace file for another editor]:editor:(vscode jetbrains)' \
+        '--export-to[Write the exported workspace file to a new file]:path:_files' \
+        '--ide-config-dir[Read VSCode/Onivim state from this directory]:dir:_files -/' \
+        '--relative[Print paths relative to the project root]' \
+        '--with-ids[Add a stable per-file id for caching consumers]' \
+        '--poll-active[Print a line each time the active file changes]' \
+        '--track[Report time spent on each file after this long]:duration:' \
+        '--interval[How often --poll-active, --track or --watch polls]:duration:' \
+        '--debounce[How long a --watch result must stay unchanged before it is printed]:duration:' \
+        '--notify[Also show the result as a desktop notification]' \
+        '--follow-symlinks[Let the JetBrains project search follow symlinked directories]' \
+        '*--project-root[Search this directory for JetBrains projects first]:dir:_files -/' \
+        '--search-depth[Directory levels the JetBrains project search descends]:levels:(1 2 3 4 5)' \
+        '--search-timeout[Give up the JetBrains project search after this long]:duration:(250ms 500ms 1s 2s)' \
+        '--editors[Only auto-detect these IDEs, in order]:list:' \
         '(-v --verbose)'{-v,--verbose}'[Enable verbose output]' \
+        '--raw[Print results as the detector produced them]' \
         '--debug-processes[List all running processes]' \
         '--debug-process[List processes matching specific name]:name:' \
         '(-h --help)'{-h,--help}'[Show help]' \
@@ -73,15 +102,63 @@ _ide_files_completion() {
     cur="${COMP_WORDS[COMP_CWORD]}"
     prev="${COMP_WORDS[COMP_CWORD-1]}"
 
-    opts="--ide --list-ides --auto --format --active --verbose --debug-processes --debug-process --help --version"
+    opts="--ide --list-ides --auto --all --format --json --sort --active --no-focus --redact --search-network-fs --cache --watch --user --all-users --since --since-strict --status-file --export --export-to --ide-config-dir --relative --with-ids --poll-active --track --interval --debounce --notify --follow-symlinks --project-root --search-depth --search-timeout --editors --verbose --raw --debug-processes --debug-process --help --version"
     
     case "${prev}" in
         --ide)
-            COMPREPLY=( $(compgen -W "goland pycharm idea vscode vs webstorm phpstorm rubymine clion vim nano" -- ${cur}) )
+            COMPREPLY=( $(compgen -W "goland pycharm idea vscode vs webstorm phpstorm rubymine clion androidstudio datagrip rider rustrover appcode dataspell vim nano onivim helix kakoune ed sublime cursor vscode-insiders" -- ${cur}) )
             return 0
             ;;
         --format)
-            COMPREPLY=( $(compgen -W "json plain paths" -- ${cur}) )
+            COMPREPLY=( $(compgen -W "json ndjson csv plain paths name summary fzf keyvalue count-by-ext by-language by-language-json" -- ${cur}) )
+            return 0
+            ;;
+        --sort)
+            COMPREPLY=( $(compgen -W "default path name tab" -- ${cur}) )
+            return 0
+            ;;
+        --status-file|--export-to)
+            COMPREPLY=( $(compgen -f -- ${cur}) )
+            return 0
+            ;;
+        --ide-config-dir|--project-root)
+            COMPREPLY=( $(compgen -d -- ${cur}) )
+            return 0
+            ;;
+        --export)
+            COMPREPLY=( $(compgen -W "vscode jetbrains" -- ${cur}) )
+            return 0
+            ;;
+        --since)
+            COMPREPLY=( $(compgen -W "30m 1h 2h 1d" -- ${cur}) )
+            return 0
+            ;;
+        --interval)
+            COMPREPLY=( $(compgen -W "500ms 1s 5s 30s 1m" -- ${cur}) )
+            return 0
+            ;;
+        --track)
+            COMPREPLY=( $(compgen -W "30m 1h 4h 8h" -- ${cur}) )
+            return 0
+            ;;
+        --search-depth)
+            COMPREPLY=( $(compgen -W "1 2 3 4 5" -- ${cur}) )
+            return 0
+            ;;
+        --search-timeout)
+            COMPREPLY=( $(compgen -W "250ms 500ms 1s 2s" -- ${cur}) )
+            return 0
+            ;;
+        --cache)
+            COMPREPLY=( $(compgen -W "1s 2s 5s 30s" -- ${cur}) )
+            return 0
+            ;;
+        --debounce)
+            COMPREPLY=( $(compgen -W "0 150ms 300ms 1s" -- ${cur}) )
+            return 0
+            ;;
+        --user)
+            COMPREPLY=( $(compgen -u -- ${cur}) )
             return 0
             ;;
         --debug-process)
@@ -0,0 +1,84 @@
+use crate::types::{DetectionResult, SupportedIDE};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Where the last result for `ide` (None for auto-detect) is cached:
+/// `$XDG_CACHE_HOME/ide-files/<ide>-<key>.json`, falling back to `~/.cache`.
+/// `settings` describes every option that changes what detection finds (the
+/// user, config directories, `--raw`, ...), so that runs differing in any of
+/// them never read each other's results.
+pub fn cache_path(ide: Option<SupportedIDE>, settings: &str) -> Option<PathBuf> {
+    let cache_dir = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+    let name = ide.map_or("auto", |ide| ide.as_str());
+    let key = &blake3::hash(settings.as_bytes()).to_hex()[..16];
+    Some(cache_dir.join("ide-files").join(format!("{}-{}.json", name, key)))
+}
+
+/// The cached result if it was written within `max_age`. A missing, stale or
+/// unparsable cache (e.g. one truncated by a crash) is a miss, never an error.
+pub fn read(path: &Path, max_age: Duration) -> Option<DetectionResult> {
+    let age = fs::metadata(path).ok()?.modified().ok()?.elapsed().ok()?;
+    if age > max_age {
+        return None;
+    }
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+/// Replace the cache with `result`. Concurrent invocations (several
+/// status-bar widgets) may write at once, so the result goes to a temporary
+/// file in the same directory first and is renamed over the cache: readers
+/// see either the old file or the new one, never a partial write.
+pub fn write(path: &Path, result: &DetectionResult) -> io::Result<()> {
+    let dir = path.parent().ok_or_else(|| io::Error::other("cache path has no directory"))?;
+    fs::create_dir_all(dir)?;
+
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("cache");
+    let temp_path = dir.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+    let written = fs::File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(&serde_json::to_vec(result)?)?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp_path, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    written
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result() -> DetectionResult {
+        DetectionResult::for_test("Vim", vec![crate::types::FileInfo::for_test("/src/main.rs", true)])
+    }
+
+    #[test]
+    fn written_cache_is_read_back_and_truncated_cache_is_a_miss() {
+        let dir = std::env::temp_dir().join(format!("ide-files-cache-{}", std::process::id()));
+        let path = dir.join("ide-files").join("vim.json");
+
+        write(&path, &result()).unwrap();
+        let cached = read(&path, Duration::from_secs(60)).unwrap();
+        assert_eq!(cached.active_file.as_deref(), Some("/src/main.rs"));
+        // Only the cache itself is left behind, no temporary file
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+
+        // A writer killed mid-write leaves a prefix of the JSON
+        let json = fs::read(&path).unwrap();
+        fs::write(&path, &json[..json.len() / 2]).unwrap();
+        assert!(read(&path, Duration::from_secs(60)).is_none());
+
+        write(&path, &result()).unwrap();
+        assert!(read(&path, Duration::ZERO).is_none());
+        assert!(read(&dir.join("missing.json"), Duration::from_secs(60)).is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,4 +1,6 @@
-use crate::types::{ProcessInfo, SupportedIDE};
+use crate::types::{FileInfo, ProcessInfo, SupportedIDE};
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -11,8 +13,39 @@ pub enum DetectionError {
     SystemError { message: String },
     #[error("Unsupported IDE: {ide}")]
     UnsupportedIDE { ide: String },
+    #[error("{ide} started {age_secs}s ago and is still restoring its session; retry shortly")]
+    IDEStarting { ide: String, age_secs: u64 },
+    #[error("Invalid status file: {message}")]
+    InvalidStatusFile { message: String },
 }
 
+impl DetectionError {
+    /// A suggestion for fixing the error, shown after its message. Kept out
+    /// of `Display` so the messages themselves stay stable.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            DetectionError::NoProcessFound { .. } => Some(
+                "check the IDE is running; --list-ides shows the supported IDEs and --debug-processes what is running",
+            ),
+            DetectionError::UnsupportedIDE { .. } => {
+                Some("run --list-ides to see the supported IDEs")
+            }
+            DetectionError::SystemError { message } if message.contains("/proc") => Some(
+                "check /proc is mounted and readable by this user (hidepid mounts and containers can hide other processes)",
+            ),
+            DetectionError::WindowParseError { message }
+                if message.contains("vscdb") || message.contains("database") =>
+            {
+                Some("VSCode's state database could not be read; this VSCode version may be unsupported")
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Processes younger than this may not have written their session state yet
+const STARTUP_GRACE_SECS: u64 = 30;
+
 pub type DetectionResult<T> = Result<T, DetectionError>;
 
 /// IDE detection strategy trait
@@ -33,73 +66,703 @@ pub trait IDEDetector {
     fn display_name(&self) -> &'static str {
         self.ide_type().display_name()
     }
+
+    /// Files or directories the IDE rewrites when its open files change,
+    /// given the last result; watched instead of re-detecting on a timer
+    fn state_paths(&self, _result: &crate::types::DetectionResult) -> Vec<PathBuf> {
+        Vec::new()
+    }
 }
 
 /// IDE detector manager
 pub struct IDEDetectorManager {
     detectors: Vec<Box<dyn IDEDetector>>,
+    owner_uid: Option<u32>,
+    verbose: bool,
+    raw: bool,
+    focus_detection: bool,
+}
+
+impl Default for IDEDetectorManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl IDEDetectorManager {
     pub fn new() -> Self {
         Self {
             detectors: Vec::new(),
+            owner_uid: None,
+            verbose: false,
+            raw: false,
+            focus_detection: true,
         }
     }
 
+    /// Only consider processes owned by `uid` (None scans every user's)
+    pub fn with_owner_uid(mut self, uid: Option<u32>) -> Self {
+        self.owner_uid = uid;
+        self
+    }
+
+    /// Report files dropped from results on stderr
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Hand out results exactly as the detector produced them, for telling
+    /// detector bugs from post-processing ones: no blank-path filtering,
+    /// sorting or symlink resolution
+    pub fn with_raw(mut self, raw: bool) -> Self {
+        self.raw = raw;
+        self
+    }
+
+    /// Let `auto_detect` prefer the IDE owning the focused window over
+    /// registration order
+    pub fn with_focus_detection(mut self, enabled: bool) -> Self {
+        self.focus_detection = enabled;
+        self
+    }
+
+    /// Register a detector, replacing any already registered for the same
+    /// IDE. Registration order is kept: it is `auto_detect`'s priority.
     pub fn register_detector(&mut self, detector: Box<dyn IDEDetector>) {
-        self.detectors.push(detector);
+        let ide_type = detector.ide_type();
+        match self.detectors.iter_mut().find(|d| d.ide_type() == ide_type) {
+            Some(existing) => *existing = detector,
+            None => self.detectors.push(detector),
+        }
+    }
+
+    /// Keep only the detectors for `ides`, consulted in that order
+    pub fn restrict_to(&mut self, ides: &[SupportedIDE]) {
+        self.detectors.retain(|d| ides.contains(&d.ide_type()));
+        self.detectors
+            .sort_by_key(|d| ides.iter().position(|&ide| ide == d.ide_type()));
+    }
+
+    pub fn has_detector(&self, ide_type: SupportedIDE) -> bool {
+        self.detectors.iter().any(|d| d.ide_type() == ide_type)
     }
 
     pub fn detect_ide(
         &self,
         ide_type: SupportedIDE,
     ) -> DetectionResult<crate::types::DetectionResult> {
-        let detector = self
-            .detectors
-            .iter()
-            .find(|d| d.ide_type() == ide_type)
-            .ok_or_else(|| DetectionError::UnsupportedIDE {
+        if !self.has_detector(ide_type) {
+            return Err(DetectionError::UnsupportedIDE {
                 ide: ide_type.display_name().to_string(),
-            })?;
+            });
+        }
+        self.detect_from_processes(ide_type, &self.scan()?)
+    }
 
-        let processes = crate::process::find_all_processes()?;
-        let target_processes: Vec<_> = processes
-            .iter()
-            .filter(|p| detector.is_target_process(p))
-            .cloned()
-            .collect();
+    pub fn auto_detect(&self) -> DetectionResult<crate::types::DetectionResult> {
+        self.detect_in(&self.scan()?, None)
+    }
+
+    /// Every IDE with running processes, in detector order, each detected
+    /// over one shared scan. IDEs whose detector fails (no files found,
+    /// still starting up) are left out.
+    pub fn detect_all(&self) -> DetectionResult<Vec<crate::types::DetectionResult>> {
+        Ok(self.detect_all_in(&self.scan()?))
+    }
 
-        if target_processes.is_empty() {
-            return Err(DetectionError::NoProcessFound {
-                ide: detector.display_name().to_string(),
+    /// `detect_ide` over a process list the caller already has (e.g. from its
+    /// own monitoring), without scanning the system. Processes `ide_type`'s
+    /// detector doesn't claim are ignored.
+    pub fn detect_from_processes(
+        &self,
+        ide_type: SupportedIDE,
+        processes: &[ProcessInfo],
+    ) -> DetectionResult<crate::types::DetectionResult> {
+        if !self.has_detector(ide_type) {
+            return Err(DetectionError::UnsupportedIDE {
+                ide: ide_type.display_name().to_string(),
             });
         }
-
-        detector.extract_files(&target_processes)
+        self.detect_in(processes, Some(ide_type))
     }
 
-    pub fn auto_detect(&self) -> DetectionResult<crate::types::DetectionResult> {
-        let processes = crate::process::find_all_processes()?;
+    /// Run every detector with matching processes, sending each file over
+    /// `sender` as soon as its detector produces it. Stops early if the
+    /// receiver is dropped.
+    pub fn stream_files(&self, sender: Sender<FileInfo>) -> DetectionResult<()> {
+        self.stream_files_in(&self.scan()?, sender);
+        Ok(())
+    }
 
+    /// `stream_files` over an existing scan
+    pub(crate) fn stream_files_in(&self, processes: &[ProcessInfo], sender: Sender<FileInfo>) {
         for detector in &self.detectors {
-            let target_processes: Vec<_> = processes
-                .iter()
-                .filter(|p| detector.is_target_process(p))
-                .cloned()
-                .collect();
+            let target_processes = targets(detector.as_ref(), processes);
+            if target_processes.is_empty() {
+                continue;
+            }
 
-            if !target_processes.is_empty() {
-                return detector.extract_files(&target_processes);
+            if let Ok(result) = detector.extract_files(&target_processes) {
+                for file in result.into_files() {
+                    if sender.send(file).is_err() {
+                        return;
+                    }
+                }
             }
         }
+    }
+
+    /// Walk the process table once. On Linux this reads all of /proc and
+    /// the windows of every process some detector claims, so a single scan
+    /// is shared by all the detectors one call consults.
+    pub(crate) fn scan(&self) -> DetectionResult<Vec<ProcessInfo>> {
+        crate::process::find_all_processes(self.owner_uid, |process| {
+            self.detectors.iter().any(|d| d.is_target_process(process))
+        })
+    }
+
+    /// `detect_ide` (or `auto_detect` for None) over an existing scan
+    pub(crate) fn detect_in(
+        &self,
+        processes: &[ProcessInfo],
+        ide_type: Option<SupportedIDE>,
+    ) -> DetectionResult<crate::types::DetectionResult> {
+        let focused_pid = match ide_type {
+            None if self.focus_detection => crate::process::get_focused_window_pid(),
+            _ => None,
+        };
+        self.detect_focused_in(processes, ide_type, focused_pid)
+    }
+
+    /// `detect_in`, trying first the IDE whose process owns the window of
+    /// `focused_pid` (directly or, for terminal editors, as an ancestor)
+    fn detect_focused_in(
+        &self,
+        processes: &[ProcessInfo],
+        ide_type: Option<SupportedIDE>,
+        focused_pid: Option<u32>,
+    ) -> DetectionResult<crate::types::DetectionResult> {
+        let mut candidates: Vec<_> = self
+            .detectors
+            .iter()
+            .filter(|d| ide_type.is_none() || ide_type == Some(d.ide_type()))
+            .map(|d| (d, targets(d.as_ref(), processes)))
+            .filter(|(_, target_processes)| !target_processes.is_empty())
+            .collect();
+        if let Some(focused) = focused_pid {
+            // Stable sort: the focused IDE first, the rest in priority order.
+            // Cached so each candidate's ancestors are read from /proc once.
+            candidates.sort_by_cached_key(|(_, target_processes)| {
+                !target_processes
+                    .iter()
+                    .any(|p| p.pid == focused || crate::process::get_ancestor_pids(p.pid).contains(&focused))
+            });
+        }
+
+        if let Some((detector, target_processes)) = candidates.first() {
+            return extract_with_startup_check(detector.as_ref(), target_processes)
+                .map(|result| self.finish(result));
+        }
 
         Err(DetectionError::NoProcessFound {
-            ide: "any supported IDE".to_string(),
+            ide: match ide_type {
+                Some(ide) => ide.display_name().to_string(),
+                None => "any supported IDE".to_string(),
+            },
         })
     }
 
+    /// `detect_all` over an existing scan
+    pub(crate) fn detect_all_in(&self, processes: &[ProcessInfo]) -> Vec<crate::types::DetectionResult> {
+        self.detectors
+            .iter()
+            .filter_map(|detector| {
+                let target_processes = targets(detector.as_ref(), processes);
+                if target_processes.is_empty() {
+                    return None;
+                }
+                extract_with_startup_check(detector.as_ref(), &target_processes)
+                    .ok()
+                    .map(|result| self.finish(result))
+            })
+            .collect()
+    }
+
+    /// State paths of the detector that produced `result`
+    pub fn state_paths(&self, result: &crate::types::DetectionResult) -> Vec<PathBuf> {
+        self.detectors
+            .iter()
+            .find(|d| d.display_name() == result.ide_name)
+            .map(|d| d.state_paths(result))
+            .unwrap_or_default()
+    }
+
+    /// PIDs of the running processes targeted by `ide_type`'s detector (or by
+    /// any detector), used to notice IDEs starting or exiting
+    pub fn target_pids(&self, ide_type: Option<SupportedIDE>) -> Vec<u32> {
+        self.scan()
+            .map(|processes| self.target_pids_in(&processes, ide_type))
+            .unwrap_or_default()
+    }
+
+    /// `target_pids` over an existing scan
+    pub(crate) fn target_pids_in(&self, processes: &[ProcessInfo], ide_type: Option<SupportedIDE>) -> Vec<u32> {
+        let mut pids: Vec<u32> = processes
+            .iter()
+            .filter(|p| !p.status.is_stale())
+            .filter(|p| {
+                self.detectors
+                    .iter()
+                    .filter(|d| ide_type.is_none() || ide_type == Some(d.ide_type()))
+                    .any(|d| d.is_target_process(p))
+            })
+            .map(|p| p.pid)
+            .collect();
+        pids.sort_unstable();
+        pids
+    }
+
     pub fn list_supported_ides(&self) -> Vec<&'static str> {
         self.detectors.iter().map(|d| d.display_name()).collect()
     }
+
+    /// Drop files without a usable path, apply the default `open_files`
+    /// ordering and resolve symlinked files before handing a result out
+    /// (unless raw)
+    fn finish(&self, mut result: crate::types::DetectionResult) -> crate::types::DetectionResult {
+        if self.raw {
+            return result;
+        }
+
+        // A malformed title or an empty fsPath would otherwise reach Path
+        // operations and the output as a blank entry
+        result.open_files.retain(|file| {
+            let keep = !file.path.trim().is_empty();
+            if !keep && self.verbose {
+                eprintln!("Dropping {} file with an empty path (name {:?})", result.ide_name, file.name);
+            }
+            keep
+        });
+        if result.active_file.as_deref().is_some_and(|path| path.trim().is_empty()) {
+            result.active_file = None;
+        }
+
+        result.sort_files_default();
+        for file in &mut result.open_files {
+            file.real_path = FileInfo::symlink_target(&file.path);
+        }
+        result
+    }
+}
+
+/// The live processes in `processes` that `detector` claims
+fn targets(detector: &dyn IDEDetector, processes: &[ProcessInfo]) -> Vec<ProcessInfo> {
+    processes
+        .iter()
+        .filter(|p| !p.status.is_stale() && detector.is_target_process(p))
+        .cloned()
+        .collect()
+}
+
+/// Run a detector, turning "no files found" into `IDEStarting` when the IDE
+/// process was only just launched and likely hasn't restored its session yet
+fn extract_with_startup_check(
+    detector: &dyn IDEDetector,
+    processes: &[ProcessInfo],
+) -> DetectionResult<crate::types::DetectionResult> {
+    match detector.extract_files(processes) {
+        Err(DetectionError::WindowParseError { message }) => {
+            let youngest = processes.iter().filter_map(|p| p.age_secs()).min();
+            match youngest {
+                Some(age_secs) if age_secs < STARTUP_GRACE_SECS => Err(DetectionError::IDEStarting {
+                    ide: detector.display_name().to_string(),
+                    age_secs,
+                }),
+                _ => Err(DetectionError::WindowParseError { message }),
+            }
+        }
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detectors::onivim::OnivimDetector;
+    use crate::types::ProcessStatus;
+
+    #[test]
+    fn errors_carry_a_hint_only_where_one_helps() {
+        let text = |text: &str| text.to_string();
+        let cases = [
+            (
+                DetectionError::NoProcessFound { ide: text("GoLand") },
+                Some("check the IDE is running; --list-ides shows the supported IDEs and --debug-processes what is running"),
+            ),
+            (
+                DetectionError::UnsupportedIDE { ide: text("Emacs") },
+                Some("run --list-ides to see the supported IDEs"),
+            ),
+            (
+                DetectionError::SystemError { message: text("cannot read /proc: permission denied") },
+                Some("check /proc is mounted and readable by this user (hidepid mounts and containers can hide other processes)"),
+            ),
+            (DetectionError::SystemError { message: text("cannot serialize result as JSON") }, None),
+            (
+                DetectionError::WindowParseError { message: text("cannot open state.vscdb: locked") },
+                Some("VSCode's state database could not be read; this VSCode version may be unsupported"),
+            ),
+            (
+                DetectionError::WindowParseError { message: text("unexpected database schema") },
+                Some("VSCode's state database could not be read; this VSCode version may be unsupported"),
+            ),
+            (DetectionError::WindowParseError { message: text("No files detected for Vim") }, None),
+            (DetectionError::IDEStarting { ide: text("GoLand"), age_secs: 3 }, None),
+            (DetectionError::InvalidStatusFile { message: text("empty") }, None),
+        ];
+
+        for (error, hint) in cases {
+            assert_eq!(error.hint(), hint, "{}", error);
+        }
+    }
+
+    #[test]
+    fn registering_an_ide_twice_replaces_its_detector() {
+        let mut manager = IDEDetectorManager::new();
+        assert!(!manager.has_detector(SupportedIDE::Onivim));
+
+        manager.register_detector(Box::new(OnivimDetector::new()));
+        manager.register_detector(Box::new(OnivimDetector::new()));
+
+        assert!(manager.has_detector(SupportedIDE::Onivim));
+        assert_eq!(manager.list_supported_ides(), vec!["Onivim 2"]);
+    }
+
+    #[test]
+    fn restricting_keeps_only_the_listed_ides_in_their_order() {
+        use crate::detectors::sublime::SublimeDetector;
+        use crate::detectors::terminal::TerminalEditorDetector;
+
+        let mut manager = IDEDetectorManager::new();
+        manager.register_detector(Box::new(TerminalEditorDetector::new(SupportedIDE::Vim)));
+        manager.register_detector(Box::new(OnivimDetector::new()));
+        manager.register_detector(Box::new(TerminalEditorDetector::new(SupportedIDE::Nano)));
+        manager.register_detector(Box::new(SublimeDetector::new()));
+
+        manager.restrict_to(&[SupportedIDE::Sublime, SupportedIDE::GoLand, SupportedIDE::Vim]);
+
+        assert_eq!(manager.list_supported_ides(), vec!["Sublime Text", "Vim"]);
+        assert!(!manager.has_detector(SupportedIDE::Onivim));
+    }
+
+    /// Claims processes named `process_name` and reports one file per process
+    struct StubDetector {
+        ide: SupportedIDE,
+        process_name: &'static str,
+    }
+
+    impl IDEDetector for StubDetector {
+        fn ide_type(&self) -> SupportedIDE {
+            self.ide
+        }
+
+        fn is_target_process(&self, process: &ProcessInfo) -> bool {
+            process.name == self.process_name
+        }
+
+        fn extract_files(&self, processes: &[ProcessInfo]) -> DetectionResult<crate::types::DetectionResult> {
+            let files = processes
+                .iter()
+                .map(|p| FileInfo::for_test(&format!("/{}/{}.txt", self.process_name, p.pid), false))
+                .collect();
+            Ok(crate::types::DetectionResult::for_test(self.display_name(), files))
+        }
+    }
+
+    #[test]
+    fn detection_runs_over_a_shared_scan() {
+        let mut manager = IDEDetectorManager::new();
+        manager.register_detector(Box::new(StubDetector { ide: SupportedIDE::Vim, process_name: "vim" }));
+        manager.register_detector(Box::new(StubDetector { ide: SupportedIDE::Nano, process_name: "nano" }));
+        let processes: Vec<ProcessInfo> = [(10, "nano"), (20, "bash"), (30, "nano")]
+            .into_iter()
+            .map(|(pid, name)| ProcessInfo {
+                pid,
+                ..ProcessInfo::for_test(name, "")
+            })
+            .collect();
+
+        // Vim comes first but has no processes, so auto-detection moves on to Nano
+        let auto = manager.detect_in(&processes, None).unwrap();
+        assert_eq!(auto.ide_name, "Nano");
+        let paths: Vec<&str> = auto.open_files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["/nano/10.txt", "/nano/30.txt"]);
+
+        assert!(matches!(
+            manager.detect_in(&processes, Some(SupportedIDE::Vim)),
+            Err(DetectionError::NoProcessFound { ide }) if ide == "Vim"
+        ));
+        assert_eq!(manager.target_pids_in(&processes, None), [10, 30]);
+        assert_eq!(manager.target_pids_in(&processes, Some(SupportedIDE::Vim)), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn zombie_and_stopped_processes_are_not_detected() {
+        let mut manager = IDEDetectorManager::new();
+        manager.register_detector(Box::new(StubDetector { ide: SupportedIDE::Vim, process_name: "vim" }));
+        manager.register_detector(Box::new(StubDetector { ide: SupportedIDE::Nano, process_name: "nano" }));
+        let processes: Vec<ProcessInfo> = [
+            (10, "vim", ProcessStatus::Zombie),
+            (20, "nano", ProcessStatus::Stopped),
+            (30, "nano", ProcessStatus::Sleeping),
+        ]
+        .into_iter()
+        .map(|(pid, name, status)| ProcessInfo {
+            pid,
+            status,
+            ..ProcessInfo::for_test(name, "")
+        })
+        .collect();
+
+        // The zombie vim doesn't count as running, so Nano is detected instead
+        let auto = manager.detect_focused_in(&processes, None, None).unwrap();
+        assert_eq!(auto.ide_name, "Nano");
+        let paths: Vec<&str> = auto.open_files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["/nano/30.txt"]);
+        assert!(matches!(
+            manager.detect_in(&processes, Some(SupportedIDE::Vim)),
+            Err(DetectionError::NoProcessFound { .. })
+        ));
+    }
+
+    /// A detector that finds no files, like a JetBrains IDE whose window
+    /// has no title yet
+    struct EmptyDetector;
+
+    impl IDEDetector for EmptyDetector {
+        fn ide_type(&self) -> SupportedIDE {
+            SupportedIDE::GoLand
+        }
+
+        fn is_target_process(&self, process: &ProcessInfo) -> bool {
+            process.name == "goland"
+        }
+
+        fn extract_files(&self, _processes: &[ProcessInfo]) -> DetectionResult<crate::types::DetectionResult> {
+            Err(DetectionError::WindowParseError {
+                message: "No files detected for GoLand".to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn freshly_started_ide_is_reported_as_starting() {
+        let mut manager = IDEDetectorManager::new();
+        manager.register_detector(Box::new(EmptyDetector));
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let started = |secs_ago| {
+            vec![ProcessInfo {
+                start_time: Some(now - secs_ago),
+                ..ProcessInfo::for_test("goland", "")
+            }]
+        };
+
+        let result = manager.detect_in(&started(5), Some(SupportedIDE::GoLand));
+        assert!(
+            matches!(&result, Err(DetectionError::IDEStarting { ide, age_secs }) if ide == "GoLand" && *age_secs < STARTUP_GRACE_SECS),
+            "{:?}",
+            result
+        );
+        // Past the grace period the parse error stands
+        assert!(matches!(
+            manager.detect_in(&started(STARTUP_GRACE_SECS + 60), Some(SupportedIDE::GoLand)),
+            Err(DetectionError::WindowParseError { .. })
+        ));
+    }
+
+    #[test]
+    fn auto_detect_prefers_the_ide_owning_the_focused_window() {
+        let mut manager = IDEDetectorManager::new();
+        manager.register_detector(Box::new(StubDetector { ide: SupportedIDE::Vim, process_name: "vim" }));
+        manager.register_detector(Box::new(StubDetector { ide: SupportedIDE::Nano, process_name: "nano" }));
+        let processes: Vec<ProcessInfo> = [(4_000_001, "vim"), (4_000_002, "nano")]
+            .into_iter()
+            .map(|(pid, name)| ProcessInfo {
+                pid,
+                ..ProcessInfo::for_test(name, "")
+            })
+            .collect();
+
+        let detected = |focused_pid| manager.detect_focused_in(&processes, None, focused_pid).unwrap().ide_name;
+        assert_eq!(detected(Some(4_000_002)), "Nano");
+        // Unknown focus keeps registration order
+        assert_eq!(detected(Some(4_000_003)), "Vim");
+        assert_eq!(detected(None), "Vim");
+    }
+
+    #[test]
+    fn every_running_ide_is_detected_from_one_scan() {
+        let mut manager = IDEDetectorManager::new();
+        for (ide, process_name) in [
+            (SupportedIDE::Vim, "vim"),
+            (SupportedIDE::Helix, "hx"),
+            (SupportedIDE::Nano, "nano"),
+        ] {
+            manager.register_detector(Box::new(StubDetector { ide, process_name }));
+        }
+        let processes: Vec<ProcessInfo> = [(10, "nano"), (20, "vim"), (30, "bash")]
+            .into_iter()
+            .map(|(pid, name)| ProcessInfo {
+                pid,
+                ..ProcessInfo::for_test(name, "")
+            })
+            .collect();
+
+        // Helix isn't running; the others come in registration order
+        let results = manager.detect_all_in(&processes);
+        let found: Vec<(&str, &str)> = results
+            .iter()
+            .map(|r| (r.ide_name.as_str(), r.open_files[0].path.as_str()))
+            .collect();
+        assert_eq!(found, [("Vim", "/vim/20.txt"), ("Nano", "/nano/10.txt")]);
+    }
+
+    #[test]
+    fn files_are_streamed_as_each_detector_produces_them() {
+        let mut manager = IDEDetectorManager::new();
+        manager.register_detector(Box::new(StubDetector { ide: SupportedIDE::Vim, process_name: "vim" }));
+        manager.register_detector(Box::new(StubDetector { ide: SupportedIDE::Nano, process_name: "nano" }));
+        let processes: Vec<ProcessInfo> = [(10, "nano"), (20, "vim"), (30, "nano")]
+            .into_iter()
+            .map(|(pid, name)| ProcessInfo {
+                pid,
+                ..ProcessInfo::for_test(name, "")
+            })
+            .collect();
+        let paths = |files: Vec<FileInfo>| files.into_iter().map(|f| f.path).collect::<Vec<_>>();
+
+        // The iterator yields exactly the vector's files, in order
+        let result = manager.detect_in(&processes, Some(SupportedIDE::Nano)).unwrap();
+        let expected = paths(result.open_files.clone());
+        assert_eq!(paths(result.into_files().collect()), expected);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        manager.stream_files_in(&processes, sender);
+        let streamed = paths(receiver.iter().collect());
+        let collected: Vec<String> = manager
+            .detect_all_in(&processes)
+            .into_iter()
+            .flat_map(|r| paths(r.open_files))
+            .collect();
+        assert_eq!(streamed, ["/vim/20.txt", "/nano/10.txt", "/nano/30.txt"]);
+        assert_eq!(streamed, collected);
+
+        // A dropped receiver ends the stream instead of failing it
+        let (sender, receiver) = std::sync::mpsc::channel();
+        drop(receiver);
+        manager.stream_files_in(&processes, sender);
+    }
+
+    #[test]
+    fn detection_runs_over_hand_built_processes() {
+        let home = std::env::temp_dir().join(format!("ide-files-embed-{}", std::process::id()));
+        let local = home.join(".config/sublime-text/Local");
+        std::fs::create_dir_all(&local).unwrap();
+        let cargo_toml = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        let session = serde_json::json!({
+            "windows": [{"buffers": [{"file": cargo_toml}], "groups": [{"selected": 0, "sheets": [{"buffer": 0}]}]}]
+        });
+        std::fs::write(local.join("Session.sublime_session"), session.to_string()).unwrap();
+
+        let mut manager = IDEDetectorManager::new();
+        manager.register_detector(Box::new(
+            crate::detectors::sublime::SublimeDetector::new().with_home_dir(Some(home.to_string_lossy().into_owned())),
+        ));
+        let processes = [
+            ProcessInfo::new(100, "bash", "/usr/bin/bash"),
+            ProcessInfo::new(200, "sublime_text", "/opt/sublime_text/sublime_text"),
+        ];
+        let result = manager.detect_from_processes(SupportedIDE::Sublime, &processes);
+        std::fs::remove_dir_all(&home).unwrap();
+
+        assert_eq!(result.unwrap().active_file.as_deref(), Some(cargo_toml));
+        assert!(matches!(
+            manager.detect_from_processes(SupportedIDE::Sublime, &processes[..1]),
+            Err(DetectionError::NoProcessFound { .. })
+        ));
+        assert!(matches!(
+            manager.detect_from_processes(SupportedIDE::Vim, &processes),
+            Err(DetectionError::UnsupportedIDE { .. })
+        ));
+    }
+
+    #[test]
+    fn files_with_blank_paths_are_dropped() {
+        let finished = IDEDetectorManager::new().finish(crate::types::DetectionResult::for_test(
+            "Vim",
+            vec![
+                FileInfo::for_test("/src/main.rs", false),
+                FileInfo::for_test("", false),
+                FileInfo::for_test(" \t", true),
+            ],
+        ));
+
+        let paths: Vec<&str> = finished.open_files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["/src/main.rs"]);
+        assert_eq!(finished.active_file, None);
+    }
+
+    #[test]
+    fn raw_results_skip_normalization() {
+        let detected = || {
+            crate::types::DetectionResult::for_test(
+                "Vim",
+                vec![
+                    FileInfo::for_test("/src/types.rs", false),
+                    FileInfo::for_test("", false),
+                    FileInfo::for_test("/src/main.rs", true),
+                ],
+            )
+        };
+        let paths = |result: &crate::types::DetectionResult| -> Vec<String> {
+            result.open_files.iter().map(|f| f.path.clone()).collect()
+        };
+
+        let normalized = IDEDetectorManager::new().finish(detected());
+        let raw = IDEDetectorManager::new().with_raw(true).finish(detected());
+
+        assert_eq!(paths(&normalized), ["/src/main.rs", "/src/types.rs"]);
+        assert_eq!(paths(&raw), ["/src/types.rs", "", "/src/main.rs"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_files_keep_their_path_and_gain_a_real_path() {
+        let dir = std::env::temp_dir().join(format!("ide-files-symlink-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("dotfiles")).unwrap();
+        let dir = dir.canonicalize().unwrap();
+        let target = dir.join("dotfiles/bashrc");
+        let link = dir.join(".bashrc");
+        std::fs::write(&target, "").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let (link, target) = (link.to_str().unwrap(), target.to_str().unwrap());
+        let finished = IDEDetectorManager::new().finish(crate::types::DetectionResult::for_test(
+            "Vim",
+            vec![FileInfo::for_test(link, false), FileInfo::for_test(target, false)],
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let paths: Vec<(&str, Option<&str>)> = finished
+            .open_files
+            .iter()
+            .map(|f| (f.path.as_str(), f.real_path.as_deref()))
+            .collect();
+        assert_eq!(paths, [(link, Some(target)), (target, None)]);
+    }
 }
@@ -1,48 +1,151 @@
 use crate::detector::{DetectionResult, IDEDetector};
-use crate::types::{FileInfo, ProcessInfo, SupportedIDE};
+use crate::types::{FileInfo, FileSource, ProcessInfo, SupportedIDE, TabKind};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
 use regex::Regex;
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Filesystem types whose directories can be slow to stat (network or FUSE remotes)
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs", "nfs4", "cifs", "smbfs", "smb3", "fuse.sshfs", "sshfs", "9p", "afs", "ceph",
+    "glusterfs", "fuse.glusterfs", "fuse.rclone", "davfs", "fuse.davfs2", "lustre",
+];
+
+/// Parse `/proc/self/mountinfo` content into the mount points of network filesystems
+fn parse_network_mounts(mountinfo: &str) -> Vec<PathBuf> {
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            // "<id> <parent> <maj:min> <root> <mount point> <opts> [optional...] - <fstype> <source> <super opts>"
+            let (mount_fields, fs_fields) = line.split_once(" - ")?;
+            let mount_point = mount_fields.split_whitespace().nth(4)?;
+            let fs_type = fs_fields.split_whitespace().next()?;
+
+            if !NETWORK_FS_TYPES.contains(&fs_type) || mount_point == "/" {
+                return None;
+            }
+
+            // mountinfo escapes space, tab, newline and backslash as octal
+            let mount_point = mount_point
+                .replace("\\040", " ")
+                .replace("\\011", "\t")
+                .replace("\\012", "\n")
+                .replace("\\134", "\\");
+            Some(PathBuf::from(mount_point))
+        })
+        .collect()
+}
+
+/// Where the kernel lists this process's mounts
+const MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+
+/// Get the mount points of network filesystems listed in `mountinfo_path`
+fn get_network_mounts(mountinfo_path: &Path) -> Vec<PathBuf> {
+    fs::read_to_string(mountinfo_path)
+        .map(|content| parse_network_mounts(&content))
+        .unwrap_or_default()
+}
+
+/// How many directory levels below each search path the project search descends
+pub const DEFAULT_SEARCH_DEPTH: u32 = 3;
 
 /// JetBrains IDE base detector
 pub struct JetBrainsDetector {
     ide_type: SupportedIDE,
-    process_names: Vec<&'static str>,
+    search_network_fs: bool,
+    mountinfo_path: PathBuf,
+    follow_symlinks: bool,
+    home_dir: Option<String>,
+    project_roots: Vec<String>,
+    search_depth: u32,
+    search_timeout: Option<Duration>,
 }
 
 impl JetBrainsDetector {
     pub fn new(ide_type: SupportedIDE) -> Self {
-        let process_names = match ide_type {
-            SupportedIDE::GoLand => vec!["goland", "goland.exe", "goland64.exe"],
-            SupportedIDE::PyCharm => vec!["pycharm", "pycharm.exe", "pycharm64.exe"],
-            SupportedIDE::IntelliJIDEA => vec!["idea", "idea.exe", "idea64.exe"],
-            SupportedIDE::WebStorm => vec!["webstorm", "webstorm.exe", "webstorm64.exe"],
-            SupportedIDE::PhpStorm => vec!["phpstorm", "phpstorm.exe", "phpstorm64.exe"],
-            SupportedIDE::RubyMine => vec!["rubymine", "rubymine.exe", "rubymine64.exe"],
-            SupportedIDE::CLion => vec!["clion", "clion.exe", "clion64.exe"],
-            _ => vec![],
-        };
-
         Self {
             ide_type,
-            process_names,
+            search_network_fs: false,
+            mountinfo_path: PathBuf::from(MOUNTINFO_PATH),
+            follow_symlinks: false,
+            home_dir: None,
+            project_roots: Vec::new(),
+            search_depth: DEFAULT_SEARCH_DEPTH,
+            search_timeout: None,
         }
     }
 
+    /// Allow the recursive project search to descend into network filesystems
+    pub fn with_network_fs_search(mut self, enabled: bool) -> Self {
+        self.search_network_fs = enabled;
+        self
+    }
+
+    /// Read the mount table from another file instead of `/proc/self/mountinfo`
+    #[cfg(test)]
+    fn with_mountinfo_path(mut self, path: PathBuf) -> Self {
+        self.mountinfo_path = path;
+        self
+    }
+
+    /// Let the recursive project search descend into symlinked directories
+    pub fn with_symlink_following(mut self, enabled: bool) -> Self {
+        self.follow_symlinks = enabled;
+        self
+    }
+
+    /// Search for projects under another user's home directory (None keeps `$HOME`)
+    pub fn with_home_dir(mut self, home_dir: Option<String>) -> Self {
+        self.home_dir = home_dir;
+        self
+    }
+
+    /// Search these directories for projects before the usual ones under the home directory
+    pub fn with_project_roots(mut self, roots: Vec<String>) -> Self {
+        self.project_roots = roots;
+        self
+    }
+
+    /// Limit how many directory levels the recursive project search descends
+    pub fn with_search_depth(mut self, depth: u32) -> Self {
+        self.search_depth = depth;
+        self
+    }
+
+    /// Give up the recursive project search after this long (None searches until done)
+    pub fn with_search_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.search_timeout = timeout;
+        self
+    }
+
     fn parse_jetbrains_window_title(&self, title: &str) -> Option<(FileInfo, Option<String>)> {
         // JetBrains IDE window title formats:
         // "filename.ext - project-name [/path/to/project] - IDE-Name 202X.X"
         // "filename.ext* - project-name [/path/to/project] - IDE-Name 202X.X" (modified)
         // "project-name - IDE-Name 202X.X" (no file open)
         // "filename.ext - project-name - IDE-Name 202X.X" (no project path)
+        // "/abs/path/notes.md - IDE-Name 202X.X" (LightEdit / single file)
+        // Some builds and window managers separate the parts with an en or em
+        // dash instead of " - ".
 
+        if let Some(parsed) = self.parse_absolute_path_title(title) {
+            return Some(parsed);
+        }
+
+        // The parts are split at spaced dashes only, so hyphenated file and
+        // project names ("my-component-test.tsx") stay whole. Patterns 0 and 1
+        // must end in " - IDE-Name 202X.X", so the simpler PyCharm 2025.1
+        // "project – filename.ext" title is left for pattern 2.
         let patterns = [
             // Full format with project path
-            r"^([^-]+?)\s*(\*)?\s*-\s*([^[]+?)\s*\[([^\]]+)\]\s*-\s*(\w+(?:\s+\w+)*)\s+([\d.]+)",
+            r"^(.+?)\s*(\*)?\s+[-–—]\s+([^\[]+?)\s*\[([^\]]+)\]\s+[-–—]\s+(\w+(?:\s+\w+)*)\s+(\d+(?:\.\d+)+)\s*$",
             // Format without project path
-            r"^([^-]+?)\s*(\*)?\s*-\s*([^-]+?)\s*-\s*(\w+(?:\s+\w+)*)\s+([\d.]+)",
+            r"^(.+?)\s*(\*)?\s+[-–—]\s+(.+?)\s+[-–—]\s+(\w+(?:\s+\w+)*)\s+(\d+(?:\.\d+)+)\s*$",
             // Simple format: "project – filename.ext" (PyCharm 2025.1)
-            r"^([^–]+?)\s*–\s*(.+)$",
+            r"^(.+?)\s+[–—]\s+(.+)$",
             // Project only (no file)
             r"^([^-]+?)\s*-\s*(\w+(?:\s+\w+)*)\s+([\d.]+)",
         ];
@@ -66,12 +169,11 @@ impl JetBrainsDetector {
                                 };
 
                                 return Some((FileInfo {
-                                    path: full_path,
                                     name: filename.to_string(),
                                     is_active: true,
                                     is_modified,
-                                    tab_index: None,
                                     project_name: Some(project_name.to_string()),
+                                    ..FileInfo::new(&full_path, FileSource::WindowTitle)
                                 }, project_path));
                             }
                         }
@@ -91,12 +193,11 @@ impl JetBrainsDetector {
                                 };
                                 
                                 return Some((FileInfo {
-                                    path: full_path,
                                     name: filename.to_string(),
                                     is_active: true,
                                     is_modified,
-                                    tab_index: None,
                                     project_name: Some(project_name.to_string()),
+                                    ..FileInfo::new(&full_path, FileSource::WindowTitle)
                                 }, project_path));
                             }
                         }
@@ -104,6 +205,10 @@ impl JetBrainsDetector {
                             // Simple format: "project – filename.ext"
                             let project_name = captures.get(1)?.as_str().trim();
                             let filename = captures.get(2)?.as_str().trim();
+
+                            if is_absolute_title_path(filename) {
+                                return Some((self.title_file_info(filename, false), None));
+                            }
                             
                             if !filename.is_empty() {
                                 // Try to find project path
@@ -115,12 +220,10 @@ impl JetBrainsDetector {
                                 };
                                 
                                 return Some((FileInfo {
-                                    path: full_path,
                                     name: filename.to_string(),
                                     is_active: true,
-                                    is_modified: false,
-                                    tab_index: None,
                                     project_name: Some(project_name.to_string()),
+                                    ..FileInfo::new(&full_path, FileSource::WindowTitle)
                                 }, project_path));
                             }
                         }
@@ -137,63 +240,33 @@ impl JetBrainsDetector {
         None
     }
 
-    /// Get process command line to find additional information
-    #[cfg(any(target_os = "linux", target_os = "macos"))]
-    fn get_process_cmdline(&self, pid: u32) -> Option<Vec<String>> {
-        #[cfg(target_os = "linux")]
-        {
-            let cmdline_path = format!("/proc/{}/cmdline", pid);
-            std::fs::read_to_string(&cmdline_path).ok().map(|content| {
-                content
-                    .split('\0')
-                    .filter(|s| !s.is_empty())
-                    .map(|s| s.to_string())
-                    .collect()
-            })
-        }
+    /// Titles whose file part is already an absolute path, as LightEdit and
+    /// scratch windows show it: the path is used verbatim, with no project
+    fn parse_absolute_path_title(&self, title: &str) -> Option<(FileInfo, Option<String>)> {
+        let regex = Regex::new(r"^(.+?)\s+[-–—]\s+\w+(?:\s+\w+)*\s+\d+(?:\.\d+)+\s*$").ok()?;
+        let captures = regex.captures(title)?;
+        let separator = Regex::new(r"\s+[-–—]\s+").ok()?;
+        let file_part = separator.split(captures.get(1)?.as_str()).next()?.trim();
+        let (path, is_modified) = match file_part.strip_suffix('*') {
+            Some(path) => (path.trim_end(), true),
+            None => (file_part, false),
+        };
 
-        #[cfg(target_os = "macos")]
-        {
-            let output = std::process::Command::new("ps")
-                .args(&["-p", &pid.to_string(), "-o", "args="])
-                .output()
-                .ok()?;
-
-            let cmdline = String::from_utf8_lossy(&output.stdout);
-            Some(
-                cmdline
-                    .trim()
-                    .split_whitespace()
-                    .map(|s| s.to_string())
-                    .collect(),
-            )
+        if !is_absolute_title_path(path) {
+            return None;
         }
+        Some((self.title_file_info(path, is_modified), None))
     }
 
-    /// Windows process command line retrieval
-    #[cfg(target_os = "windows")]
-    fn get_process_cmdline(&self, pid: u32) -> Option<Vec<String>> {
-        let output = std::process::Command::new("wmic")
-            .args(&[
-                "process",
-                "where",
-                &format!("ProcessId={}", pid),
-                "get",
-                "CommandLine",
-                "/value",
-            ])
-            .output()
-            .ok()?;
-
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        for line in output_str.lines() {
-            if line.starts_with("CommandLine=") {
-                let cmdline = line.trim_start_matches("CommandLine=");
-                return Some(shell_words::split(cmdline).unwrap_or_default());
-            }
+    /// The active file named by a title by its absolute path
+    fn title_file_info(&self, path: &str, is_modified: bool) -> FileInfo {
+        let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+        FileInfo {
+            name: name.to_string(),
+            is_active: true,
+            is_modified,
+            ..FileInfo::new(path, FileSource::WindowTitle)
         }
-
-        None
     }
 
     /// Extract project path from command line arguments
@@ -213,73 +286,133 @@ impl JetBrainsDetector {
         None
     }
 
+    /// Where to look for projects, in order: the configured project roots,
+    /// then common code directories under the home directory and the home
+    /// directory itself
+    fn project_search_paths(&self) -> Vec<PathBuf> {
+        let mut search_paths: Vec<PathBuf> = self.project_roots.iter().map(PathBuf::from).collect();
+        let home_dir = self.home_dir.clone().or_else(|| std::env::var("HOME").ok());
+        if let Some(home_dir) = home_dir.filter(|home| !home.is_empty()) {
+            let home = Path::new(&home_dir);
+            for dir in ["codes", "projects", "workspace", "dev", "Documents", "Dropbox/dev"] {
+                search_paths.push(home.join(dir));
+            }
+            search_paths.push(home.to_path_buf());
+        }
+        search_paths
+    }
+
     /// Try to find project path by searching for .idea directories
     fn find_project_path(&self, project_name: &str) -> Option<String> {
-        // Common locations to search for projects
-        let search_paths = vec![
-            format!("/home/{}/codes", std::env::var("USER").unwrap_or_default()),
-            format!("/home/{}/projects", std::env::var("USER").unwrap_or_default()),
-            format!("/home/{}/workspace", std::env::var("USER").unwrap_or_default()),
-            format!("/home/{}/dev", std::env::var("USER").unwrap_or_default()),
-            format!("/home/{}/Documents", std::env::var("USER").unwrap_or_default()),
-            format!("/home/{}/Dropbox/dev", std::env::var("USER").unwrap_or_default()),
-            format!("/home/{}", std::env::var("USER").unwrap_or_default()),
-        ];
+        let search_paths = self.project_search_paths();
 
         // First, try exact match with project name
         for base_path in &search_paths {
-            let potential_path = format!("{}/{}", base_path, project_name);
-            let idea_path = Path::new(&potential_path).join(".idea");
-            if idea_path.exists() && idea_path.is_dir() {
-                return Some(potential_path);
+            let potential_path = base_path.join(project_name);
+            if potential_path.join(".idea").is_dir() {
+                return Some(potential_path.to_string_lossy().into_owned());
             }
         }
 
-        // If not found, search recursively (limited depth)
+        // If not found, search recursively (limited depth). Directory walks on
+        // NFS/SSHFS mounts can stall for seconds, so skip them unless asked.
+        let skipped_mounts = if self.search_network_fs {
+            Vec::new()
+        } else {
+            get_network_mounts(&self.mountinfo_path)
+        };
+
+        // Shared across search paths: the home directory contains the others
+        let mut visited = HashSet::new();
+        let deadline = self.search_timeout.map(|timeout| Instant::now() + timeout);
         for base_path in &search_paths {
-            if let Ok(path) = self.find_project_in_directory(Path::new(base_path), project_name, 3) {
+            if let Ok(path) = self.find_project_in_directory(base_path, project_name, self.search_depth, &skipped_mounts, &mut visited, deadline) {
                 return Some(path);
             }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
         }
 
         None
     }
 
-    /// Recursively search for project directory with .idea folder
-    fn find_project_in_directory(&self, base: &Path, project_name: &str, max_depth: u32) -> Result<String, std::io::Error> {
+    /// The project name shown by the IDE when it was renamed away from the
+    /// directory name, as stored in `.idea/.name`
+    fn read_project_display_name(&self, project_dir: &Path) -> Option<String> {
+        fs::read_to_string(project_dir.join(".idea").join(".name"))
+            .ok()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Recursively search for project directory with .idea folder. Each
+    /// directory is searched once, by canonical path, so symlink loops and
+    /// aliased trees don't multiply the walk. Past `deadline` the search
+    /// stops descending and reports the project as not found.
+    fn find_project_in_directory(
+        &self,
+        base: &Path,
+        project_name: &str,
+        max_depth: u32,
+        skipped_mounts: &[PathBuf],
+        visited: &mut HashSet<PathBuf>,
+        deadline: Option<Instant>,
+    ) -> Result<String, std::io::Error> {
         if max_depth == 0 {
             return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Max depth reached"));
         }
 
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "Search timed out"));
+        }
+
+        if skipped_mounts.iter().any(|mount| base.starts_with(mount)) {
+            return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Skipped network filesystem"));
+        }
+
         if !base.exists() || !base.is_dir() {
             return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Base path not found"));
         }
 
-        // Check if current directory matches
-        if let Some(dir_name) = base.file_name().and_then(|n| n.to_str()) {
-            if dir_name.eq_ignore_ascii_case(project_name) {
-                let idea_path = base.join(".idea");
-                if idea_path.exists() && idea_path.is_dir() {
-                    return Ok(base.to_string_lossy().to_string());
-                }
+        if !visited.insert(fs::canonicalize(base)?) {
+            return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Already searched"));
+        }
+
+        // Check if current directory matches, by directory name or by the
+        // display name the IDE was given in .idea/.name
+        let idea_path = base.join(".idea");
+        if idea_path.is_dir() {
+            let dir_name_matches = base
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|dir_name| dir_name.eq_ignore_ascii_case(project_name));
+            let display_name_matches = self
+                .read_project_display_name(base)
+                .is_some_and(|name| name.eq_ignore_ascii_case(project_name));
+
+            if dir_name_matches || display_name_matches {
+                return Ok(base.to_string_lossy().to_string());
             }
         }
 
         // Search subdirectories
-        for entry in fs::read_dir(base)? {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.is_dir() {
-                    // Skip hidden directories and common non-project directories
-                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                        if !name.starts_with('.') && 
-                           !name.eq_ignore_ascii_case("node_modules") &&
-                           !name.eq_ignore_ascii_case("target") &&
-                           !name.eq_ignore_ascii_case("build") &&
-                           !name.eq_ignore_ascii_case("dist") {
-                            if let Ok(found) = self.find_project_in_directory(&path, project_name, max_depth - 1) {
-                                return Ok(found);
-                            }
+        for entry in fs::read_dir(base)?.flatten() {
+            let path = entry.path();
+            let is_symlink = entry.file_type().is_ok_and(|t| t.is_symlink());
+            if is_symlink && !self.follow_symlinks {
+                continue;
+            }
+            if path.is_dir() {
+                // Skip hidden directories and common non-project directories
+                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                    if !name.starts_with('.') && 
+                       !name.eq_ignore_ascii_case("node_modules") &&
+                       !name.eq_ignore_ascii_case("target") &&
+                       !name.eq_ignore_ascii_case("build") &&
+                       !name.eq_ignore_ascii_case("dist") {
+                        if let Ok(found) = self.find_project_in_directory(&path, project_name, max_depth - 1, skipped_mounts, visited, deadline) {
+                            return Ok(found);
                         }
                     }
                 }
@@ -289,14 +422,16 @@ impl JetBrainsDetector {
         Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Project not found"))
     }
 
-    /// Try to find opened files in JetBrains workspace
-    fn get_jetbrains_recent_files(&self, project_path: &str) -> Result<Vec<FileInfo>, std::io::Error> {
+    /// Try to find opened files in JetBrains workspace, along with the number
+    /// of editor tab groups (splits) when FileEditorManager is present
+    fn get_jetbrains_recent_files(&self, project_path: &str) -> Result<(Vec<FileInfo>, Option<usize>), std::io::Error> {
         let mut files = Vec::new();
+        let mut group_count = None;
         
         // JetBrains stores file information in .idea directory
         let idea_dir = Path::new(project_path).join(".idea");
         if !idea_dir.exists() {
-            return Ok(files);
+            return Ok((files, group_count));
         }
 
         // Try both workspace.xml and workspace_with_tabs.xml, sorted by modification time
@@ -313,85 +448,65 @@ impl JetBrainsDetector {
         });
 
         for workspace_file in workspace_files {
-            if workspace_file.exists() {
-                if let Ok(content) = fs::read_to_string(&workspace_file) {
-                    // Parse FileEditorManager component for open tabs
-                    if let Some(editor_manager_start) = content.find("<component name=\"FileEditorManager\">") {
-                        if let Some(editor_manager_end) = content[editor_manager_start..].find("</component>") {
-                            let editor_section = &content[editor_manager_start..editor_manager_start + editor_manager_end];
-                            
-                            // Regex to find file entries with tab status
-                            if let Ok(regex) = Regex::new(r#"<file[^>]*current-in-tab="([^"]*)"[^>]*>\s*<entry file="file://\$PROJECT_DIR\$([^"]+)""#) {
-                                for cap in regex.captures_iter(editor_section) {
-                                    if let (Some(is_current), Some(path_match)) = (cap.get(1), cap.get(2)) {
-                                        let relative_path = path_match.as_str();
-                                        let full_path = format!("{}{}", project_path, relative_path);
-                                        let is_active = is_current.as_str() == "true";
-                                        
-                                        if Path::new(&full_path).exists() {
-                                            let file_name = Path::new(relative_path)
-                                                .file_name()
-                                                .and_then(|n| n.to_str())
-                                                .unwrap_or(relative_path)
-                                                .to_string();
-
-                                            files.push(FileInfo {
-                                                path: full_path,
-                                                name: file_name,
-                                                is_active,
-                                                is_modified: false,
-                                                tab_index: None,
-                                                project_name: None,
-                                            });
-                                        }
-                                    }
-                                }
-                            }
-                            
-                            // If we found files in this workspace file, return early
-                            if !files.is_empty() {
-                                break;
-                            }
+            if let Ok(content) = fs::read_to_string(&workspace_file) {
+                let (parsed_files, parsed_group_count) = parse_workspace(&content, project_path, self.home_dir.as_deref());
+                group_count = group_count.or(parsed_group_count);
+                if !parsed_files.is_empty() {
+                    files = parsed_files;
+                    group_count = parsed_group_count;
+                    break;
+                }
+            }
+        }
+
+        Ok((files, group_count))
+    }
+
+    /// Combine the files seen in a project's window titles with the tabs in
+    /// its workspace file, returning them with the workspace's group count
+    fn merge_workspace_files(&self, proj_path: &str, mut open_files: Vec<FileInfo>) -> (Vec<FileInfo>, Option<usize>) {
+        let mut group_count = None;
+        let active_file = open_files.iter().find(|f| f.is_active).map(|f| f.path.clone());
+
+        if let Ok((workspace_files, workspace_group_count)) = self.get_jetbrains_recent_files(proj_path) {
+            group_count = workspace_group_count;
+
+            if !workspace_files.is_empty() {
+                // Check if window title detection found an active file that's not in workspace
+                // This suggests workspace file might be outdated
+                let window_active_found = active_file.is_some();
+                let workspace_has_different_active = workspace_files.iter()
+                    .find(|f| f.is_active)
+                    .map(|f| &f.path) != active_file.as_ref();
+                
+                // If window title shows different active file, prefer window title detection
+                if window_active_found && workspace_has_different_active {
+                    // Keep window title results, just supplement with workspace files that aren't duplicates
+                    for workspace_file in workspace_files {
+                        if !open_files.iter().any(|f| f.path == workspace_file.path) {
+                            let mut supplemented_file = workspace_file;
+                            supplemented_file.is_active = false; // Window title takes precedence for active status
+                            open_files.push(supplemented_file);
                         }
                     }
-                    
-                    // Fallback: Simple regex to find file paths in XML (for older formats)
-                    if files.is_empty() {
-                        if let Ok(regex) = Regex::new(r#"file://\$PROJECT_DIR\$([^"]+)"#) {
-                            for cap in regex.captures_iter(&content) {
-                                if let Some(path_match) = cap.get(1) {
-                                    let relative_path = path_match.as_str();
-                                    let full_path = format!("{}{}", project_path, relative_path);
-                                    
-                                    if Path::new(&full_path).exists() {
-                                        let file_name = Path::new(relative_path)
-                                            .file_name()
-                                            .and_then(|n| n.to_str())
-                                            .unwrap_or(relative_path)
-                                            .to_string();
-
-                                        files.push(FileInfo {
-                                            path: full_path,
-                                            name: file_name,
-                                            is_active: false,
-                                            is_modified: false,
-                                            tab_index: None,
-                                            project_name: None,
-                                        });
-
-                                        if files.len() >= 10 { // Limit number of files
-                                            break;
-                                        }
-                                    }
-                                }
-                            }
+                } else {
+                    // Replace window title detection with workspace file info
+                    open_files = workspace_files;
+                }
+            } else if open_files.is_empty() || open_files.len() == 1 {
+                // Fallback to old behavior for older IDE versions
+                if let Ok((recent_files, _)) = self.get_jetbrains_recent_files(proj_path) {
+                    for recent_file in recent_files {
+                        // Avoid duplicates
+                        if !open_files.iter().any(|f| f.path == recent_file.path) {
+                            open_files.push(recent_file);
                         }
                     }
                 }
             }
         }
 
-        Ok(files)
+        (open_files, group_count)
     }
 }
 
@@ -401,104 +516,1226 @@ impl IDEDetector for JetBrainsDetector {
     }
 
     fn is_target_process(&self, process: &ProcessInfo) -> bool {
-        self.process_names
+        self.ide_type.matches_process_name(&process.name)
+    }
+
+    fn state_paths(&self, result: &crate::types::DetectionResult) -> Vec<PathBuf> {
+        // workspace.xml and workspace_with_tabs.xml live in .idea
+        result
+            .project_path
             .iter()
-            .any(|&name| process.name.to_lowercase().contains(&name.to_lowercase()))
+            .map(|project_path| Path::new(project_path).join(".idea"))
+            .collect()
     }
 
     fn extract_files(
         &self,
         processes: &[ProcessInfo],
     ) -> DetectionResult<crate::types::DetectionResult> {
-        let mut open_files = Vec::new();
-        let mut active_file = None;
-        let mut project_path = None;
         let ide_version = None;
 
+        // Each frame (window) shows one project, and one IDE process can own
+        // several frames. Collect the title files per project, in the order
+        // projects are first seen.
+        let mut projects: Vec<(Option<String>, Vec<FileInfo>)> = Vec::new();
+        let mut primary_project = None;
+        let mut cmdline_project = None;
+
         for process in processes {
-            // Try to extract info from window title
-            if let Some((file_info, extracted_project_path)) = self.parse_jetbrains_window_title(&process.window_title) {
-                if file_info.is_active {
-                    active_file = Some(file_info.path.clone());
-                }
+            // A synthetic title is the raw cmdline, whose paths can spuriously
+            // match the title patterns.
+            let titles: &[String] = if process.title_is_synthetic {
+                &[]
+            } else {
+                &process.window_titles
+            };
 
-                if let Some(path) = extracted_project_path {
-                    project_path = Some(path);
+            for title in titles {
+                if let Some((file_info, project_path)) = self.parse_jetbrains_window_title(title) {
+                    match projects.iter_mut().find(|(path, _)| *path == project_path) {
+                        Some((_, files)) => files.push(file_info),
+                        None => projects.push((project_path.clone(), vec![file_info])),
+                    }
+                    // Titles come bottom to top: the last one is the frontmost frame
+                    primary_project = Some(project_path);
                 }
-
-                open_files.push(file_info);
             }
 
             // Also try to extract project path from command line
-            if project_path.is_none() {
-                if let Some(cmdline) = self.get_process_cmdline(process.pid) {
-                    if let Some(cmd_project_path) = self.extract_project_from_cmdline(&cmdline) {
-                        project_path = Some(cmd_project_path);
-                    }
+            if cmdline_project.is_none() && !projects.iter().any(|(path, _)| path.is_some()) {
+                if let Some(cmdline) = crate::process::get_process_cmdline(process.pid) {
+                    cmdline_project = self.extract_project_from_cmdline(&cmdline);
                 }
             }
         }
 
-        // If we found a project path, try to get opened files from workspace
-        if let Some(ref proj_path) = project_path {
-            if let Ok(workspace_files) = self.get_jetbrains_recent_files(proj_path) {
-                if !workspace_files.is_empty() {
-                    // Check if window title detection found an active file that's not in workspace
-                    // This suggests workspace file might be outdated
-                    let window_active_found = open_files.iter().any(|f| f.is_active);
-                    let workspace_has_different_active = workspace_files.iter()
-                        .find(|f| f.is_active)
-                        .map(|f| &f.path) != active_file.as_ref();
-                    
-                    // If window title shows different active file, prefer window title detection
-                    if window_active_found && workspace_has_different_active {
-                        // Keep window title results, just supplement with workspace files that aren't duplicates
-                        for workspace_file in workspace_files {
-                            if !open_files.iter().any(|f| f.path == workspace_file.path) {
-                                let mut supplemented_file = workspace_file;
-                                supplemented_file.is_active = false; // Window title takes precedence for active status
-                                open_files.push(supplemented_file);
-                            }
-                        }
-                    } else {
-                        // Replace window title detection with workspace file info
-                        open_files.clear();
-                        active_file = None;
-                        
-                        for workspace_file in workspace_files {
-                            if workspace_file.is_active {
-                                active_file = Some(workspace_file.path.clone());
-                            }
-                            open_files.push(workspace_file);
-                        }
-                    }
-                } else if open_files.is_empty() || open_files.len() == 1 {
-                    // Fallback to old behavior for older IDE versions
-                    if let Ok(recent_files) = self.get_jetbrains_recent_files(proj_path) {
-                        for recent_file in recent_files {
-                            // Avoid duplicates
-                            if !open_files.iter().any(|f| f.path == recent_file.path) {
-                                open_files.push(recent_file);
-                            }
-                        }
-                    }
+        // Without a project from any title, fall back to the command line one
+        if !projects.iter().any(|(path, _)| path.is_some()) {
+            if let Some(path) = cmdline_project {
+                match projects.first_mut() {
+                    Some((project_path, _)) => *project_path = Some(path.clone()),
+                    None => projects.push((Some(path.clone()), Vec::new())),
                 }
+                primary_project = Some(Some(path));
             }
         }
 
-        if open_files.is_empty() {
+        // The frontmost frame's project comes first and holds the active file
+        let primary_index = projects
+            .iter()
+            .position(|(path, _)| Some(path) == primary_project.as_ref())
+            .unwrap_or(0);
+        if primary_index > 0 {
+            let primary = projects.remove(primary_index);
+            projects.insert(0, primary);
+        }
+
+        let mut open_files = Vec::new();
+        let mut active_file = None;
+        let mut project_path = None;
+        let mut group_count = None;
+
+        for (index, (path, title_files)) in projects.into_iter().enumerate() {
+            let (mut files, project_group_count) = match &path {
+                Some(path) => self.merge_workspace_files(path, title_files),
+                None => (title_files, None),
+            };
+
+            // .idea/.name is the authoritative project name; the window title
+            // and directory name can differ from it
+            if let Some(display_name) = path
+                .as_deref()
+                .and_then(|path| self.read_project_display_name(Path::new(path)))
+            {
+                for file in &mut files {
+                    file.project_name = Some(display_name.clone());
+                }
+            }
+
+            for file in &mut files {
+                file.project_path = path.clone();
+            }
+
+            if index == 0 {
+                active_file = files.iter().find(|f| f.is_active).map(|f| f.path.clone());
+                project_path = path;
+                group_count = project_group_count;
+            } else {
+                // Background frames have an active tab too, but it isn't focused
+                for file in &mut files {
+                    file.is_active = false;
+                }
+            }
+
+            for file in files {
+                if !open_files.iter().any(|f: &FileInfo| f.path == file.path) {
+                    open_files.push(file);
+                }
+            }
+        }
+
+        // A project with no editors open (fresh clone, just indexed) is
+        // still a result: consumers learn the project context
+        if open_files.is_empty() && project_path.is_none() {
             return Err(crate::detector::DetectionError::WindowParseError {
                 message: format!("No files detected for {}", self.display_name()),
             });
         }
 
         Ok(crate::types::DetectionResult {
+            format_version: crate::types::FORMAT_VERSION,
             timestamp: chrono::Utc::now().to_rfc3339(),
             ide_name: self.display_name().to_string(),
             ide_version,
             active_file,
             open_files,
+            project_language: project_path
+                .as_deref()
+                .and_then(|path| project_language(path, self.home_dir.as_deref())),
             project_path,
+            group_count,
+            seq: None,
+        })
+    }
+}
+
+/// The body of a `<component name="...">` element, if present and not empty
+fn component_section<'a>(content: &'a str, name: &str) -> Option<&'a str> {
+    let start = content.find(&format!("<component name=\"{}\">", name))?;
+    let end = content[start..].find("</component>")?;
+    Some(&content[start..start + end])
+}
+
+/// A `<file>` tab in a workspace's FileEditorManager
+struct EditorTab {
+    /// The tab's `<entry file=...>` URL, path macros unexpanded
+    entry: String,
+    /// Tab group (`<leaf>`) it is shown in, from 0
+    group: usize,
+    current_in_tab: Option<bool>,
+    /// Pre-2020 marker of the selected tab
+    current: bool,
+    pinned: bool,
+    /// Saved `<caret>` line and column (0-based), from the tab's editor state
+    caret: Option<(usize, usize)>,
+}
+
+/// Each tab's position in its group's tab strip
+fn visual_tab_indexes(tabs: &[EditorTab]) -> Vec<usize> {
+    let mut indexes = vec![0; tabs.len()];
+    let groups: HashSet<usize> = tabs.iter().map(|tab| tab.group).collect();
+    for group in groups {
+        let members: Vec<usize> = (0..tabs.len()).filter(|&i| tabs[i].group == group).collect();
+        let kinds: Vec<TabKind> = members
+            .iter()
+            .map(|&i| if tabs[i].pinned { TabKind::Pinned } else { TabKind::Regular })
+            .collect();
+        for (member, index) in members.into_iter().zip(TabKind::visual_indexes(&kinds)) {
+            indexes[member] = index;
+        }
+    }
+    indexes
+}
+
+/// An attribute's unescaped value
+fn xml_attribute(element: &BytesStart, name: &str) -> Option<String> {
+    let attribute = element.try_get_attribute(name).ok()??;
+    attribute.unescape_value().ok().map(|value| value.into_owned())
+}
+
+/// The tabs in a workspace's FileEditorManager, in tab order, and
+/// its number of tab groups; None when the component is missing or empty.
+/// A file cut short mid-write yields the tabs read up to that point.
+fn editor_tabs(content: &str) -> Option<(Vec<EditorTab>, usize)> {
+    let mut reader = Reader::from_str(content);
+    let mut depth = 0;
+    let mut manager_depth = None;
+    let mut group_count: usize = 0;
+    // The <file> being read and its depth
+    let mut tab: Option<(EditorTab, usize)> = None;
+    let mut tabs = Vec::new();
+
+    loop {
+        let (element, is_empty) = match reader.read_event() {
+            Ok(Event::Start(element)) => (element, false),
+            Ok(Event::Empty(element)) => (element, true),
+            Ok(Event::End(element)) => {
+                depth -= 1;
+                match element.name().as_ref() {
+                    b"file" if tab.as_ref().is_some_and(|(_, d)| *d == depth) => {
+                        let (file, _) = tab.take().unwrap();
+                        if !file.entry.is_empty() {
+                            tabs.push(file);
+                        }
+                    }
+                    b"component" if manager_depth == Some(depth) => break,
+                    _ => {}
+                }
+                continue;
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(_) => continue,
+        };
+
+        match (manager_depth, element.name().as_ref()) {
+            (None, b"component")
+                if !is_empty && xml_attribute(&element, "name").as_deref() == Some("FileEditorManager") =>
+            {
+                manager_depth = Some(depth);
+            }
+            (Some(_), b"leaf") => group_count += 1,
+            (Some(_), b"file") if !is_empty => {
+                let flag = |name| xml_attribute(&element, name).map(|value| value == "true");
+                let file = EditorTab {
+                    entry: String::new(),
+                    group: group_count.saturating_sub(1),
+                    current_in_tab: flag("current-in-tab"),
+                    current: flag("current").unwrap_or(false),
+                    pinned: flag("pinned").unwrap_or(false),
+                    caret: None,
+                };
+                tab = Some((file, depth));
+            }
+            // Line and column are left out when 0
+            (Some(_), b"caret") => {
+                if let Some((file, _)) = tab.as_mut().filter(|(file, _)| file.caret.is_none()) {
+                    let position = |name| xml_attribute(&element, name).and_then(|v| v.parse().ok()).unwrap_or(0);
+                    file.caret = Some((position("line"), position("column")));
+                }
+            }
+            // The tab's own <entry>, not one nested in its editor state
+            (Some(_), b"entry") => {
+                if let Some((file, file_depth)) = tab.as_mut() {
+                    if *file_depth + 1 == depth && file.entry.is_empty() {
+                        if let Some(entry) = xml_attribute(&element, "file") {
+                            file.entry = entry;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        if !is_empty {
+            depth += 1;
+        }
+    }
+
+    manager_depth.map(|_| (tabs, group_count.max(1)))
+}
+
+/// The absolute path a workspace.xml file reference points to: a `file://`
+/// or `jar://` URL, or a bare path, with its leading path macro expanded.
+/// For a `jar://` entry (a class or source inside an archive) this is the
+/// jar itself. None for other schemes and unknown macros.
+///
+/// `$MODULE_DIR$` is taken to be the project directory, where the module of
+/// a single-module project (the usual case) lives. `$USER_HOME$` is
+/// `home_dir`, or `$HOME` when unset.
+fn expand_jetbrains_macro(raw: &str, project_path: &str, home_dir: Option<&str>) -> Option<String> {
+    let path = if let Some(path) = raw.strip_prefix("file://") {
+        path
+    } else if let Some(path) = raw.strip_prefix("jar://") {
+        // jar://<archive>!/<entry inside it>
+        path.split_once("!/").map_or(path, |(archive, _)| archive)
+    } else if raw.contains("://") {
+        return None;
+    } else {
+        raw
+    };
+
+    let expanded = match path.strip_prefix('$') {
+        Some(macro_path) => {
+            let (name, rest) = macro_path.split_once('$')?;
+            match name {
+                "PROJECT_DIR" | "MODULE_DIR" => format!("{}{}", project_path, rest),
+                "USER_HOME" => format!("{}{}", user_home(home_dir)?, rest),
+                "APPLICATION_CONFIG_DIR" => application_config_file(rest, home_dir)?,
+                _ => return None,
+            }
+        }
+        None => path.to_string(),
+    };
+
+    Some(expanded).filter(|path| !path.is_empty() && is_absolute_title_path(path))
+}
+
+/// `home_dir`, else `$HOME`
+fn user_home(home_dir: Option<&str>) -> Option<String> {
+    home_dir.map(str::to_string).or_else(|| std::env::var("HOME").ok())
+}
+
+/// Where `$APPLICATION_CONFIG_DIR$<rest>` (e.g. `/scratches/scratch.txt`)
+/// leads: the IDE's versioned configuration directory is not recorded, so
+/// the newest one under the JetBrains configuration root holding the file.
+/// The environment describes the invoking user, so it is only consulted
+/// when `home_dir` is unset.
+fn application_config_file(rest: &str, home_dir: Option<&str>) -> Option<String> {
+    #[cfg(target_os = "macos")]
+    let root = PathBuf::from(user_home(home_dir)?).join("Library/Application Support/JetBrains");
+    #[cfg(windows)]
+    let root = match home_dir {
+        Some(home) => Path::new(home).join("AppData").join("Roaming"),
+        None => PathBuf::from(std::env::var("APPDATA").ok()?),
+    }
+    .join("JetBrains");
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let root = match home_dir {
+        Some(home) => Path::new(home).join(".config"),
+        None => std::env::var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|_| std::env::var("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .ok()?,
+    }
+    .join("JetBrains");
+
+    let mut candidates: Vec<(std::time::SystemTime, String)> = fs::read_dir(root)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let path = format!("{}{}", entry.path().to_string_lossy(), rest);
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Path::new(&path).exists().then_some((modified, path))
         })
+        .collect();
+    candidates.sort();
+    candidates.pop().map(|(_, path)| path)
+}
+
+/// The project's primary language ("java", "python", "go", ...): from the
+/// project SDK in `.idea/misc.xml`, else from the type of the first module
+/// in `.idea/modules.xml`. None when neither names a known one.
+fn project_language(project_path: &str, home_dir: Option<&str>) -> Option<String> {
+    let idea = Path::new(project_path).join(".idea");
+
+    let sdk_language = fs::read_to_string(idea.join("misc.xml"))
+        .ok()
+        .and_then(|misc| xml_element_attribute(&misc, "component", "project-jdk-type"))
+        .and_then(|sdk| match sdk.as_str() {
+            "JavaSDK" | "IDEA JDK" | "Android SDK" => Some("java"),
+            "Python SDK" => Some("python"),
+            "RUBY_SDK" => Some("ruby"),
+            "Go SDK" => Some("go"),
+            _ => None,
+        });
+
+    let module_language = || {
+        let modules = fs::read_to_string(idea.join("modules.xml")).ok()?;
+        let module_file = xml_element_attribute(&modules, "module", "filepath")?;
+        let module = fs::read_to_string(expand_jetbrains_macro(&module_file, project_path, home_dir)?).ok()?;
+        match xml_element_attribute(&module, "module", "type")?.as_str() {
+            "JAVA_MODULE" => Some("java"),
+            "PYTHON_MODULE" => Some("python"),
+            "RUBY_MODULE" => Some("ruby"),
+            "GO_MODULE" => Some("go"),
+            "RUST_MODULE" => Some("rust"),
+            "CPP_MODULE" => Some("cpp"),
+            "WEB_MODULE" => Some("javascript"),
+            _ => None,
+        }
+    };
+
+    sdk_language.or_else(module_language).map(str::to_string)
+}
+
+/// `attribute` of the first `<element>` in `content` carrying it. Parsing
+/// stops at the first malformed markup.
+fn xml_element_attribute(content: &str, element: &str, attribute: &str) -> Option<String> {
+    let mut reader = Reader::from_str(content);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(start)) | Ok(Event::Empty(start)) if start.name().as_ref() == element.as_bytes() => {
+                if let Some(value) = xml_attribute(&start, attribute) {
+                    return Some(value);
+                }
+            }
+            Ok(Event::Eof) | Err(_) => return None,
+            Ok(_) => {}
+        }
+    }
+}
+
+/// A FileInfo for a workspace.xml file reference, if the file still exists
+fn workspace_file_info(
+    project_path: &str,
+    home_dir: Option<&str>,
+    raw: &str,
+    source: FileSource,
+) -> Option<FileInfo> {
+    let full_path = expand_jetbrains_macro(raw, project_path, home_dir)?;
+    if !Path::new(&full_path).exists() {
+        return None;
+    }
+
+    Some(FileInfo::new(&full_path, source))
+}
+
+/// Parse a workspace.xml into its open files and, when FileEditorManager is
+/// present, its number of editor tab groups (splits).
+///
+/// Open tabs come from FileEditorManager. When it records none, the recent
+/// editors in editorHistoryManager and IdeDocumentHistory are used instead,
+/// tagged `RecentHistory`. (RecentsManager only holds dialog history, such as
+/// recent move targets, so it is not a source of files.) Path macros are
+/// expanded against `project_path` and `home_dir`.
+fn parse_workspace(content: &str, project_path: &str, home_dir: Option<&str>) -> (Vec<FileInfo>, Option<usize>) {
+    let mut files: Vec<FileInfo> = Vec::new();
+    let mut group_count = None;
+
+    // Each tab group is a <leaf> under the (possibly nested) <splitter>
+    if let Some((tabs, groups)) = editor_tabs(content) {
+        group_count = Some(groups);
+
+        // Workspaces written before 2020 have no current-in-tab; the selected
+        // tab is the one with current="true"
+        let legacy = tabs.iter().all(|tab| tab.current_in_tab.is_none());
+        // Pinned tabs are drawn at the start of their group's strip
+        let tab_indexes = visual_tab_indexes(&tabs);
+        for (tab, tab_index) in tabs.iter().zip(tab_indexes) {
+            if let Some(mut file) = workspace_file_info(project_path, home_dir, &tab.entry, FileSource::SessionState) {
+                file.is_active = if legacy { tab.current } else { tab.current_in_tab == Some(true) };
+                file.is_pinned = tab.pinned;
+                file.tab_index = Some(tab_index);
+                if let Some((line, column)) = tab.caret {
+                    file.cursor_line = Some(line + 1);
+                    file.cursor_column = Some(column + 1);
+                }
+                files.push(file);
+            }
+        }
+    }
+
+    if files.is_empty() {
+        // Both list the most recent editor last
+        let history_regex = Regex::new(r#"(?:<entry file|<option value)="([^"]+)""#).unwrap();
+        let history = ["editorHistoryManager", "IdeDocumentHistory"]
+            .iter()
+            .filter_map(|name| component_section(content, name));
+        for section in history {
+            let paths: Vec<&str> = history_regex
+                .captures_iter(section)
+                .filter_map(|cap| cap.get(1).map(|m| m.as_str()))
+                .collect();
+            for raw in paths.into_iter().rev() {
+                if let Some(file) = workspace_file_info(project_path, home_dir, raw, FileSource::RecentHistory) {
+                    if !files.iter().any(|f| f.path == file.path) {
+                        files.push(file);
+                    }
+                }
+            }
+        }
+    }
+
+    // Fallback: any project file mentioned, for older formats
+    if files.is_empty() {
+        let legacy_regex = Regex::new(r#"file://\$PROJECT_DIR\$[^"]+"#).unwrap();
+        for entry in legacy_regex.find_iter(content) {
+            if let Some(file) = workspace_file_info(project_path, home_dir, entry.as_str(), FileSource::SessionState) {
+                files.push(file);
+                if files.len() >= 10 { // Limit number of files
+                    break;
+                }
+            }
+        }
+    }
+
+    (files, group_count)
+}
+
+/// Whether a title names a file by absolute path (`/...` or `C:\...`)
+/// rather than relative to the project
+fn is_absolute_title_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with('/')
+        || (bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && (bytes[2] == b'\\' || bytes[2] == b'/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_target_process_matches_jetbrains_launchers_only() {
+        let cases = [
+            (SupportedIDE::GoLand, "goland", true),
+            (SupportedIDE::GoLand, "goland.sh", true),
+            (SupportedIDE::GoLand, "goland64.exe", true),
+            (SupportedIDE::GoLand, "goland.exe", true),
+            (SupportedIDE::GoLand, "GoLand", true),
+            (SupportedIDE::GoLand, "pycharm", false),
+            (SupportedIDE::PyCharm, "pycharm", true),
+            (SupportedIDE::PyCharm, "PyCharm", true),
+            (SupportedIDE::PyCharm, "pycharm64.exe", true),
+            (SupportedIDE::PyCharm, "pycharm-helper", false),
+            (SupportedIDE::IntelliJIDEA, "idea", true),
+            (SupportedIDE::IntelliJIDEA, "idea64.exe", true),
+            (SupportedIDE::IntelliJIDEA, "ideapad-daemon", false),
+            (SupportedIDE::IntelliJIDEA, "java", false),
+            (SupportedIDE::IntelliJIDEA, "fsnotifier", false),
+            (SupportedIDE::CLion, "clion", true),
+            (SupportedIDE::CLion, "clangd", false),
+            (SupportedIDE::WebStorm, "webstorm64.exe", true),
+            (SupportedIDE::PhpStorm, "phpstorm", true),
+            (SupportedIDE::RubyMine, "rubymine", true),
+            (SupportedIDE::AndroidStudio, "studio", true),
+            (SupportedIDE::AndroidStudio, "studio.sh", true),
+            (SupportedIDE::AndroidStudio, "studio64.exe", true),
+            (SupportedIDE::AndroidStudio, "android-studio-helper", false),
+            (SupportedIDE::DataGrip, "datagrip", true),
+            (SupportedIDE::Rider, "rider64.exe", true),
+            (SupportedIDE::RustRover, "rustrover", true),
+            (SupportedIDE::RustRover, "rust-analyzer", false),
+            (SupportedIDE::AppCode, "appcode", true),
+            (SupportedIDE::DataSpell, "dataspell", true),
+        ];
+
+        for (ide, name, expected) in cases {
+            let detector = JetBrainsDetector::new(ide);
+            let process = ProcessInfo::for_test(name, "");
+            assert_eq!(detector.is_target_process(&process), expected, "{:?}: {}", ide, name);
+        }
+    }
+
+    #[test]
+    fn absolute_path_titles_are_used_verbatim() {
+        let detector = JetBrainsDetector::new(SupportedIDE::GoLand);
+        let cases = [
+            ("/home/me/notes.md - GoLand 2024.1", "/home/me/notes.md", "notes.md", false),
+            ("/home/me/my-notes.md* - GoLand 2024.1", "/home/me/my-notes.md", "my-notes.md", true),
+            ("/home/me/my-notes.md — GoLand 2024.1", "/home/me/my-notes.md", "my-notes.md", false),
+            ("C:\\Users\\me\\notes.md - GoLand 2024.1", "C:\\Users\\me\\notes.md", "notes.md", false),
+        ];
+
+        for (title, path, name, is_modified) in cases {
+            let (file, project_path) = detector.parse_jetbrains_window_title(title).expect(title);
+            assert_eq!(file.path, path, "{}", title);
+            assert_eq!(file.name, name, "{}", title);
+            assert_eq!(file.is_modified, is_modified, "{}", title);
+            assert_eq!(file.project_name, None, "{}", title);
+            assert_eq!(project_path, None, "{}", title);
+        }
+    }
+
+    #[test]
+    fn synthetic_titles_are_not_parsed_as_window_titles() {
+        let detector = JetBrainsDetector::new(SupportedIDE::GoLand);
+        // A command line that happens to look like a JetBrains title
+        let process = |title_is_synthetic| ProcessInfo {
+            window_title: "/home/me/notes.md - GoLand 2024.1".to_string(),
+            window_titles: vec!["/home/me/notes.md - GoLand 2024.1".to_string()],
+            title_is_synthetic,
+            ..ProcessInfo::for_test("goland", "")
+        };
+
+        let result = detector.extract_files(&[process(false)]).unwrap();
+        assert_eq!(result.active_file.as_deref(), Some("/home/me/notes.md"));
+        assert!(matches!(
+            detector.extract_files(&[process(true)]),
+            Err(crate::detector::DetectionError::WindowParseError { .. })
+        ));
+    }
+
+    #[test]
+    fn hyphenated_file_names_survive_title_parsing() {
+        let detector = JetBrainsDetector::new(SupportedIDE::CLion);
+        let cases = [
+            ("my-file.rs - proj [/p] - CLion 2024.1", "/p/my-file.rs", "my-file.rs", "proj", false),
+            (
+                "my-component-test.tsx* - web-app [/src/web-app] - WebStorm 2024.2.1",
+                "/src/web-app/my-component-test.tsx",
+                "my-component-test.tsx",
+                "web-app",
+                true,
+            ),
+            ("my-file.rs – proj [/p] – CLion 2024.1", "/p/my-file.rs", "my-file.rs", "proj", false),
+            ("my-file.rs — my-proj [/p] — CLion 2024.1", "/p/my-file.rs", "my-file.rs", "my-proj", false),
+        ];
+
+        for (title, path, name, project, is_modified) in cases {
+            let (file, project_path) = detector.parse_jetbrains_window_title(title).expect(title);
+            assert_eq!(file.path, path, "{}", title);
+            assert_eq!(file.name, name, "{}", title);
+            assert_eq!(file.project_name.as_deref(), Some(project), "{}", title);
+            assert_eq!(file.is_modified, is_modified, "{}", title);
+            assert_eq!(project_path.as_deref(), path.rsplit_once('/').map(|(dir, _)| dir), "{}", title);
+        }
+    }
+
+    #[test]
+    fn pycharm_2025_1_titles_put_the_project_first() {
+        let root = std::env::temp_dir().join(format!("ide-files-pycharm-title-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/ml-pipeline/.idea")).unwrap();
+        let path = |relative: &str| root.join(relative).to_string_lossy().into_owned();
+        let detector = JetBrainsDetector::new(SupportedIDE::PyCharm)
+            .with_home_dir(Some(path("home")))
+            .with_project_roots(vec![path("src")]);
+
+        // Exactly as PyCharm 2025.1 sets it: project, en dash, file, no IDE suffix
+        let (file, project_path) = detector
+            .parse_jetbrains_window_title("ml-pipeline – train-model_v2.py")
+            .unwrap();
+        assert_eq!(file.name, "train-model_v2.py");
+        assert_eq!(file.project_name.as_deref(), Some("ml-pipeline"));
+        assert_eq!(file.path, path("src/ml-pipeline/train-model_v2.py"));
+        assert_eq!(project_path, Some(path("src/ml-pipeline")));
+
+        // An unknown project still yields the file, without a directory
+        let (file, project_path) = detector
+            .parse_jetbrains_window_title("scratch-area — settings.py")
+            .unwrap();
+        assert_eq!((file.path.as_str(), file.project_name.as_deref()), ("settings.py", Some("scratch-area")));
+        assert_eq!(project_path, None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// FileEditorManager as written by 2023.x+ IDEs: attributes in any order,
+    /// omitted when false, and pinned tabs
+    const WORKSPACE_WITH_PINNED_TABS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project version="4">
+  <component name="FileEditorManager">
+    <splitter split-orientation="horizontal" split-proportion="0.5">
+      <split-first>
+        <leaf SIDE_TABS_SIZE_LIMIT_KEY="-1">
+          <file pinned="true">
+            <entry file="file://$PROJECT_DIR$/Cargo.toml" />
+          </file>
+          <file current-in-tab="true" pinned="false">
+            <entry file="file://$PROJECT_DIR$/src/main.rs" />
+          </file>
+        </leaf>
+      </split-first>
+      <split-second>
+        <leaf>
+          <file>
+            <entry file="file://$PROJECT_DIR$/src/types.rs" />
+          </file>
+          <file current-in-tab="false">
+            <entry file="file://$PROJECT_DIR$/src/deleted.rs" />
+          </file>
+        </leaf>
+      </split-second>
+    </splitter>
+  </component>
+</project>"#;
+
+    /// No open tabs recorded; only the recent-editor components
+    const WORKSPACE_WITH_HISTORY_ONLY: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project version="4">
+  <component name="FileEditorManager" />
+  <component name="IdeDocumentHistory">
+    <option name="CHANGED_PATHS">
+      <list>
+        <option value="$PROJECT_DIR$/src/types.rs" />
+        <option value="$PROJECT_DIR$/Cargo.toml" />
+      </list>
+    </option>
+  </component>
+  <component name="RecentsManager">
+    <key name="MoveFile.RECENT_KEYS">
+      <recent name="$PROJECT_DIR$/src" />
+    </key>
+  </component>
+  <component name="editorHistoryManager">
+    <entry file="file://$PROJECT_DIR$/README.md">
+      <provider selected="true" editor-type-id="text-editor" />
+    </entry>
+    <entry file="file://$PROJECT_DIR$/src/main.rs">
+      <provider selected="true" editor-type-id="text-editor" />
+    </entry>
+  </component>
+</project>"#;
+
+    /// A pre-2020 workspace: every <file> has leaf-file-name and pinned, and
+    /// the selected tab is marked current="true" instead of current-in-tab
+    const LEGACY_WORKSPACE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project version="4">
+  <component name="FileEditorManager">
+    <leaf>
+      <file leaf-file-name="Cargo.toml" pinned="false" current="false">
+        <entry file="file://$PROJECT_DIR$/Cargo.toml">
+          <provider selected="true" editor-type-id="text-editor">
+            <state relative-caret-position="0" />
+          </provider>
+        </entry>
+      </file>
+      <file leaf-file-name="main.rs" pinned="true" current="true">
+        <entry file="file://$PROJECT_DIR$/src/main.rs">
+          <provider selected="true" editor-type-id="text-editor">
+            <state relative-caret-position="120" />
+          </provider>
+        </entry>
+      </file>
+    </leaf>
+  </component>
+</project>"#;
+
+    /// A 2024.x workspace.xml: attributes reordered, current-in-tab missing
+    /// from background tabs, and each tab's editor state nested in its <entry>
+    const WORKSPACE_2024: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project version="4">
+  <component name="ChangeListManager">
+    <list default="true" id="4f1c" name="Changes" comment="" />
+  </component>
+  <component name="FileEditorManager">
+    <leaf ideFingerprint="2r8c9k" SIDE_TABS_SIZE_LIMIT_KEY="-1">
+      <file>
+        <entry file="file://$PROJECT_DIR$/README.md">
+          <provider editor-type-id="split-provider[text-editor;markdown-preview-editor]" selected="true">
+            <state split_layout="SPLIT">
+              <first_editor relative-caret-position="330">
+                <caret line="22" column="14" selection-start-line="22" />
+              </first_editor>
+            </state>
+          </provider>
+        </entry>
+      </file>
+      <file pinned="true" current-in-tab="true">
+        <entry file="file://$PROJECT_DIR$/src/main.rs">
+          <provider selected="true" editor-type-id="text-editor">
+            <state relative-caret-position="1472">
+              <caret line="92" column="8" />
+            </state>
+          </provider>
+        </entry>
+        <tab><![CDATA[{"tabTitle":"main.rs"}]]></tab>
+      </file>
+      <file>
+        <entry file="file://$PROJECT_DIR$/Cargo.toml">
+          <provider selected="true" editor-type-id="text-editor" />
+        </entry>
+      </file>
+    </leaf>
+  </component>
+  <component name="editorHistoryManager">
+    <entry file="file://$PROJECT_DIR$/src/types.rs" />
+  </component>
+</project>"#;
+
+    fn summarize(files: &[FileInfo]) -> Vec<(String, bool, bool, FileSource)> {
+        let project = env!("CARGO_MANIFEST_DIR");
+        files
+            .iter()
+            .map(|f| (f.path.replacen(project, "", 1), f.is_active, f.is_pinned, f.source))
+            .collect()
+    }
+
+    #[test]
+    fn workspace_tabs_are_read_with_pinned_status() {
+        let (files, group_count) = parse_workspace(WORKSPACE_WITH_PINNED_TABS, env!("CARGO_MANIFEST_DIR"), None);
+        let tab = |path: &str, active, pinned| (path.to_string(), active, pinned, FileSource::SessionState);
+
+        assert_eq!(
+            summarize(&files),
+            [
+                tab("/Cargo.toml", false, true),
+                tab("/src/main.rs", true, false),
+                tab("/src/types.rs", false, false),
+            ]
+        );
+        assert_eq!(group_count, Some(2));
+    }
+
+    #[test]
+    fn reordered_attributes_and_nested_editor_state_keep_tab_order() {
+        let (files, group_count) = parse_workspace(WORKSPACE_2024, env!("CARGO_MANIFEST_DIR"), None);
+        let tab = |path: &str, active, pinned| (path.to_string(), active, pinned, FileSource::SessionState);
+
+        assert_eq!(
+            summarize(&files),
+            [
+                tab("/README.md", false, false),
+                tab("/src/main.rs", true, true),
+                tab("/Cargo.toml", false, false),
+            ]
+        );
+        // The pinned main.rs is drawn first
+        let tab_indexes: Vec<Option<usize>> = files.iter().map(|f| f.tab_index).collect();
+        assert_eq!(tab_indexes, [Some(1), Some(0), Some(2)]);
+        assert_eq!(group_count, Some(1));
+    }
+
+    #[test]
+    fn saved_carets_become_one_based_cursor_positions() {
+        let (files, _) = parse_workspace(WORKSPACE_2024, env!("CARGO_MANIFEST_DIR"), None);
+        let cursors: Vec<(Option<usize>, Option<usize>)> =
+            files.iter().map(|f| (f.cursor_line, f.cursor_column)).collect();
+
+        // README's caret is in the split editor's text half; Cargo.toml has no saved state
+        assert_eq!(cursors, [(Some(23), Some(15)), (Some(93), Some(9)), (None, None)]);
+    }
+
+    #[test]
+    fn legacy_workspace_marks_the_current_tab_active() {
+        let (files, group_count) = parse_workspace(LEGACY_WORKSPACE, env!("CARGO_MANIFEST_DIR"), None);
+        let tab = |path: &str, active, pinned| (path.to_string(), active, pinned, FileSource::SessionState);
+
+        assert_eq!(summarize(&files), [tab("/Cargo.toml", false, false), tab("/src/main.rs", true, true)]);
+        assert_eq!(group_count, Some(1));
+    }
+
+    #[test]
+    fn path_macros_and_jar_entries_expand_to_absolute_paths() {
+        let cases = [
+            ("file://$PROJECT_DIR$/src/main.rs", Some("/work/api/src/main.rs".to_string())),
+            ("$PROJECT_DIR$/README.md", Some("/work/api/README.md".to_string())),
+            ("file://$MODULE_DIR$/build.gradle", Some("/work/api/build.gradle".to_string())),
+            ("file:///etc/hosts", Some("/etc/hosts".to_string())),
+            ("jar:///usr/lib/jvm/lib/src.zip!/java.base/java/lang/String.java", Some("/usr/lib/jvm/lib/src.zip".to_string())),
+            ("$MAVEN_REPOSITORY$/junit/junit.jar", None),
+            ("mock:///src/main.rs", None),
+            ("true", None),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(expand_jetbrains_macro(raw, "/work/api", None), expected, "{}", raw);
+        }
+
+        if let Ok(home) = std::env::var("HOME") {
+            assert_eq!(
+                expand_jetbrains_macro("jar://$USER_HOME$/.m2/repository/lib-1.0-sources.jar!/lib/Lib.java", "/work/api", None),
+                Some(format!("{}/.m2/repository/lib-1.0-sources.jar", home))
+            );
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn home_macros_expand_against_the_target_users_home() {
+        let home = std::env::temp_dir().join(format!("ide-files-jetbrains-home-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        let scratches = home.join(".config/JetBrains/GoLand2024.1/scratches");
+        fs::create_dir_all(&scratches).unwrap();
+        fs::create_dir_all(home.join(".m2")).unwrap();
+        fs::write(scratches.join("scratch.go"), "").unwrap();
+        fs::write(home.join(".m2/lib-sources.jar"), "").unwrap();
+        let path = |relative: &str| home.join(relative).to_string_lossy().into_owned();
+
+        let workspace = r#"<project version="4">
+  <component name="FileEditorManager">
+    <leaf>
+      <file><entry file="jar://$USER_HOME$/.m2/lib-sources.jar!/com/example/Lib.java" /></file>
+      <file current-in-tab="true"><entry file="file://$APPLICATION_CONFIG_DIR$/scratches/scratch.go" /></file>
+    </leaf>
+  </component>
+</project>"#;
+        let (files, _) = parse_workspace(workspace, "/work/api", home.to_str());
+        fs::remove_dir_all(&home).unwrap();
+
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                path(".m2/lib-sources.jar"),
+                path(".config/JetBrains/GoLand2024.1/scratches/scratch.go"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tabs_outside_the_project_are_kept() {
+        let root = std::env::temp_dir().join(format!("ide-files-jetbrains-macros-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("api/src")).unwrap();
+        fs::create_dir_all(root.join("libs")).unwrap();
+        fs::write(root.join("api/src/main.rs"), "").unwrap();
+        fs::write(root.join("libs/lib-sources.jar"), "").unwrap();
+        fs::write(root.join("notes.txt"), "").unwrap();
+        let path = |relative: &str| root.join(relative).to_string_lossy().into_owned();
+
+        let workspace = format!(
+            r#"<project version="4">
+  <component name="FileEditorManager">
+    <leaf>
+      <file><entry file="file://$PROJECT_DIR$/src/main.rs" /></file>
+      <file current-in-tab="true"><entry file="jar://{}!/com/example/Lib.java" /></file>
+      <file><entry file="file://{}" /></file>
+    </leaf>
+  </component>
+</project>"#,
+            path("libs/lib-sources.jar"),
+            path("notes.txt"),
+        );
+        let (files, _) = parse_workspace(&workspace, &path("api"), None);
+        fs::remove_dir_all(&root).unwrap();
+
+        let summary: Vec<(&str, &str, bool)> = files
+            .iter()
+            .map(|f| (f.path.as_str(), f.name.as_str(), f.is_active))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (path("api/src/main.rs").as_str(), "main.rs", false),
+                (path("libs/lib-sources.jar").as_str(), "lib-sources.jar", true),
+                (path("notes.txt").as_str(), "notes.txt", false),
+            ]
+        );
+    }
+
+    const MISC_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project version="4">
+  <component name="Black">
+    <option name="sdkName" value="Python 3.12 (api)" />
+  </component>
+  <component name="ProjectRootManager" version="2" project-jdk-name="Python 3.12 (api)" project-jdk-type="Python SDK" />
+</project>"#;
+
+    #[test]
+    fn project_language_comes_from_the_sdk_or_the_module_type() {
+        let root = std::env::temp_dir().join(format!("ide-files-jetbrains-language-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let project = |name: &str| {
+            let idea = root.join(name).join(".idea");
+            fs::create_dir_all(&idea).unwrap();
+            (root.join(name).to_string_lossy().into_owned(), idea)
+        };
+
+        let (sdk, idea) = project("sdk");
+        fs::write(idea.join("misc.xml"), MISC_XML).unwrap();
+
+        // No SDK (GoLand keeps GOROOT elsewhere): the module type decides
+        let (module, idea) = project("module");
+        fs::write(
+            idea.join("misc.xml"),
+            r#"<project version="4"><component name="ProjectRootManager" version="2" /></project>"#,
+        )
+        .unwrap();
+        fs::write(
+            idea.join("modules.xml"),
+            r#"<project version="4">
+  <component name="ProjectModuleManager">
+    <modules>
+      <module fileurl="file://$PROJECT_DIR$/.idea/module.iml" filepath="$PROJECT_DIR$/.idea/module.iml" />
+    </modules>
+  </component>
+</project>"#,
+        )
+        .unwrap();
+        fs::write(idea.join("module.iml"), r#"<module type="GO_MODULE" version="4" />"#).unwrap();
+
+        let (truncated, idea) = project("truncated");
+        fs::write(idea.join("misc.xml"), &MISC_XML[..MISC_XML.find("project-jdk-name").unwrap()]).unwrap();
+
+        let languages = [sdk, module, truncated].map(|path| project_language(&path, None));
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(languages, [Some("python".to_string()), Some("go".to_string()), None]);
+    }
+
+    #[test]
+    fn recent_editor_history_is_used_without_open_tabs() {
+        let (files, group_count) = parse_workspace(WORKSPACE_WITH_HISTORY_ONLY, env!("CARGO_MANIFEST_DIR"), None);
+        let recent = |path: &str| (path.to_string(), false, false, FileSource::RecentHistory);
+
+        // Most recent first: editorHistoryManager, then IdeDocumentHistory
+        assert_eq!(
+            summarize(&files),
+            [recent("/src/main.rs"), recent("/README.md"), recent("/Cargo.toml"), recent("/src/types.rs")]
+        );
+        assert_eq!(group_count, None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn project_without_open_editors_is_a_result_with_no_files() {
+        let root = std::env::temp_dir().join(format!("ide-files-jetbrains-empty-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("fresh-clone/.idea")).unwrap();
+        fs::write(
+            root.join("fresh-clone/.idea/workspace.xml"),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<project version="4">
+  <component name="FileEditorManager" />
+</project>
+"#,
+        )
+        .unwrap();
+        let project = root.join("fresh-clone").to_string_lossy().into_owned();
+
+        // An IDE launched on the project, with no file in its title
+        let mut child = std::process::Command::new("sh")
+            .args(["-c", "while sleep 1; do :; done", "goland", &project])
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        let started = Instant::now();
+        while crate::process::get_process_cmdline(child.id())
+            .is_some_and(|cmdline| cmdline.first().map(String::as_str) != Some("sh"))
+            && started.elapsed() < Duration::from_secs(5)
+        {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let process = ProcessInfo {
+            pid: child.id(),
+            window_title: "fresh-clone - GoLand 2024.3".to_string(),
+            window_titles: vec!["fresh-clone - GoLand 2024.3".to_string()],
+            ..ProcessInfo::for_test("goland", "")
+        };
+        let result = JetBrainsDetector::new(SupportedIDE::GoLand).extract_files(&[process]);
+        let _ = child.kill();
+        let _ = child.wait();
+        fs::remove_dir_all(&root).unwrap();
+
+        let result = result.unwrap();
+        assert_eq!(result.project_path, Some(project));
+        assert!(result.open_files.is_empty());
+        assert_eq!(result.active_file, None);
+    }
+
+    #[test]
+    fn one_process_with_two_frames_reports_both_projects() {
+        let root = std::env::temp_dir().join(format!("ide-files-jetbrains-frames-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let path = |relative: &str| root.join(relative).to_string_lossy().into_owned();
+        for (project, files) in [("api", ["main.go", "handler.go"]), ("web", ["app.ts", "index.ts"])] {
+            fs::create_dir_all(root.join(project).join(".idea")).unwrap();
+            let entries: String = files
+                .iter()
+                .enumerate()
+                .map(|(i, file)| {
+                    format!(
+                        r#"<file{}><entry file="file://$PROJECT_DIR$/{}" /></file>"#,
+                        if i == 0 { r#" current-in-tab="true""# } else { "" },
+                        file
+                    )
+                })
+                .collect();
+            fs::write(
+                root.join(project).join(".idea/workspace.xml"),
+                format!(
+                    r#"<project version="4"><component name="FileEditorManager"><leaf>{}</leaf></component></project>"#,
+                    entries
+                ),
+            )
+            .unwrap();
+            for file in files {
+                fs::write(root.join(project).join(file), "").unwrap();
+            }
+        }
+
+        // One JVM, two frames; titles come bottom to top, so web is in front
+        let titles = vec![
+            format!("main.go - api [{}] - IntelliJ IDEA 2024.3", path("api")),
+            format!("app.ts - web [{}] - IntelliJ IDEA 2024.3", path("web")),
+        ];
+        let process = ProcessInfo {
+            pid: u32::MAX,
+            window_title: titles[1].clone(),
+            window_titles: titles,
+            ..ProcessInfo::for_test("idea", "")
+        };
+        let result = JetBrainsDetector::new(SupportedIDE::IntelliJIDEA).extract_files(&[process]).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        let files: Vec<(String, Option<String>, bool)> = result
+            .open_files
+            .iter()
+            .map(|f| (f.path.clone(), f.project_path.clone(), f.is_active))
+            .collect();
+        assert_eq!(
+            files,
+            [
+                (path("web/app.ts"), Some(path("web")), true),
+                (path("web/index.ts"), Some(path("web")), false),
+                (path("api/main.go"), Some(path("api")), false),
+                (path("api/handler.go"), Some(path("api")), false),
+            ]
+        );
+        assert_eq!(result.project_path, Some(path("web")));
+        assert_eq!(result.active_file, Some(path("web/app.ts")));
+    }
+
+    #[test]
+    fn project_search_stops_at_the_configured_depth_and_deadline() {
+        let root = std::env::temp_dir().join(format!("ide-files-search-limits-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/api/.idea")).unwrap();
+        fs::create_dir_all(root.join("src/team/backend/billing/.idea")).unwrap();
+        let path = |relative: &str| root.join(relative).to_string_lossy().into_owned();
+        let detector = || {
+            JetBrainsDetector::new(SupportedIDE::GoLand)
+                .with_home_dir(Some(path("home")))
+                .with_project_roots(vec![path("src")])
+        };
+
+        // billing sits four levels below the root, counting the root itself
+        assert_eq!(detector().find_project_path("billing"), None);
+        assert_eq!(
+            detector().with_search_depth(4).find_project_path("billing"),
+            Some(path("src/team/backend/billing"))
+        );
+
+        // An expired deadline stops the recursive walk, but a project directly
+        // below a search path is still found
+        let expired = detector().with_search_depth(4).with_search_timeout(Some(Duration::ZERO));
+        assert_eq!(expired.find_project_path("billing"), None);
+        assert_eq!(expired.find_project_path("api"), Some(path("src/api")));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn project_search_skips_network_mounts_unless_asked() {
+        let root = std::env::temp_dir().join(format!("ide-files-network-mounts-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/nfs/billing/.idea")).unwrap();
+        fs::create_dir_all(root.join("src/local/api/.idea")).unwrap();
+        let path = |relative: &str| root.join(relative).to_string_lossy().into_owned();
+        let mountinfo = root.join("mountinfo");
+        fs::write(
+            &mountinfo,
+            format!(
+                "22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw\n\
+                 41 22 0:45 / {} rw,relatime shared:30 - nfs4 fileserver:/export rw,vers=4.2\n\
+                 42 22 8:2 / {} rw,relatime shared:31 - ext4 /dev/sda2 rw\n",
+                path("src/nfs"),
+                path("src/local"),
+            ),
+        )
+        .unwrap();
+        let detector = || {
+            JetBrainsDetector::new(SupportedIDE::GoLand)
+                .with_home_dir(Some(path("home")))
+                .with_project_roots(vec![path("src")])
+                .with_mountinfo_path(mountinfo.clone())
+        };
+
+        assert_eq!(get_network_mounts(&mountinfo), vec![root.join("src/nfs")]);
+        assert_eq!(detector().find_project_path("billing"), None);
+        assert_eq!(detector().find_project_path("api"), Some(path("src/local/api")));
+        assert_eq!(
+            detector().with_network_fs_search(true).find_project_path("billing"),
+            Some(path("src/nfs/billing"))
+        );
+        // An unreadable mount table skips nothing
+        assert_eq!(
+            detector().with_mountinfo_path(root.join("missing")).find_project_path("billing"),
+            Some(path("src/nfs/billing"))
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn configured_project_roots_are_searched_before_the_home_directory() {
+        let root = std::env::temp_dir().join(format!("ide-files-project-roots-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("home/projects/api/.idea")).unwrap();
+        fs::create_dir_all(root.join("data/src/api/.idea")).unwrap();
+        fs::create_dir_all(root.join("data/src/team/billing/.idea")).unwrap();
+        let path = |relative: &str| root.join(relative).to_string_lossy().into_owned();
+
+        let detector = JetBrainsDetector::new(SupportedIDE::GoLand)
+            .with_home_dir(Some(path("home")))
+            .with_project_roots(vec![path("data/src")]);
+        let search_paths = detector.project_search_paths();
+
+        assert_eq!(search_paths.first(), Some(&root.join("data/src")));
+        assert_eq!(search_paths.last(), Some(&root.join("home")));
+        // Found in the configured root first, and by the recursive search below it
+        assert_eq!(detector.find_project_path("api"), Some(path("data/src/api")));
+        assert_eq!(detector.find_project_path("billing"), Some(path("data/src/team/billing")));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn project_search_survives_symlink_loops() {
+        use std::os::unix::fs::symlink;
+
+        let root = std::env::temp_dir().join(format!("ide-files-symlink-loop-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("work/nested/service/.idea")).unwrap();
+        fs::create_dir_all(root.join("linked/app/.idea")).unwrap();
+        // Two loops back to the root: without the visited set, a deep search
+        // would branch twice per level
+        symlink("..", root.join("work/loop")).unwrap();
+        symlink("../..", root.join("work/nested/loop")).unwrap();
+        symlink("../linked", root.join("work/shortcut")).unwrap();
+
+        // Searching work/, app is only reachable through work/shortcut
+        let work = root.join("work");
+        let search = |detector: &JetBrainsDetector, name: &str| {
+            detector.find_project_in_directory(&work, name, 64, &[], &mut HashSet::new(), None).ok()
+        };
+        let following = JetBrainsDetector::new(SupportedIDE::GoLand).with_symlink_following(true);
+        let default = JetBrainsDetector::new(SupportedIDE::GoLand);
+
+        let started = std::time::Instant::now();
+        assert_eq!(search(&following, "missing"), None);
+        assert_eq!(search(&default, "missing"), None);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+
+        let service = root.join("work/nested/service").to_string_lossy().to_string();
+        assert_eq!(search(&following, "service"), Some(service.clone()));
+        assert_eq!(search(&default, "service"), Some(service));
+        assert!(search(&following, "app").is_some());
+        assert_eq!(search(&default, "app"), None);
+
+        fs::remove_dir_all(&root).unwrap();
     }
 }
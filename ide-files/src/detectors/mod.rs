@@ -1,3 +1,134 @@
 pub mod jetbrains;
+pub mod neovim;
+pub mod onivim;
+pub mod sublime;
 pub mod terminal;
 pub mod vscode;
+
+use crate::detector::IDEDetectorManager;
+use crate::types::SupportedIDE;
+use std::time::Duration;
+use jetbrains::JetBrainsDetector;
+use onivim::OnivimDetector;
+use sublime::SublimeDetector;
+use terminal::TerminalEditorDetector;
+use vscode::VSCodeDetector;
+
+/// Settings passed on to the built-in detectors
+#[derive(Debug, Clone)]
+pub struct DetectorOptions {
+    /// Home directory of the user whose editors are inspected; `$HOME` when unset
+    pub home_dir: Option<String>,
+    /// Runtime directory of that user, where nvim keeps its sockets; `$XDG_RUNTIME_DIR` when unset
+    pub runtime_dir: Option<String>,
+    /// Non-default user data directory for VSCode, Cursor and Onivim
+    pub ide_config_dir: Option<String>,
+    /// Also search network mounts for JetBrains projects
+    pub search_network_fs: bool,
+    /// Follow symlinked directories while searching for JetBrains projects
+    pub follow_symlinks: bool,
+    /// Directories to search for JetBrains projects before the defaults under the home directory
+    pub project_roots: Vec<String>,
+    /// Directory levels the JetBrains project search descends below each search path
+    pub search_depth: u32,
+    /// Wall-clock limit for the JetBrains project search; None searches until done
+    pub search_timeout: Option<Duration>,
+    /// Use window focus and tmux panes to pick the active terminal editor
+    pub focus_detection: bool,
+    /// Keep files a detector would drop as duplicates (`--raw`)
+    pub raw: bool,
+}
+
+impl Default for DetectorOptions {
+    fn default() -> Self {
+        Self {
+            home_dir: None,
+            runtime_dir: None,
+            ide_config_dir: None,
+            search_network_fs: false,
+            follow_symlinks: false,
+            project_roots: Vec::new(),
+            search_depth: jetbrains::DEFAULT_SEARCH_DEPTH,
+            search_timeout: None,
+            focus_detection: true,
+            raw: false,
+        }
+    }
+}
+
+/// Register a detector for every supported IDE, in auto-detection priority order
+pub fn register_all(manager: &mut IDEDetectorManager, options: &DetectorOptions) {
+    let home_dir = &options.home_dir;
+
+    for ide in [
+        SupportedIDE::GoLand,
+        SupportedIDE::PyCharm,
+        SupportedIDE::IntelliJIDEA,
+        SupportedIDE::WebStorm,
+        SupportedIDE::PhpStorm,
+        SupportedIDE::RubyMine,
+        SupportedIDE::CLion,
+        SupportedIDE::AndroidStudio,
+        SupportedIDE::DataGrip,
+        SupportedIDE::Rider,
+        SupportedIDE::RustRover,
+        SupportedIDE::AppCode,
+        SupportedIDE::DataSpell,
+    ] {
+        manager.register_detector(Box::new(
+            JetBrainsDetector::new(ide)
+                .with_network_fs_search(options.search_network_fs)
+                .with_symlink_following(options.follow_symlinks)
+                .with_home_dir(home_dir.clone())
+                .with_project_roots(options.project_roots.clone())
+                .with_search_depth(options.search_depth)
+                .with_search_timeout(options.search_timeout),
+        ));
+    }
+
+    for ide in [
+        SupportedIDE::Vim,
+        SupportedIDE::Nano,
+        SupportedIDE::Helix,
+        SupportedIDE::Kakoune,
+        SupportedIDE::Ed,
+    ] {
+        manager.register_detector(Box::new(
+            TerminalEditorDetector::new(ide)
+                .with_focus_detection(options.focus_detection)
+                .with_home_dir(home_dir.clone())
+                .with_runtime_dir(options.runtime_dir.clone()),
+        ));
+    }
+
+    manager.register_detector(Box::new(
+        VSCodeDetector::new()
+            .with_home_dir(home_dir.clone())
+            .with_config_dir(options.ide_config_dir.clone())
+            .with_raw(options.raw),
+    ));
+    manager.register_detector(Box::new(
+        VSCodeDetector::insiders()
+            .with_home_dir(home_dir.clone())
+            .with_config_dir(options.ide_config_dir.clone())
+            .with_raw(options.raw),
+    ));
+    manager.register_detector(Box::new(
+        VSCodeDetector::cursor()
+            .with_home_dir(home_dir.clone())
+            .with_config_dir(options.ide_config_dir.clone())
+            .with_raw(options.raw),
+    ));
+
+    manager.register_detector(Box::new(
+        OnivimDetector::new()
+            .with_home_dir(home_dir.clone())
+            .with_config_dir(options.ide_config_dir.clone()),
+    ));
+
+    manager.register_detector(Box::new(
+        SublimeDetector::new()
+            .with_home_dir(home_dir.clone())
+            .with_raw(options.raw),
+    ));
+}
@@ -0,0 +1,235 @@
+//! Neovim's msgpack-rpc API, used to list the buffers of a running nvim
+//! rather than only the files on its command line
+
+use rmpv::Value;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// How long to wait on an unresponsive nvim before giving up on it
+const RPC_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// A listed buffer of a running nvim
+#[derive(Debug, PartialEq)]
+pub struct NvimBuffer {
+    pub name: String,
+    pub is_current: bool,
+    pub is_modified: bool,
+}
+
+/// The RPC address of the nvim process `pid`: its `--listen` argument, the
+/// `$NVIM_LISTEN_ADDRESS` it was started with, or the socket nvim creates by
+/// default (`nvim.<pid>.0` in `runtime_dir`, else `$XDG_RUNTIME_DIR`)
+pub fn find_socket(pid: u32, cmdline: &[String], runtime_dir: Option<&Path>) -> Option<PathBuf> {
+    let listen = cmdline
+        .iter()
+        .position(|arg| arg == "--listen")
+        .and_then(|index| cmdline.get(index + 1))
+        .map(PathBuf::from);
+    let environment = crate::process::get_process_env_var(pid, "NVIM_LISTEN_ADDRESS").map(PathBuf::from);
+
+    // A host:port address is TCP, which we don't connect to
+    listen
+        .filter(|path| path.exists())
+        .or_else(|| environment.filter(|path| path.exists()))
+        .or_else(|| default_socket(pid, runtime_dir))
+}
+
+/// The socket nvim `pid` serves by default, if it exists. Hosts embedding
+/// Neovim under their own process name are found this way.
+pub fn default_socket(pid: u32, runtime_dir: Option<&Path>) -> Option<PathBuf> {
+    #[cfg(windows)]
+    let default = {
+        let _ = runtime_dir;
+        PathBuf::from(format!(r"\\.\pipe\nvim.{}.0", pid))
+    };
+    #[cfg(not(windows))]
+    let default = match runtime_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => PathBuf::from(std::env::var("XDG_RUNTIME_DIR").ok()?),
+    }
+    .join(format!("nvim.{}.0", pid));
+
+    Some(default).filter(|path| path.exists())
+}
+
+/// The listed, named buffers of the nvim serving `socket`, in buffer order
+pub fn list_buffers(socket: &Path) -> Option<Vec<NvimBuffer>> {
+    let mut client = RpcClient::connect(socket)?;
+
+    let current = client.call("nvim_get_current_buf", vec![])?;
+    let buffers = client.call("nvim_list_bufs", vec![])?;
+
+    let mut listed = Vec::new();
+    for buffer in buffers.as_array()? {
+        let handle = buffer_handle(buffer)?;
+        let option = |client: &mut RpcClient, name: &str| {
+            let opts = Value::Map(vec![(Value::from("buf"), Value::from(handle))]);
+            client.call("nvim_get_option_value", vec![Value::from(name), opts])
+        };
+
+        if option(&mut client, "buflisted")?.as_bool() != Some(true) {
+            continue;
+        }
+        let name = client.call("nvim_buf_get_name", vec![buffer.clone()])?;
+        let name = name.as_str().unwrap_or_default();
+        if name.is_empty() {
+            continue;
+        }
+
+        listed.push(NvimBuffer {
+            name: name.to_string(),
+            is_current: *buffer == current,
+            is_modified: option(&mut client, "modified")?.as_bool() == Some(true),
+        });
+    }
+
+    Some(listed)
+}
+
+/// The integer handle inside a Buffer (msgpack ext type 0)
+fn buffer_handle(buffer: &Value) -> Option<i64> {
+    match buffer {
+        Value::Ext(0, data) => rmpv::decode::read_value(&mut data.as_slice()).ok()?.as_i64(),
+        other => other.as_i64(),
+    }
+}
+
+#[cfg(not(windows))]
+type Connection = std::os::unix::net::UnixStream;
+#[cfg(windows)]
+type Connection = std::fs::File;
+
+/// Blocking msgpack-rpc requests over nvim's socket or named pipe
+struct RpcClient {
+    connection: Connection,
+    next_id: u32,
+}
+
+impl RpcClient {
+    fn connect(socket: &Path) -> Option<Self> {
+        #[cfg(not(windows))]
+        let connection = {
+            let stream = std::os::unix::net::UnixStream::connect(socket).ok()?;
+            stream.set_read_timeout(Some(RPC_TIMEOUT)).ok()?;
+            stream.set_write_timeout(Some(RPC_TIMEOUT)).ok()?;
+            stream
+        };
+        #[cfg(windows)]
+        let connection = {
+            let _ = RPC_TIMEOUT;
+            std::fs::OpenOptions::new().read(true).write(true).open(socket).ok()?
+        };
+
+        Some(Self { connection, next_id: 0 })
+    }
+
+    /// Send `[0, id, method, params]` and wait for its `[1, id, error, result]`
+    fn call(&mut self, method: &str, params: Vec<Value>) -> Option<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = Value::Array(vec![
+            Value::from(0),
+            Value::from(id),
+            Value::from(method),
+            Value::Array(params),
+        ]);
+        let mut encoded = Vec::new();
+        rmpv::encode::write_value(&mut encoded, &request).ok()?;
+        self.connection.write_all(&encoded).ok()?;
+
+        loop {
+            let message = rmpv::decode::read_value(&mut self.connection).ok()?;
+            let message = message.as_array()?;
+            // Skip notifications (type 2) and anything not answering us
+            if message.first()?.as_u64() != Some(1) || message.get(1)?.as_u64() != Some(id as u64) {
+                continue;
+            }
+            if !message.get(2)?.is_nil() {
+                return None;
+            }
+            return message.get(3).cloned();
+        }
+    }
+}
+
+/// Neovim is the `nvim` program; Vim has no RPC socket
+pub fn is_neovim(cmdline: &[String]) -> bool {
+    cmdline
+        .first()
+        .and_then(|program| Path::new(program).file_stem())
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|stem| stem.eq_ignore_ascii_case("nvim"))
+}
+
+#[cfg(all(test, unix))]
+pub(crate) mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+
+    /// Answer the API calls `list_buffers` makes like an nvim with buffers
+    /// 1 (`dir`/a.rs, current), 2 (`dir`/b.rs, modified), 3 (unlisted) and
+    /// 4 (no name)
+    pub(crate) fn serve_fake_nvim(listener: UnixListener, dir: &str) {
+        let (mut stream, _) = listener.accept().unwrap();
+        let buffer = |handle: i64| {
+            let mut data = Vec::new();
+            rmpv::encode::write_value(&mut data, &Value::from(handle)).unwrap();
+            Value::Ext(0, data)
+        };
+
+        while let Ok(request) = rmpv::decode::read_value(&mut stream) {
+            let request = request.as_array().unwrap().clone();
+            let id = request[1].clone();
+            let params = request[3].as_array().unwrap().clone();
+            let option_buffer = || {
+                params[1].as_map().unwrap()[0].1.as_i64().unwrap()
+            };
+
+            let result = match request[2].as_str().unwrap() {
+                "nvim_get_current_buf" => buffer(1),
+                "nvim_list_bufs" => Value::Array((1..=4).map(buffer).collect()),
+                "nvim_buf_get_name" => match buffer_handle(&params[0]).unwrap() {
+                    1 => Value::from(format!("{}/a.rs", dir)),
+                    2 => Value::from(format!("{}/b.rs", dir)),
+                    3 => Value::from(format!("{}/unlisted.rs", dir)),
+                    _ => Value::from(""),
+                },
+                "nvim_get_option_value" => match params[0].as_str().unwrap() {
+                    "buflisted" => Value::from(option_buffer() != 3),
+                    "modified" => Value::from(option_buffer() == 2),
+                    _ => Value::Nil,
+                },
+                _ => Value::Nil,
+            };
+
+            // A notification first, which the client must skip
+            let notification = Value::Array(vec![Value::from(2), Value::from("redraw"), Value::Array(vec![])]);
+            let response = Value::Array(vec![Value::from(1), id, Value::Nil, result]);
+            rmpv::encode::write_value(&mut stream, &notification).unwrap();
+            rmpv::encode::write_value(&mut stream, &response).unwrap();
+        }
+    }
+
+    #[test]
+    fn lists_named_listed_buffers_over_rpc() {
+        let socket = std::env::temp_dir().join(format!("ide-files-nvim-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket);
+        let listener = UnixListener::bind(&socket).unwrap();
+        let server = std::thread::spawn(move || serve_fake_nvim(listener, "/src"));
+
+        let buffers = list_buffers(&socket);
+        std::fs::remove_file(&socket).unwrap();
+        server.join().unwrap();
+
+        let buffer = |name: &str, is_current, is_modified| NvimBuffer {
+            name: name.to_string(),
+            is_current,
+            is_modified,
+        };
+        assert_eq!(
+            buffers,
+            Some(vec![buffer("/src/a.rs", true, false), buffer("/src/b.rs", false, true)])
+        );
+    }
+}
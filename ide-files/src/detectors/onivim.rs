@@ -0,0 +1,327 @@
+use crate::detector::{DetectionResult, IDEDetector};
+use crate::types::{FileInfo, FileSource, ProcessInfo, SupportedIDE};
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Onivim 2 detector
+pub struct OnivimDetector {
+    home_dir: Option<String>,
+    config_dir: Option<String>,
+}
+
+impl Default for OnivimDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OnivimDetector {
+    pub fn new() -> Self {
+        Self {
+            home_dir: None,
+            config_dir: None,
+        }
+    }
+
+    /// Read another user's session file (None keeps `$HOME`)
+    pub fn with_home_dir(mut self, home_dir: Option<String>) -> Self {
+        self.home_dir = home_dir;
+        self
+    }
+
+    /// Read the session from this directory instead of the default config locations
+    pub fn with_config_dir(mut self, config_dir: Option<String>) -> Self {
+        self.config_dir = config_dir;
+        self
+    }
+
+    /// Onivim keeps its state under the config directory, like VSCode-derived editors
+    fn get_session_file_candidates(&self) -> Vec<PathBuf> {
+        if let Some(config_dir) = &self.config_dir {
+            return vec![Path::new(config_dir).join("session.json")];
+        }
+
+        let mut config_dirs = Vec::new();
+
+        // The environment describes the invoking user, not another one
+        if let Some(home) = &self.home_dir {
+            config_dirs.push(Path::new(home).join(".config").join("oni2"));
+            config_dirs.push(Path::new(home).join(".oni2"));
+            return config_dirs
+                .into_iter()
+                .map(|dir| dir.join("session.json"))
+                .collect();
+        }
+
+        if let Ok(xdg_config) = env::var("XDG_CONFIG_HOME") {
+            config_dirs.push(PathBuf::from(xdg_config).join("oni2"));
+        }
+        if let Ok(home) = env::var("HOME") {
+            config_dirs.push(Path::new(&home).join(".config").join("oni2"));
+            config_dirs.push(Path::new(&home).join(".oni2"));
+        }
+        if let Ok(app_data) = env::var("APPDATA") {
+            config_dirs.push(Path::new(&app_data).join("Oni2"));
+        }
+
+        config_dirs
+            .into_iter()
+            .map(|dir| dir.join("session.json"))
+            .collect()
+    }
+
+    /// Parse an Onivim session file into its open buffers.
+    ///
+    /// Buffers are listed either as plain paths or as objects with a
+    /// `filePath`/`path` field; the active one is named by `activeBuffer`.
+    fn parse_session(&self, content: &str) -> Option<(Vec<FileInfo>, Option<String>)> {
+        let session: Value = serde_json::from_str(content).ok()?;
+        let buffers = session.get("buffers")?.as_array()?;
+
+        let active_path = session
+            .get("activeBuffer")
+            .and_then(|v| v.as_str().or_else(|| v.get("filePath").and_then(|p| p.as_str())))
+            .map(|s| s.to_string());
+
+        let mut files = Vec::new();
+        for buffer in buffers {
+            let path = buffer
+                .as_str()
+                .or_else(|| buffer.get("filePath").and_then(|p| p.as_str()))
+                .or_else(|| buffer.get("path").and_then(|p| p.as_str()));
+
+            if let Some(path) = path {
+                // Skip scratch buffers and terminals that have no file behind them
+                if path.is_empty() || !Path::new(path).is_file() {
+                    continue;
+                }
+
+                let is_active = active_path.as_deref() == Some(path);
+                let mut file = self.create_file_info(path, is_active, FileSource::SessionState);
+                file.tab_index = Some(files.len());
+                files.push(file);
+            }
+        }
+
+        let workspace = session
+            .get("workingDirectory")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        Some((files, workspace))
+    }
+
+    /// Read the newest session file that lists any buffers
+    fn get_session_files(&self) -> Option<(Vec<FileInfo>, Option<String>)> {
+        let mut candidates: Vec<PathBuf> = self
+            .get_session_file_candidates()
+            .into_iter()
+            .filter(|path| path.is_file())
+            .collect();
+
+        // Sort by modification time (newest first)
+        candidates.sort_by(|a, b| {
+            let a_time = a.metadata().and_then(|m| m.modified()).unwrap_or(std::time::UNIX_EPOCH);
+            let b_time = b.metadata().and_then(|m| m.modified()).unwrap_or(std::time::UNIX_EPOCH);
+            b_time.cmp(&a_time)
+        });
+
+        candidates.iter().find_map(|path| {
+            let content = fs::read_to_string(path).ok()?;
+            self.parse_session(&content)
+                .filter(|(files, _)| !files.is_empty())
+        })
+    }
+
+    /// Extract file arguments from the command line, Vim style
+    fn extract_files_from_cmdline(&self, cmdline: &[String]) -> Vec<FileInfo> {
+        cmdline
+            .iter()
+            .skip(1) // Skip program name
+            .filter(|arg| !arg.starts_with('-') && !arg.starts_with('+'))
+            .filter(|arg| Path::new(arg.as_str()).is_file())
+            .map(|arg| {
+                let path = std::fs::canonicalize(arg)
+                    .ok()
+                    .and_then(|p| p.to_str().map(|s| s.to_string()))
+                    .unwrap_or_else(|| arg.clone());
+                self.create_file_info(&path, false, FileSource::CommandLine)
+            })
+            .collect()
+    }
+
+    /// Create a FileInfo struct from a path
+    fn create_file_info(&self, path: &str, is_active: bool, source: FileSource) -> FileInfo {
+        FileInfo {
+            is_active,
+            ..FileInfo::new(path, source)
+        }
+    }
+}
+
+impl IDEDetector for OnivimDetector {
+    fn ide_type(&self) -> SupportedIDE {
+        SupportedIDE::Onivim
+    }
+
+    fn is_target_process(&self, process: &ProcessInfo) -> bool {
+        self.ide_type().matches_process_name(&process.name)
+    }
+
+    fn state_paths(&self, _result: &crate::types::DetectionResult) -> Vec<PathBuf> {
+        self.get_session_file_candidates()
+            .into_iter()
+            .filter(|path| path.is_file())
+            .collect()
+    }
+
+    fn extract_files(
+        &self,
+        processes: &[ProcessInfo],
+    ) -> DetectionResult<crate::types::DetectionResult> {
+        let mut open_files = Vec::new();
+        let mut project_path = None;
+
+        if let Some((session_files, workspace)) = self.get_session_files() {
+            open_files = session_files;
+            project_path = workspace;
+        }
+
+        // Fall back to files passed on the command line
+        if open_files.is_empty() {
+            for process in processes {
+                if let Some(cmdline) = crate::process::get_process_cmdline(process.pid) {
+                    for file in self.extract_files_from_cmdline(&cmdline) {
+                        if !open_files.iter().any(|f: &FileInfo| f.path == file.path) {
+                            open_files.push(file);
+                        }
+                    }
+                }
+            }
+
+            // Like Vim, the first file argument is the one shown on startup
+            if let Some(first) = open_files.first_mut() {
+                first.is_active = true;
+            }
+        }
+
+        if open_files.is_empty() {
+            return Err(crate::detector::DetectionError::WindowParseError {
+                message: format!("No files detected for {}", self.display_name()),
+            });
+        }
+
+        let active_file = open_files
+            .iter()
+            .find(|f| f.is_active)
+            .map(|f| f.path.clone());
+
+        Ok(crate::types::DetectionResult {
+            format_version: crate::types::FORMAT_VERSION,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            ide_name: self.display_name().to_string(),
+            ide_version: None,
+            active_file,
+            open_files,
+            project_path,
+            group_count: None,
+            project_language: None,
+            seq: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_target_process_matches_onivim_only() {
+        let detector = OnivimDetector::new();
+        let cases = [
+            ("oni2", true),
+            ("Onivim2", true),
+            ("Oni2.exe", true),
+            ("Onivim2.exe", true),
+            ("oni2-helper", false),
+            ("onion", false),
+            ("vim", false),
+        ];
+
+        for (name, expected) in cases {
+            let process = ProcessInfo::for_test(name, "");
+            assert_eq!(detector.is_target_process(&process), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn session_buffers_are_read_and_cmdline_files_are_the_fallback() {
+        let root = std::env::temp_dir().join(format!("ide-files-onivim-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("config")).unwrap();
+        fs::create_dir_all(root.join("empty")).unwrap();
+        let path = |relative: &str| root.join(relative).to_string_lossy().into_owned();
+        for name in ["src/main.rs", "src/lib.rs", "README.md"] {
+            fs::create_dir_all(root.join(name).parent().unwrap()).unwrap();
+            fs::write(root.join(name), "").unwrap();
+        }
+        fs::write(
+            root.join("config/session.json"),
+            serde_json::json!({
+                "workingDirectory": path(""),
+                "activeBuffer": { "filePath": path("src/lib.rs") },
+                "buffers": [
+                    path("src/main.rs"),
+                    { "filePath": path("src/lib.rs") },
+                    { "path": path("deleted.rs") },
+                    { "filePath": "" },
+                ],
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let detector = OnivimDetector::new().with_config_dir(Some(path("config")));
+        let result = detector.extract_files(&[]).unwrap();
+        let paths: Vec<_> = result.open_files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(paths, [path("src/main.rs"), path("src/lib.rs")]);
+        assert_eq!(result.open_files[1].tab_index, Some(1));
+        assert_eq!(result.active_file, Some(path("src/lib.rs")));
+        assert_eq!(result.project_path, Some(path("")));
+        assert!(result.open_files.iter().all(|f| f.source == FileSource::SessionState));
+
+        // Without a session, the file arguments of the running process are used
+        let mut child = std::process::Command::new("tail")
+            .args(["-f", &path("README.md"), &path("src/main.rs")])
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        // Until the child execs, its command line is still this test's
+        let started = std::time::Instant::now();
+        while crate::process::get_process_cmdline(child.id())
+            .is_some_and(|cmdline| cmdline.first().map(String::as_str) != Some("tail"))
+            && started.elapsed() < std::time::Duration::from_secs(5)
+        {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let process = ProcessInfo {
+            pid: child.id(),
+            ..ProcessInfo::for_test("oni2", "")
+        };
+        let detector = OnivimDetector::new().with_config_dir(Some(path("empty")));
+        let result = detector.extract_files(&[process]);
+        let _ = child.kill();
+        let _ = child.wait();
+
+        let result = result.unwrap();
+        let paths: Vec<_> = result.open_files.iter().map(|f| f.path.clone()).collect();
+        let canonical = |relative: &str| fs::canonicalize(root.join(relative)).unwrap().to_string_lossy().into_owned();
+        assert_eq!(paths, [canonical("README.md"), canonical("src/main.rs")]);
+        assert_eq!(result.active_file, Some(canonical("README.md")));
+        assert!(result.open_files.iter().all(|f| f.source == FileSource::CommandLine));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
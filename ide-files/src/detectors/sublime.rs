@@ -0,0 +1,281 @@
+use crate::detector::{DetectionResult, IDEDetector};
+use crate::types::{FileInfo, FileSource, ProcessInfo, SupportedIDE};
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Session files in Sublime's `Local` directory. The auto-save one is
+/// rewritten while Sublime runs; the other only on exit.
+const SESSION_FILE_NAMES: &[&str] = &["Auto Save Session.sublime_session", "Session.sublime_session"];
+
+/// Sublime Text detector
+pub struct SublimeDetector {
+    home_dir: Option<String>,
+    /// Keep a file once per tab showing it instead of listing it once
+    raw: bool,
+}
+
+impl Default for SublimeDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SublimeDetector {
+    pub fn new() -> Self {
+        Self {
+            home_dir: None,
+            raw: false,
+        }
+    }
+
+    /// Read another user's session file (None keeps `$HOME`)
+    pub fn with_home_dir(mut self, home_dir: Option<String>) -> Self {
+        self.home_dir = home_dir;
+        self
+    }
+
+    /// List a file open in several tabs (e.g. in two windows) once per tab
+    pub fn with_raw(mut self, raw: bool) -> Self {
+        self.raw = raw;
+        self
+    }
+
+    /// Sublime Text 4 and 3 data directories on each platform
+    fn get_session_file_candidates(&self) -> Vec<PathBuf> {
+        let mut data_dirs = Vec::new();
+
+        // The environment describes the invoking user, not another one
+        let home = self.home_dir.clone().or_else(|| env::var("HOME").ok());
+        if self.home_dir.is_none() {
+            if let Ok(xdg_config) = env::var("XDG_CONFIG_HOME") {
+                data_dirs.push(PathBuf::from(&xdg_config).join("sublime-text"));
+                data_dirs.push(PathBuf::from(&xdg_config).join("sublime-text-3"));
+            }
+        }
+        if let Some(home) = home {
+            let home = Path::new(&home);
+            data_dirs.push(home.join(".config").join("sublime-text"));
+            data_dirs.push(home.join(".config").join("sublime-text-3"));
+            let app_support = home.join("Library").join("Application Support");
+            data_dirs.push(app_support.join("Sublime Text"));
+            data_dirs.push(app_support.join("Sublime Text 3"));
+        }
+        if self.home_dir.is_none() {
+            if let Ok(app_data) = env::var("APPDATA") {
+                data_dirs.push(Path::new(&app_data).join("Sublime Text"));
+                data_dirs.push(Path::new(&app_data).join("Sublime Text 3"));
+            }
+        }
+
+        data_dirs
+            .into_iter()
+            .flat_map(|dir| SESSION_FILE_NAMES.iter().map(move |name| dir.join("Local").join(name)))
+            .collect()
+    }
+
+    /// Parse a Sublime session into its open files and group count.
+    ///
+    /// Each window lists `buffers` (the files) and `groups` of `sheets`
+    /// (the tabs), which refer to a buffer by index. The active file is the
+    /// `selected` sheet of the first window's `selected_group`.
+    fn parse_session(&self, content: &str) -> Option<(Vec<FileInfo>, Option<usize>)> {
+        let session: Value = serde_json::from_str(content).ok()?;
+        let windows = session.get("windows")?.as_array()?;
+
+        let mut files: Vec<FileInfo> = Vec::new();
+        for (window_index, window) in windows.iter().enumerate() {
+            let buffers = window.get("buffers").and_then(|b| b.as_array());
+            let buffer_at = |index: Option<u64>| buffers?.get(index? as usize);
+
+            let selected_group = window.get("selected_group").and_then(|g| g.as_u64()).unwrap_or(0);
+            let groups = window.get("groups").and_then(|g| g.as_array()).cloned().unwrap_or_default();
+
+            for (group_index, group) in groups.iter().enumerate() {
+                // Sublime 4 allows selecting several tabs and writes a list
+                let selected_sheet = group.get("selected").and_then(|s| {
+                    s.as_u64().or_else(|| s.as_array()?.first()?.as_u64())
+                });
+                let sheets = group.get("sheets").and_then(|s| s.as_array());
+
+                for (sheet_index, sheet) in sheets.into_iter().flatten().enumerate() {
+                    let buffer = buffer_at(sheet.get("buffer").and_then(|b| b.as_u64()));
+                    let path = sheet
+                        .get("file")
+                        .and_then(|f| f.as_str())
+                        .or_else(|| buffer?.get("file")?.as_str());
+                    let Some(path) = path.map(native_path) else {
+                        continue;
+                    };
+                    let duplicate = !self.raw && files.iter().any(|f| f.path == path);
+                    if duplicate || !Path::new(&path).is_file() {
+                        continue;
+                    }
+
+                    let mut file = FileInfo::new(&path, FileSource::SessionState);
+                    file.is_active = window_index == 0
+                        && group_index as u64 == selected_group
+                        && selected_sheet == Some(sheet_index as u64);
+                    // Hot exit keeps unsaved edits as the buffer's contents
+                    file.is_modified = buffer.is_some_and(|b| b.get("contents").is_some());
+                    file.tab_index = Some(sheet_index);
+                    files.push(file);
+                }
+            }
+
+            // Buffers without a tab, e.g. from a session with no group data
+            for buffer in buffers.into_iter().flatten() {
+                let Some(path) = buffer.get("file").and_then(|f| f.as_str()).map(native_path) else {
+                    continue;
+                };
+                if files.iter().any(|f| f.path == path) || !Path::new(&path).is_file() {
+                    continue;
+                }
+                let mut file = FileInfo::new(&path, FileSource::SessionState);
+                file.is_modified = buffer.get("contents").is_some();
+                files.push(file);
+            }
+        }
+
+        let group_count = windows
+            .first()
+            .and_then(|w| w.get("groups"))
+            .and_then(|g| g.as_array())
+            .map(|g| g.len())
+            .filter(|n| *n > 0);
+
+        Some((files, group_count))
+    }
+
+    /// Read the newest session file that lists any files
+    fn get_session_files(&self) -> Option<(Vec<FileInfo>, Option<usize>)> {
+        let mut candidates: Vec<PathBuf> = self
+            .get_session_file_candidates()
+            .into_iter()
+            .filter(|path| path.is_file())
+            .collect();
+
+        // Sort by modification time (newest first)
+        candidates.sort_by(|a, b| {
+            let a_time = a.metadata().and_then(|m| m.modified()).unwrap_or(std::time::UNIX_EPOCH);
+            let b_time = b.metadata().and_then(|m| m.modified()).unwrap_or(std::time::UNIX_EPOCH);
+            b_time.cmp(&a_time)
+        });
+
+        candidates.iter().find_map(|path| {
+            let content = fs::read_to_string(path).ok()?;
+            self.parse_session(&content)
+                .filter(|(files, _)| !files.is_empty())
+        })
+    }
+}
+
+/// Sublime writes Windows paths as `/C/Users/...`
+fn native_path(path: &str) -> String {
+    if cfg!(windows) {
+        let mut chars = path.chars();
+        if let (Some('/'), Some(drive), Some('/')) = (chars.next(), chars.next(), chars.next()) {
+            if drive.is_ascii_alphabetic() {
+                return format!("{}:\\{}", drive, chars.as_str().replace('/', "\\"));
+            }
+        }
+    }
+    path.to_string()
+}
+
+impl IDEDetector for SublimeDetector {
+    fn ide_type(&self) -> SupportedIDE {
+        SupportedIDE::Sublime
+    }
+
+    fn is_target_process(&self, process: &ProcessInfo) -> bool {
+        self.ide_type().matches_process_name(&process.name)
+    }
+
+    fn state_paths(&self, _result: &crate::types::DetectionResult) -> Vec<PathBuf> {
+        self.get_session_file_candidates()
+            .into_iter()
+            .filter(|path| path.is_file())
+            .collect()
+    }
+
+    fn extract_files(
+        &self,
+        _processes: &[ProcessInfo],
+    ) -> DetectionResult<crate::types::DetectionResult> {
+        let Some((open_files, group_count)) = self.get_session_files() else {
+            return Err(crate::detector::DetectionError::WindowParseError {
+                message: format!("No files detected for {}", self.display_name()),
+            });
+        };
+
+        let active_file = open_files
+            .iter()
+            .find(|f| f.is_active)
+            .map(|f| f.path.clone());
+
+        Ok(crate::types::DetectionResult {
+            format_version: crate::types::FORMAT_VERSION,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            ide_name: self.display_name().to_string(),
+            ide_version: None,
+            active_file,
+            open_files,
+            project_path: None,
+            group_count,
+            project_language: None,
+            seq: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_session_reads_tabs_and_the_selected_sheet() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let session = serde_json::json!({
+            "windows": [
+                {
+                    "buffers": [
+                        {"file": format!("{}/Cargo.toml", dir)},
+                        {"file": format!("{}/README.md", dir), "contents": "unsaved"},
+                        {"contents": "scratch"},
+                        {"file": format!("{}/deleted.rs", dir)}
+                    ],
+                    "selected_group": 1,
+                    "groups": [
+                        {"selected": 0, "sheets": [{"buffer": 0}, {"buffer": 2}, {"buffer": 3}]},
+                        {"selected": [0], "sheets": [{"buffer": 1, "file": format!("{}/README.md", dir)}]}
+                    ]
+                },
+                {
+                    "buffers": [{"file": format!("{}/Makefile", dir)}],
+                    "selected_group": 0,
+                    "groups": [{"selected": 0, "sheets": [{"buffer": 0}]}]
+                }
+            ]
+        });
+
+        let (files, group_count) = SublimeDetector::new()
+            .parse_session(&session.to_string())
+            .unwrap();
+        let summary: Vec<(&str, bool, bool, Option<usize>)> = files
+            .iter()
+            .map(|f| (f.name.as_str(), f.is_active, f.is_modified, f.tab_index))
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                ("Cargo.toml", false, false, Some(0)),
+                ("README.md", true, true, Some(0)),
+                ("Makefile", false, false, Some(0)),
+            ]
+        );
+        assert_eq!(group_count, Some(2));
+    }
+}
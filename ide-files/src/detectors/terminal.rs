@@ -1,141 +1,566 @@
 use crate::detector::{DetectionResult, IDEDetector};
-use crate::types::{FileInfo, ProcessInfo, SupportedIDE};
+use crate::types::{FileInfo, FileSource, ProcessInfo, SupportedIDE, TerminalHost};
+use super::neovim;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Vim options whose value is the next argument rather than a file to edit.
+/// Not `-q`: its errorfile is optional, so `vim -q file.rs` edits file.rs
+/// (an errorfile given with it is attached, `-qerrors.txt`).
+const VIM_OPTIONS_WITH_VALUE: &[&str] = &[
+    "-c", "--cmd", "-S", "-u", "-U", "-i", "-t", "-T", "-s", "-w", "-W",
+    "--servername", "--startuptime", "--log",
+];
+
+/// Helix options whose value is the next argument
+const HELIX_OPTIONS_WITH_VALUE: &[&str] = &["-c", "--config", "--log", "-w", "--working-dir"];
+
+/// Kakoune options whose value is the next argument
+const KAKOUNE_OPTIONS_WITH_VALUE: &[&str] =
+    &["-c", "-s", "-e", "-E", "-ui", "-debug", "-f", "-i", "-p"];
+
+/// Nano options whose value is the next argument
+const NANO_OPTIONS_WITH_VALUE: &[&str] = &[
+    "-T", "--tabsize", "-Y", "--syntax", "-o", "--operatingdir", "-Q", "--quotestr",
+    "-r", "--fill", "-X", "--wordchars", "-f", "--rcfile", "-s", "--speller",
+    "-C", "--backupdir", "-J", "--guidestripe",
+];
+
+/// ed options whose value is the next argument
+const ED_OPTIONS_WITH_VALUE: &[&str] = &["-p", "--prompt"];
+
+/// Where a `+` argument (or Helix `file:line`) puts the cursor
+enum LineJump {
+    /// `+N` (or `+N,C`, `+N:C`): line N, and column C when given
+    Line(usize, Option<usize>),
+    /// `+`: the last line
+    LastLine,
+    /// `+/pattern`: the first match, which we don't resolve
+    Pattern,
+}
+
+/// A file named on an editor command line
+struct FileArg {
+    path: String,
+    line_jump: Option<LineJump>,
+}
+
+/// What an editor command line opens
+struct EditorArgs {
+    /// Files in argument (arglist) order
+    files: Vec<FileArg>,
+    /// Windows shown side by side on startup: one, or one per split
+    windows: usize,
+    /// Files are opened read-only (`vim -R`)
+    read_only: bool,
+}
+
+impl EditorArgs {
+    fn new(paths: Vec<String>) -> Self {
+        Self {
+            files: paths
+                .into_iter()
+                .map(|path| FileArg { path, line_jump: None })
+                .collect(),
+            windows: 1,
+            read_only: false,
+        }
+    }
+}
 
 /// Terminal editor detector
 pub struct TerminalEditorDetector {
     ide_type: SupportedIDE,
-    process_names: Vec<&'static str>,
+    focus_detection: bool,
+    home_dir: Option<String>,
+    runtime_dir: Option<String>,
 }
 
 impl TerminalEditorDetector {
     pub fn new(ide_type: SupportedIDE) -> Self {
-        let process_names = match ide_type {
-            SupportedIDE::Vim => vec!["vim", "nvim", "gvim"],
-            SupportedIDE::Nano => vec!["nano"],
-            _ => vec![],
-        };
-
         Self {
             ide_type,
-            process_names,
+            focus_detection: true,
+            home_dir: None,
+            runtime_dir: None,
         }
     }
 
-    /// Get process command line arguments via /proc filesystem (Linux/macOS)
-    #[cfg(any(target_os = "linux", target_os = "macos"))]
-    fn get_process_cmdline(&self, pid: u32) -> Option<Vec<String>> {
-        #[cfg(target_os = "linux")]
-        {
-            let cmdline_path = format!("/proc/{}/cmdline", pid);
-            std::fs::read_to_string(&cmdline_path).ok().map(|content| {
-                content
-                    .split('\0')
-                    .filter(|s| !s.is_empty())
-                    .map(|s| s.to_string())
-                    .collect()
+    /// Enable or disable picking the foreground editor via window focus and tmux panes
+    pub fn with_focus_detection(mut self, enabled: bool) -> Self {
+        self.focus_detection = enabled;
+        self
+    }
+
+    /// Read another user's editor state, e.g. Nano's position history (None keeps `$HOME`)
+    pub fn with_home_dir(mut self, home_dir: Option<String>) -> Self {
+        self.home_dir = home_dir;
+        self
+    }
+
+    /// Look for nvim's default sockets in another user's runtime directory (None keeps `$XDG_RUNTIME_DIR`)
+    pub fn with_runtime_dir(mut self, runtime_dir: Option<String>) -> Self {
+        self.runtime_dir = runtime_dir;
+        self
+    }
+
+    /// Collect the file arguments of an editor command line, in the order
+    /// given, along with the number of windows they are shown in. Relative
+    /// paths are resolved against the editor's working directory `cwd`.
+    fn extract_files_from_cmdline(&self, cmdline: &[String], cwd: &str) -> (Vec<FileInfo>, usize) {
+        if cmdline.is_empty() {
+            return (Vec::new(), 1);
+        }
+
+        let mut args = match self.ide_type {
+            // vim format: vim [options] file1 file2 ...
+            // May have options that take a value: vim -c 'set nu' --cmd cmd +10 file.txt
+            SupportedIDE::Vim => self.vim_arguments(&cmdline[1..]),
+            // hx [options] file1[:line[:col]] file2 ...
+            SupportedIDE::Helix => self.helix_arguments(&cmdline[1..], cwd),
+            // kak [options] [+line[:col]] file1 file2 ...
+            SupportedIDE::Kakoune => self.kakoune_arguments(&cmdline[1..]),
+            // nano [options] [+line[,col]] file1 [+line[,col]] file2 ...
+            SupportedIDE::Nano => self.nano_arguments(&cmdline[1..]),
+            // ed [-s] [-p prompt] [file]; `!command` reads a command's output
+            SupportedIDE::Ed => {
+                let mut file_paths = Vec::new();
+                let mut args = cmdline.iter().skip(1);
+                while let Some(arg) = args.next() {
+                    if ED_OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+                        args.next();
+                    } else if !arg.is_empty() && !arg.starts_with('-') && !arg.starts_with('!') {
+                        file_paths.push(arg.clone());
+                        break;
+                    }
+                }
+                EditorArgs::new(file_paths)
+            }
+            _ => EditorArgs::new(Vec::new()),
+        };
+
+        // Invoked as `view`, Vim behaves as `vim -R`
+        if self.ide_type == SupportedIDE::Vim {
+            let program = std::path::Path::new(&cmdline[0])
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default()
+                .to_lowercase();
+            if program == "view" || program.starts_with("view.") {
+                args.read_only = true;
+            }
+        }
+
+        // Nano reopens files where they were last left (with `--positionlog`)
+        let positions = if self.ide_type == SupportedIDE::Nano {
+            self.nano_positions()
+        } else {
+            HashMap::new()
+        };
+        let remembered_position = |path: &str| {
+            positions.get(path).copied().or_else(|| {
+                let canonical = std::fs::canonicalize(path).ok()?;
+                positions.get(canonical.to_str()?).copied()
             })
+        };
+
+        // Only files that actually exist count, numbered in arglist order
+        let files = args
+            .files
+            .into_iter()
+            .map(|arg| (self.create_file_info(arg.path, cwd), arg.line_jump))
+            .filter(|(file_info, _)| self.file_exists(&file_info.path))
+            .enumerate()
+            .map(|(index, (file_info, line_jump))| {
+                let (cursor_line, cursor_column) = match line_jump {
+                    Some(LineJump::Line(line, column)) => (Some(line), column),
+                    Some(LineJump::LastLine) => (self.count_lines(&file_info.path), None),
+                    Some(LineJump::Pattern) => (None, None),
+                    None => remembered_position(&file_info.path)
+                        .map_or((None, None), |(line, column)| (Some(line), Some(column))),
+                };
+                FileInfo {
+                    tab_index: Some(index),
+                    read_only: args.read_only,
+                    cursor_line,
+                    cursor_column,
+                    ..file_info
+                }
+            })
+            .collect();
+
+        (files, args.windows)
+    }
+
+    /// Pick the file arguments, `+` line jump and window layout out of Vim's
+    /// arguments (program name excluded)
+    fn vim_arguments(&self, args: &[String]) -> EditorArgs {
+        let mut files = Vec::new();
+        let mut line_jump = None;
+        let mut read_only = false;
+        // Some(None) for a split flag without a window count
+        let mut split: Option<Option<usize>> = None;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                // Everything after "--" is a file name
+                files.extend(args.by_ref().filter(|a| !a.is_empty()).cloned());
+                break;
+            }
+
+            if VIM_OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+                args.next();
+                continue;
+            }
+
+            // +N, +/pattern and bare + position the cursor; +cmd runs a command
+            if let Some(jump) = arg.strip_prefix('+') {
+                if jump.is_empty() {
+                    line_jump = Some(LineJump::LastLine);
+                } else if let Ok(line) = jump.parse::<usize>() {
+                    line_jump = Some(LineJump::Line(line, None));
+                } else if jump.starts_with('/') {
+                    line_jump = Some(LineJump::Pattern);
+                }
+                continue;
+            }
+
+            // -o[N] / -O[N] split windows; -p[N] opens tab pages, one window
+            // shown at a time; -d splits every file for diffing
+            if let Some(count) = arg.strip_prefix("-o").or_else(|| arg.strip_prefix("-O")) {
+                if count.is_empty() || count.chars().all(|c| c.is_ascii_digit()) {
+                    split = Some(count.parse().ok());
+                    continue;
+                }
+            }
+            if arg == "-d" {
+                split = Some(None);
+                continue;
+            }
+            if arg == "-R" {
+                read_only = true;
+                continue;
+            }
+
+            // Other options (including -p[N]) are not files
+            if arg.is_empty() || arg.starts_with('-') {
+                continue;
+            }
+
+            files.push(arg.clone());
         }
 
-        #[cfg(target_os = "macos")]
-        {
-            // macOS uses ps command to get process arguments
-            let output = Command::new("ps")
-                .args(&["-p", &pid.to_string(), "-o", "args="])
-                .output()
-                .ok()?;
-
-            let cmdline = String::from_utf8_lossy(&output.stdout);
-            Some(
-                cmdline
-                    .trim()
-                    .split_whitespace()
-                    .map(|s| s.to_string())
-                    .collect(),
-            )
-        }
-    }
-
-    /// Windows process command line retrieval
-    #[cfg(target_os = "windows")]
-    fn get_process_cmdline(&self, pid: u32) -> Option<Vec<String>> {
-        // Windows implementation - simplified version
-        // Could use WMI or PowerShell for full command line
-        let output = Command::new("wmic")
-            .args(&[
-                "process",
-                "where",
-                &format!("ProcessId={}", pid),
-                "get",
-                "CommandLine",
-                "/value",
-            ])
-            .output()
-            .ok()?;
+        let windows = match split {
+            Some(Some(count)) => count.max(1),
+            Some(None) => files.len().max(1),
+            None => 1,
+        };
+
+        let mut args = EditorArgs::new(files);
+        args.windows = windows;
+        args.read_only = read_only;
+        // A line jump applies to the first file Vim opens
+        if let Some(first) = args.files.first_mut() {
+            first.line_jump = line_jump;
+        }
+        args
+    }
+
+    /// Helix takes `file[:line[:col]]` arguments and `--vsplit`/`--hsplit`
+    fn helix_arguments(&self, args: &[String], cwd: &str) -> EditorArgs {
+        let mut files = Vec::new();
+        let mut split = false;
+        let mut args = args.iter();
 
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        for line in output_str.lines() {
-            if line.starts_with("CommandLine=") {
-                let cmdline = line.trim_start_matches("CommandLine=");
-                return Some(shell_words::split(cmdline).unwrap_or_default());
+        while let Some(arg) = args.next() {
+            if HELIX_OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+                args.next();
+                continue;
             }
+            if arg == "--vsplit" || arg == "--hsplit" {
+                split = true;
+                continue;
+            }
+            if arg.is_empty() || arg.starts_with('-') {
+                continue;
+            }
+
+            files.push(self.split_line_suffix(arg, cwd));
         }
 
-        None
+        let windows = if split { files.len().max(1) } else { 1 };
+        EditorArgs {
+            files,
+            windows,
+            read_only: false,
+        }
     }
 
-    fn extract_file_from_cmdline(&self, cmdline: &[String]) -> Option<FileInfo> {
-        if cmdline.is_empty() {
+    /// Kakoune takes a `+line[:col]` argument for the first file
+    fn kakoune_arguments(&self, args: &[String]) -> EditorArgs {
+        let mut files = Vec::new();
+        let mut line_jump = None;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if KAKOUNE_OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+                args.next();
+                continue;
+            }
+            if let Some(position) = arg.strip_prefix('+') {
+                line_jump = parse_position(position, ':');
+                continue;
+            }
+            if arg.is_empty() || arg.starts_with('-') {
+                continue;
+            }
+
+            files.push(arg.clone());
+        }
+
+        let mut args = EditorArgs::new(files);
+        if let Some(first) = args.files.first_mut() {
+            first.line_jump = line_jump;
+        }
+        args
+    }
+
+    /// Files in a running nvim's buffer list, queried over its RPC socket,
+    /// with the index of the current buffer. None for Vim, or an nvim whose
+    /// socket can't be reached, leaving its command line as the source.
+    fn nvim_buffer_files(&self, pid: u32, cmdline: &[String]) -> Option<(Vec<FileInfo>, usize)> {
+        if self.ide_type != SupportedIDE::Vim {
             return None;
         }
+        // Vim has no RPC socket, so only nvim (or a host embedding it) has one
+        let socket = neovim::find_socket(pid, cmdline, self.runtime_dir.as_deref().map(Path::new))?;
+
+        let mut current = 0;
+        let mut files = Vec::new();
+        for buffer in neovim::list_buffers(&socket)? {
+            // Skip terminals (term://...) and buffers for files not on disk
+            if !self.file_exists(&buffer.name) {
+                continue;
+            }
+            if buffer.is_current {
+                current = files.len();
+            }
+            files.push(FileInfo {
+                tab_index: Some(files.len()),
+                is_modified: buffer.is_modified,
+                source: FileSource::SessionState,
+                ..self.create_file_info(buffer.name, "")
+            });
+        }
+
+        if files.is_empty() {
+            None
+        } else {
+            Some((files, current))
+        }
+    }
+
+    /// Nano opens every file argument in its own buffer; a `+line[,col]`
+    /// argument applies to the file after it
+    fn nano_arguments(&self, args: &[String]) -> EditorArgs {
+        let mut files = Vec::new();
+        let mut line_jump = None;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if NANO_OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+                args.next();
+                continue;
+            }
+            if let Some(position) = arg.strip_prefix('+') {
+                // Also `+/string` or `+,col`, which name no line
+                line_jump = parse_position(position, ',');
+                continue;
+            }
+            if arg.is_empty() || arg.starts_with('-') {
+                continue;
+            }
+
+            files.push(FileArg {
+                path: arg.clone(),
+                line_jump: line_jump.take(),
+            });
+        }
+
+        EditorArgs {
+            files,
+            windows: 1,
+            read_only: false,
+        }
+    }
+
+    /// Line and column of each file in Nano's position history, by absolute path
+    fn nano_positions(&self) -> HashMap<String, (usize, usize)> {
+        let home = self.home_dir.clone().or_else(|| std::env::var("HOME").ok());
+        let Some(home) = home else {
+            return HashMap::new();
+        };
+        let home = Path::new(&home);
+
+        // Nano 8 keeps it under the XDG state directory, 4 to 7 the data
+        // directory, older versions ~/.nano
+        let mut candidates = Vec::new();
+        if self.home_dir.is_none() {
+            for var in ["XDG_STATE_HOME", "XDG_DATA_HOME"] {
+                if let Ok(dir) = std::env::var(var) {
+                    candidates.push(PathBuf::from(dir).join("nano"));
+                }
+            }
+        }
+        candidates.push(home.join(".local").join("state").join("nano"));
+        candidates.push(home.join(".local").join("share").join("nano"));
+        candidates.push(home.join(".nano"));
+
+        candidates
+            .into_iter()
+            .find_map(|dir| std::fs::read_to_string(dir.join("filepos_history")).ok())
+            .map(|content| parse_nano_positions(&content))
+            .unwrap_or_default()
+    }
+
+    /// Split a Helix `path:line[:col]` argument, unless the whole argument
+    /// names an existing file
+    fn split_line_suffix(&self, arg: &str, cwd: &str) -> FileArg {
+        let whole = FileArg {
+            path: arg.to_string(),
+            line_jump: None,
+        };
+        if self.file_exists(&self.absolute_path(arg.to_string(), cwd)) {
+            return whole;
+        }
+
+        let mut parts = arg.rsplitn(3, ':');
+        let last = parts.next();
+        let middle = parts.next();
+        let rest = parts.next();
+        let numeric = |s: Option<&str>| s.and_then(|s| s.parse::<usize>().ok());
+
+        match (rest, numeric(middle), numeric(last)) {
+            // path:line:col
+            (Some(path), Some(line), Some(column)) => FileArg {
+                path: path.to_string(),
+                line_jump: Some(LineJump::Line(line, Some(column))),
+            },
+            // path:line
+            (_, _, Some(line)) => match arg.rsplit_once(':') {
+                Some((path, _)) => FileArg {
+                    path: path.to_string(),
+                    line_jump: Some(LineJump::Line(line, None)),
+                },
+                None => whole,
+            },
+            _ => whole,
+        }
+    }
+
+    /// Number of lines in a file, i.e. the line `vim +` starts on
+    fn count_lines(&self, path: &str) -> Option<usize> {
+        let content = std::fs::read(path).ok()?;
+        let lines = content.split(|&b| b == b'\n').count();
+        // A trailing newline ends the last line rather than starting a new one
+        if content.ends_with(b"\n") {
+            Some(lines - 1)
+        } else {
+            Some(lines)
+        }
+        .map(|lines| lines.max(1))
+    }
+
+    /// A command-line path made absolute against the editor's working
+    /// directory `cwd`, or ours when it is unknown (empty)
+    fn absolute_path(&self, file_path: String, cwd: &str) -> String {
+        if file_path.starts_with('/') {
+            return file_path;
+        }
+        let base = if cwd.is_empty() {
+            std::env::current_dir().ok()
+        } else {
+            Some(PathBuf::from(cwd))
+        };
+        base.and_then(|base| base.join(&file_path).to_str().map(|s| s.to_string()))
+            .unwrap_or(file_path)
+    }
+
+    fn create_file_info(&self, file_path: String, cwd: &str) -> FileInfo {
+        // Not active until all editors are known; modification can't be seen
+        FileInfo::new(&self.absolute_path(file_path, cwd), FileSource::CommandLine)
+    }
+
+    /// Get the pane PIDs of the active pane in each attached tmux session
+    fn get_tmux_active_pane_pids(&self) -> Vec<u32> {
+        let output = match std::process::Command::new("tmux")
+            .args([
+                "list-panes",
+                "-a",
+                "-F",
+                "#{session_attached} #{window_active} #{pane_active} #{pane_pid}",
+            ])
+            .output()
+        {
+            Ok(output) if output.status.success() => output,
+            _ => return Vec::new(),
+        };
 
-        // Find file argument
-        let file_path = match self.ide_type {
-            SupportedIDE::Vim => {
-                // vim format: vim /path/to/file.txt
-                // nvim format: nvim /path/to/file.txt
-                // May have options: vim -n /path/to/file.txt
-                cmdline
-                    .iter()
-                    .skip(1) // Skip program name
-                    .find(|arg| !arg.starts_with('-') && !arg.is_empty())
-                    .cloned()
-            }
-            SupportedIDE::Nano => {
-                // nano format: nano /path/to/file.txt
-                // May have options: nano -w /path/to/file.txt
-                cmdline
-                    .iter()
-                    .skip(1) // Skip program name
-                    .find(|arg| !arg.starts_with('-') && !arg.is_empty())
-                    .cloned()
-            }
-            _ => None,
-        }?;
-
-        // Convert to absolute path
-        let absolute_path = if file_path.starts_with('/') {
-            file_path
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                match fields.as_slice() {
+                    [attached, "1", "1", pane_pid] if *attached != "0" => pane_pid.parse().ok(),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Pick which editor process is in the foreground, from the current
+    /// window focus and tmux panes
+    fn select_foreground_editor(&self, pids: &[u32]) -> Option<usize> {
+        foreground_editor(
+            pids,
+            crate::process::get_focused_window_pid(),
+            &self.get_tmux_active_pane_pids(),
+        )
+    }
+
+    /// The terminal emulator window the editor `pid` runs in
+    fn terminal_host(&self, pid: u32) -> Option<TerminalHost> {
+        let ancestors = crate::process::get_ancestor_pids(pid);
+        let (terminal_pid, name) = find_terminal_emulator(&ancestors, crate::process::get_process_name)?;
+        Some(TerminalHost {
+            name,
+            window_id: crate::process::get_window_id_by_pid(terminal_pid),
+        })
+    }
+
+    /// The application embedding a headless nvim, when there is one: the
+    /// process itself when it serves nvim's RPC under another name, or the
+    /// parent that started `nvim --embed` (e.g. a GUI or the VSCode Neovim
+    /// extension). The nvim TUI embeds its editor the same way, but runs in
+    /// a terminal, so has no host.
+    fn embedding_host(&self, process: &ProcessInfo, cmdline: &[String]) -> Option<TerminalHost> {
+        let (host_pid, name) = if !neovim::is_neovim(cmdline) && !self.ide_type.matches_process_name(&process.name) {
+            (process.pid, process.name.clone())
+        } else if cmdline.iter().any(|arg| arg == "--embed") {
+            let parent = crate::process::get_parent_pid(process.pid)?;
+            let name = crate::process::get_process_name(parent)?;
+            if self.ide_type.matches_process_name(&name) {
+                return None;
+            }
+            (parent, name)
         } else {
-            // Relative path, try to get current working directory
-            std::env::current_dir()
-                .ok()
-                .and_then(|cwd| cwd.join(&file_path).to_str().map(|s| s.to_string()))
-                .unwrap_or(file_path)
+            return None;
         };
 
-        let file_name = std::path::Path::new(&absolute_path)
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or(&absolute_path)
-            .to_string();
-
-        Some(FileInfo {
-            path: absolute_path,
-            name: file_name,
-            is_active: true,    // Terminal editors usually edit one file
-            is_modified: false, // Can't easily detect modification status
-            tab_index: Some(0),
-            project_name: None,
+        Some(TerminalHost {
+            name,
+            window_id: crate::process::get_window_id_by_pid(host_pid),
         })
     }
 
@@ -145,37 +570,147 @@ impl TerminalEditorDetector {
     }
 }
 
+/// Pick which of the editor `pids` is in the foreground.
+///
+/// An editor scores for being inside the process tree of the focused
+/// window's owner (`focused_pid`, the terminal emulator) and for being inside
+/// one of the `active_panes`. Returns the index of the single best-scoring
+/// editor, or None when focus can't be determined.
+fn foreground_editor(pids: &[u32], focused_pid: Option<u32>, active_panes: &[u32]) -> Option<usize> {
+    let scores: Vec<u32> = pids
+        .iter()
+        .map(|&pid| {
+            let ancestors = crate::process::get_ancestor_pids(pid);
+            let in_focused_window = focused_pid.is_some_and(|f| ancestors.contains(&f));
+            let in_active_pane = active_panes.iter().any(|p| ancestors.contains(p));
+            (in_focused_window as u32) * 2 + in_active_pane as u32
+        })
+        .collect();
+
+    let best = *scores.iter().max()?;
+    if best == 0 || scores.iter().filter(|&&s| s == best).count() > 1 {
+        return None;
+    }
+
+    scores.iter().position(|&s| s == best)
+}
+
+/// Process names of terminal emulators, as /proc/<pid>/comm shows them
+/// (truncated to 15 characters, hence "gnome-terminal-")
+const TERMINAL_EMULATORS: &[&str] = &[
+    "gnome-terminal-", "kgx", "kitty", "alacritty", "wezterm-gui", "konsole", "xterm", "uxterm",
+    "urxvt", "st", "foot", "terminator", "tilix", "xfce4-terminal", "lxterminal", "mate-terminal",
+    "terminology", "ghostty", "rio", "qterminal",
+];
+
+/// The nearest ancestor in `ancestors` (the editor first, then its parent,
+/// ...) that is a terminal emulator, with its name. Editors inside tmux or
+/// screen descend from the multiplexer server rather than a terminal, so
+/// have none.
+fn find_terminal_emulator(ancestors: &[u32], name_of: impl Fn(u32) -> Option<String>) -> Option<(u32, String)> {
+    ancestors.iter().skip(1).find_map(|&pid| {
+        let name = name_of(pid)?;
+        TERMINAL_EMULATORS
+            .contains(&name.to_lowercase().as_str())
+            .then_some((pid, name))
+    })
+}
+
+/// A `line[<separator>column]` position, as in `nano +12,5` or `kak +12:5`
+fn parse_position(position: &str, separator: char) -> Option<LineJump> {
+    let (line, column) = match position.split_once(separator) {
+        Some((line, column)) => (line, column.parse().ok()),
+        None => (position, None),
+    };
+    line.parse().ok().map(|line| LineJump::Line(line, column))
+}
+
+/// Parse Nano's `filepos_history`: one `path line column` entry per file,
+/// the most recently updated last. Paths may contain spaces.
+fn parse_nano_positions(content: &str) -> HashMap<String, (usize, usize)> {
+    content
+        .lines()
+        .filter_map(|entry| {
+            let mut fields = entry.rsplitn(3, ' ');
+            let column = fields.next()?.parse().ok()?;
+            let line = fields.next()?.parse().ok()?;
+            let path = fields.next()?;
+            Some((path.to_string(), (line, column)))
+        })
+        .collect()
+}
+
 impl IDEDetector for TerminalEditorDetector {
     fn ide_type(&self) -> SupportedIDE {
         self.ide_type
     }
 
     fn is_target_process(&self, process: &ProcessInfo) -> bool {
-        self.process_names.iter().any(|&name| {
-            let process_name = process.name.to_lowercase();
-            process_name == name || process_name.starts_with(name)
-        })
+        // Neovim embedded in another program still serves its default socket
+        self.ide_type.matches_process_name(&process.name)
+            || (self.ide_type == SupportedIDE::Vim
+                && neovim::default_socket(process.pid, self.runtime_dir.as_deref().map(Path::new)).is_some())
     }
 
     fn extract_files(
         &self,
         processes: &[ProcessInfo],
     ) -> DetectionResult<crate::types::DetectionResult> {
-        let mut open_files = Vec::new();
+        // (pid, files, windows, index of the file shown)
+        let mut editors: Vec<(u32, Vec<FileInfo>, usize, usize)> = Vec::new();
         let mut active_file = None;
+        let mut group_count = Some(1);
+        // The nvim TUI runs its editor as an `nvim --embed` child; once the
+        // child answers over RPC, the parent's command line adds nothing
+        let mut rpc_pids = Vec::new();
+        let mut rpc_parents = Vec::new();
+        let mut hosts = HashMap::new();
 
         for process in processes {
-            if let Some(cmdline) = self.get_process_cmdline(process.pid) {
-                if let Some(file_info) = self.extract_file_from_cmdline(&cmdline) {
-                    // Verify file actually exists
-                    if self.file_exists(&file_info.path) {
-                        if file_info.is_active {
-                            active_file = Some(file_info.path.clone());
-                        }
-                        open_files.push(file_info);
+            if let Some(cmdline) = crate::process::get_process_cmdline(process.pid) {
+                if let Some((files, current)) = self.nvim_buffer_files(process.pid, &cmdline) {
+                    rpc_pids.push(process.pid);
+                    rpc_parents.extend(crate::process::get_parent_pid(process.pid));
+                    if let Some(host) = self.embedding_host(process, &cmdline) {
+                        hosts.insert(process.pid, host);
                     }
+                    editors.push((process.pid, files, 1, current));
+                    continue;
                 }
+
+                let (files, windows) = self.extract_files_from_cmdline(&cmdline, &process.cwd);
+                if !files.is_empty() {
+                    editors.push((process.pid, files, windows, 0));
+                }
+            }
+        }
+        editors.retain(|(pid, _, _, _)| rpc_pids.contains(pid) || !rpc_parents.contains(pid));
+
+        // With several editors running (e.g. in tmux panes), only the one in the
+        // foreground is active; otherwise fall back to the last one found
+        let active_index = if editors.len() > 1 && self.focus_detection {
+            let editor_pids: Vec<u32> = editors.iter().map(|(pid, _, _, _)| *pid).collect();
+            self.select_foreground_editor(&editor_pids)
+        } else {
+            None
+        }
+        .or(editors.len().checked_sub(1));
+
+        let mut open_files = Vec::new();
+        for (index, (pid, mut files, windows, current)) in editors.into_iter().enumerate() {
+            let terminal = hosts.remove(&pid).or_else(|| self.terminal_host(pid));
+            for file in &mut files {
+                file.terminal = terminal.clone();
+            }
+
+            // Editors show the first file argument on startup (the top/left
+            // window when split); nvim tells us its current buffer
+            if Some(index) == active_index {
+                files[current].is_active = true;
+                active_file = Some(files[current].path.clone());
+                group_count = Some(windows);
             }
+            open_files.extend(files);
         }
 
         if open_files.is_empty() {
@@ -185,12 +720,393 @@ impl IDEDetector for TerminalEditorDetector {
         }
 
         Ok(crate::types::DetectionResult {
+            format_version: crate::types::FORMAT_VERSION,
             timestamp: chrono::Utc::now().to_rfc3339(),
             ide_name: self.display_name().to_string(),
             ide_version: None,
             active_file,
             open_files,
             project_path: None,
+            group_count,
+            project_language: None,
+            seq: None,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_target_process_matches_vim_variants_only() {
+        let detector = TerminalEditorDetector::new(SupportedIDE::Vim);
+        let cases = [
+            ("vim", true),
+            ("nvim", true),
+            ("gvim", true),
+            ("vim.basic", true),
+            ("vim.tiny", true),
+            ("vim.exe", true),
+            ("nvim.exe", true),
+            ("view", true),
+            ("ex", true),
+            ("vimdiff", false),
+            ("vimtutor", false),
+            ("nvim-qt", false),
+            ("neovide", false),
+            ("nano", false),
+            ("ed", false),
+        ];
+
+        for (name, expected) in cases {
+            let process = ProcessInfo::for_test(name, "");
+            assert_eq!(detector.is_target_process(&process), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn view_and_vim_r_open_files_read_only() {
+        let detector = TerminalEditorDetector::new(SupportedIDE::Vim);
+        let file = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        let cases: [(&[&str], bool); 4] = [
+            (&["view", file], true),
+            (&["/usr/bin/view", file], true),
+            (&["vim", "-R", file], true),
+            (&["vim", file], false),
+        ];
+
+        for (cmdline, expected) in cases {
+            let cmdline: Vec<String> = cmdline.iter().map(|arg| arg.to_string()).collect();
+            let (files, _) = detector.extract_files_from_cmdline(&cmdline, "");
+            assert_eq!(files.len(), 1, "{:?}", cmdline);
+            assert_eq!(files[0].read_only, expected, "{:?}", cmdline);
+        }
+    }
+
+    #[test]
+    fn relative_arguments_resolve_against_the_editor_working_directory() {
+        let detector = TerminalEditorDetector::new(SupportedIDE::Vim);
+        let src = concat!(env!("CARGO_MANIFEST_DIR"), "/src");
+        let cmdline: Vec<String> = ["vim", "lib.rs", "detectors/mod.rs"].iter().map(|arg| arg.to_string()).collect();
+
+        // Not where this test runs (the crate root), but where vim was started
+        let (files, _) = detector.extract_files_from_cmdline(&cmdline, src);
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, [format!("{}/lib.rs", src), format!("{}/detectors/mod.rs", src)]);
+
+        let helix = TerminalEditorDetector::new(SupportedIDE::Helix);
+        let cmdline: Vec<String> = ["hx", "lib.rs:12:5"].iter().map(|arg| arg.to_string()).collect();
+        let (files, _) = helix.extract_files_from_cmdline(&cmdline, src);
+        assert_eq!(files[0].path, format!("{}/lib.rs", src));
+        assert_eq!((files[0].cursor_line, files[0].cursor_column), (Some(12), Some(5)));
+    }
+
+    #[test]
+    fn vim_opens_every_file_argument_after_a_line_jump() {
+        let detector = TerminalEditorDetector::new(SupportedIDE::Vim);
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let (a, b, c) = (
+            format!("{}/Cargo.toml", dir),
+            format!("{}/README.md", dir),
+            format!("{}/Makefile", dir),
+        );
+        let cmdline: Vec<String> = ["vim", "+42", &a, "-c", "set nu", &b, &c]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+
+        let (files, windows) = detector.extract_files_from_cmdline(&cmdline, "");
+        let summary: Vec<(&str, Option<usize>, Option<usize>)> = files
+            .iter()
+            .map(|f| (f.path.as_str(), f.tab_index, f.cursor_line))
+            .collect();
+        // The jump applies to the first file, the one Vim shows
+        assert_eq!(
+            summary,
+            [(a.as_str(), Some(0), Some(42)), (b.as_str(), Some(1), None), (c.as_str(), Some(2), None)]
+        );
+        assert_eq!(windows, 1);
+    }
+
+    #[test]
+    fn plus_arguments_position_the_cursor_instead_of_naming_files() {
+        let dir = std::env::temp_dir().join(format!("ide-files-line-jump-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("notes.txt");
+        std::fs::write(&file, "one\ntwo\nthree\n").unwrap();
+        let file = file.to_string_lossy().into_owned();
+
+        let detector = TerminalEditorDetector::new(SupportedIDE::Vim);
+        let cursor = |jump: &str| {
+            let cmdline: Vec<String> = ["vim", jump, &file].iter().map(|arg| arg.to_string()).collect();
+            let (files, _) = detector.extract_files_from_cmdline(&cmdline, "");
+            assert_eq!(files.len(), 1, "{}", jump);
+            assert_eq!(files[0].path, file, "{}", jump);
+            (files[0].cursor_line, files[0].cursor_column)
+        };
+
+        assert_eq!(cursor("+42"), (Some(42), None));
+        // A pattern jump isn't resolved, but isn't a file either
+        assert_eq!(cursor("+/fn main"), (None, None));
+        // A bare + starts on the last line
+        assert_eq!(cursor("+"), (Some(3), None));
+        // +cmd runs an Ex command
+        assert_eq!(cursor("+set nu"), (None, None));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn vim_option_values_are_not_mistaken_for_files() {
+        let detector = TerminalEditorDetector::new(SupportedIDE::Vim);
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let (a, b, c) = (
+            format!("{}/Cargo.toml", dir),
+            format!("{}/README.md", dir),
+            format!("{}/Makefile", dir),
+        );
+        // Each value names an existing file, so only the option parsing keeps it out
+        let cases: [(Vec<&str>, Vec<&str>); 5] = [
+            (vec!["vim", &a, &b, &c], vec![&a, &b, &c]),
+            (vec!["vim", "-c", &c, "--cmd", &b, &a], vec![&a]),
+            (vec!["vim", "-S", &c, "-u", &b, "-i", "NONE", &a, "--", "-c"], vec![&a]),
+            (vec!["vim", "--servername", &b, "--startuptime", &c, &a], vec![&a]),
+            // -q's errorfile is optional: a following argument is a file to edit
+            (vec!["vim", "-q", &a, &b], vec![&a, &b]),
+        ];
+
+        for (cmdline, expected) in cases {
+            let cmdline: Vec<String> = cmdline.iter().map(|arg| arg.to_string()).collect();
+            let (files, _) = detector.extract_files_from_cmdline(&cmdline, "");
+            let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+            assert_eq!(paths, expected, "{:?}", cmdline);
+            let indexes: Vec<Option<usize>> = files.iter().map(|f| f.tab_index).collect();
+            assert_eq!(indexes, (0..expected.len()).map(Some).collect::<Vec<_>>(), "{:?}", cmdline);
+        }
+    }
+
+    #[test]
+    fn tab_and_split_flags_open_every_file_in_arglist_order() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let (a, b, c) = (
+            format!("{}/Cargo.toml", dir),
+            format!("{}/README.md", dir),
+            format!("{}/Makefile", dir),
+        );
+        let parse = |ide: SupportedIDE, cmdline: &[&str]| {
+            let cmdline: Vec<String> = cmdline.iter().map(|arg| arg.to_string()).collect();
+            let (files, windows) = TerminalEditorDetector::new(ide).extract_files_from_cmdline(&cmdline, "");
+            let files: Vec<(String, Option<usize>)> = files.into_iter().map(|f| (f.path, f.tab_index)).collect();
+            (files, windows)
+        };
+        let tabs = |paths: &[&String]| -> Vec<(String, Option<usize>)> {
+            paths.iter().enumerate().map(|(i, path)| (path.to_string(), Some(i))).collect()
+        };
+
+        // Tab pages show one window at a time; splits show every file
+        assert_eq!(parse(SupportedIDE::Vim, &["vim", "-p", &a, &b, &c]), (tabs(&[&a, &b, &c]), 1));
+        assert_eq!(parse(SupportedIDE::Vim, &["vim", "-o", &a, &b]), (tabs(&[&a, &b]), 2));
+        assert_eq!(parse(SupportedIDE::Vim, &["vim", "-O3", &a]), (tabs(&[&a]), 3));
+        assert_eq!(parse(SupportedIDE::Vim, &["vim", "-d", &a, &b]), (tabs(&[&a, &b]), 2));
+
+        assert_eq!(parse(SupportedIDE::Helix, &["hx", "--vsplit", &a, &b]), (tabs(&[&a, &b]), 2));
+        assert_eq!(parse(SupportedIDE::Helix, &["hx", "-c", &c, &a, &b]), (tabs(&[&a, &b]), 1));
+        let (files, _) = TerminalEditorDetector::new(SupportedIDE::Helix)
+            .extract_files_from_cmdline(&["hx".to_string(), format!("{}:7", b)], "");
+        assert_eq!((files[0].path.as_str(), files[0].cursor_line), (b.as_str(), Some(7)));
+
+        assert_eq!(parse(SupportedIDE::Kakoune, &["kak", "-s", "work", &a, &b]), (tabs(&[&a, &b]), 1));
+        let (files, _) = TerminalEditorDetector::new(SupportedIDE::Kakoune)
+            .extract_files_from_cmdline(&["kak", "+9:4", &a, &b].map(String::from), "");
+        let cursors: Vec<_> = files.iter().map(|f| (f.cursor_line, f.cursor_column)).collect();
+        assert_eq!(cursors, [(Some(9), Some(4)), (None, None)]);
+    }
+
+    #[test]
+    fn ed_opens_its_file_argument() {
+        let detector = TerminalEditorDetector::new(SupportedIDE::Ed);
+        let file = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        let cmdline: Vec<String> = ["ed", "-p", "*", file].iter().map(|arg| arg.to_string()).collect();
+
+        let (files, _) = detector.extract_files_from_cmdline(&cmdline, "");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, file);
+        assert!(!files[0].read_only);
+    }
+
+    #[test]
+    fn is_target_process_matches_nano_only() {
+        let detector = TerminalEditorDetector::new(SupportedIDE::Nano);
+        let cases = [
+            ("nano", true),
+            ("nano.exe", true),
+            ("nanorc-check", false),
+            ("rnano", false),
+            ("vim", false),
+        ];
+
+        for (name, expected) in cases {
+            let process = ProcessInfo::for_test(name, "");
+            assert_eq!(detector.is_target_process(&process), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn nano_opens_every_file_argument_in_order() {
+        let detector = TerminalEditorDetector::new(SupportedIDE::Nano)
+            .with_home_dir(Some("/nonexistent".to_string()));
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let (a, b, c) = (
+            format!("{}/Cargo.toml", dir),
+            format!("{}/README.md", dir),
+            format!("{}/Makefile", dir),
+        );
+        let cmdline: Vec<String> = ["nano", "-F", "-T", "4", &a, "--rcfile", "/etc/nanorc", &b, "+3,2", &c]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+
+        let (files, windows) = detector.extract_files_from_cmdline(&cmdline, "");
+        let summary: Vec<_> = files
+            .iter()
+            .map(|f| (f.path.as_str(), f.tab_index, f.cursor_line, f.cursor_column))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (a.as_str(), Some(0), None, None),
+                (b.as_str(), Some(1), None, None),
+                (c.as_str(), Some(2), Some(3), Some(2)),
+            ]
+        );
+        assert_eq!(windows, 1);
+    }
+
+    #[test]
+    fn nano_cursor_comes_from_the_position_history() {
+        let home = std::env::temp_dir().join(format!("ide-files-nano-{}", std::process::id()));
+        let history_dir = home.join(".local").join("share").join("nano");
+        std::fs::create_dir_all(&history_dir).unwrap();
+
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let (a, b) = (format!("{}/Cargo.toml", dir), format!("{}/README.md", dir));
+        std::fs::write(
+            history_dir.join("filepos_history"),
+            format!("{a} 4 1\n/elsewhere/with space.txt 9 2\n{b} 20 5\n{a} 12 3\n"),
+        )
+        .unwrap();
+
+        let detector = TerminalEditorDetector::new(SupportedIDE::Nano)
+            .with_home_dir(home.to_str().map(String::from));
+        let cmdline: Vec<String> = ["nano", &a, "+7", &b].iter().map(|arg| arg.to_string()).collect();
+        let (files, _) = detector.extract_files_from_cmdline(&cmdline, "");
+        std::fs::remove_dir_all(&home).unwrap();
+
+        // The latest history entry wins; an explicit +line beats the history
+        let cursors: Vec<(Option<usize>, Option<usize>)> =
+            files.iter().map(|f| (f.cursor_line, f.cursor_column)).collect();
+        assert_eq!(cursors, [(Some(12), Some(3)), (Some(7), None)]);
+        assert_eq!(parse_nano_positions("/elsewhere/with space.txt 9 2")["/elsewhere/with space.txt"], (9, 2));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn only_the_vim_in_the_active_tmux_pane_is_in_the_foreground() {
+        // Two vims in their own panes, both under this process (the terminal)
+        let mut panes: Vec<std::process::Child> = (0..2)
+            .map(|_| std::process::Command::new("sleep").arg("30").spawn().unwrap())
+            .collect();
+        let vims: Vec<ProcessInfo> = panes
+            .iter()
+            .map(|pane| ProcessInfo {
+                pid: pane.id(),
+                ..ProcessInfo::for_test("vim", "/usr/bin/vim")
+            })
+            .collect();
+        let pids: Vec<u32> = vims.iter().map(|vim| vim.pid).collect();
+        let terminal = std::process::id();
+
+        assert_eq!(foreground_editor(&pids, None, &[pids[1]]), Some(1));
+        assert_eq!(foreground_editor(&pids, Some(terminal), &[pids[0]]), Some(0));
+        // The focused terminal alone can't tell them apart
+        assert_eq!(foreground_editor(&pids, Some(terminal), &[]), None);
+        assert_eq!(foreground_editor(&pids, None, &[]), None);
+
+        for pane in &mut panes {
+            let _ = pane.kill();
+            let _ = pane.wait();
+        }
+    }
+
+    #[test]
+    fn terminal_emulator_is_the_nearest_terminal_ancestor() {
+        // kitty (100) → bash (200) → vim (300); gnome-terminal-server (110) →
+        // zsh (210) → nano (310); tmux server (120) → bash (220) → hx (320)
+        let names: HashMap<u32, &str> = [
+            (1, "systemd"),
+            (100, "kitty"),
+            (200, "bash"),
+            (300, "vim"),
+            (110, "gnome-terminal-"),
+            (210, "zsh"),
+            (310, "nano"),
+            (120, "tmux: server"),
+            (220, "bash"),
+            (320, "hx"),
+        ]
+        .into_iter()
+        .collect();
+        let name_of = |pid: u32| names.get(&pid).map(|name| name.to_string());
+
+        assert_eq!(
+            find_terminal_emulator(&[300, 200, 100, 1], name_of),
+            Some((100, "kitty".to_string()))
+        );
+        assert_eq!(
+            find_terminal_emulator(&[310, 210, 110, 1], name_of),
+            Some((110, "gnome-terminal-".to_string()))
+        );
+        assert_eq!(find_terminal_emulator(&[320, 220, 120, 1], name_of), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn embedded_nvim_is_queried_through_its_default_socket() {
+        use std::os::unix::net::UnixListener;
+
+        // This test process stands in for a host application embedding nvim
+        let pid = std::process::id();
+        let root = std::env::temp_dir().join(format!("ide-files-embedded-nvim-{}", pid));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("runtime")).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.rs"), "").unwrap();
+        std::fs::write(root.join("src/b.rs"), "").unwrap();
+        let src = root.join("src").to_string_lossy().into_owned();
+
+        let listener = UnixListener::bind(root.join(format!("runtime/nvim.{}.0", pid))).unwrap();
+        let server = {
+            let src = src.clone();
+            std::thread::spawn(move || neovim::tests::serve_fake_nvim(listener, &src))
+        };
+
+        let detector = TerminalEditorDetector::new(SupportedIDE::Vim)
+            .with_runtime_dir(Some(root.join("runtime").to_string_lossy().into_owned()));
+        let host = ProcessInfo::new(pid, "code", "/usr/share/code/code");
+        assert!(detector.is_target_process(&host));
+        assert!(!TerminalEditorDetector::new(SupportedIDE::Nano).is_target_process(&host));
+
+        let result = detector.extract_files(&[host]);
+        server.join().unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let result = result.unwrap();
+        let paths: Vec<&str> = result.open_files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, [format!("{}/a.rs", src), format!("{}/b.rs", src)]);
+        assert_eq!(result.active_file, Some(format!("{}/a.rs", src)));
+        assert!(result.open_files[1].is_modified);
+        let host = result.open_files[0].terminal.as_ref().map(|host| host.name.as_str());
+        assert_eq!(host, Some("code"));
+    }
+}
@@ -1,98 +1,184 @@
 use crate::detector::{DetectionResult, IDEDetector};
-use crate::types::{FileInfo, ProcessInfo, SupportedIDE};
-use rusqlite::{Connection, Result as SqliteResult};
+use crate::types::{Comparison, FileInfo, FileSource, ProcessInfo, SupportedIDE, TabKind};
+use rusqlite::{Connection, OpenFlags, Result as SqliteResult};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 
-/// Visual Studio Code detector
+/// `ItemTable` keys that have held the editor layout across VSCode versions,
+/// tried in order
+const EDITOR_STATE_KEYS: &[&str] = &[
+    "memento/workbench.parts.editor",
+    "memento/workbench.editors.files.textFileEditor",
+    "memento/workbench.editor.parts",
+];
+
+/// `ItemTable` key of the text editors' saved view state (cursor, scroll, folding)
+const TEXT_EDITOR_STATE_KEY: &str = "memento/workbench.editors.files.textFileEditor";
+
+/// Saved cursor position (1-based line and column) by file path, then by editor group id
+type EditorCursors = HashMap<String, HashMap<String, (usize, usize)>>;
+
+/// How long to wait on a VSCode write in progress before giving up on its database
+const DB_BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Editor state read from a VSCode workspace database
+struct EditorSession {
+    files: Vec<FileInfo>,
+    workspace: Option<String>,
+    group_count: Option<usize>,
+}
+
+/// Workspace and files named on a VSCode command line
+struct LaunchArgs {
+    workspace: String,
+    /// `.code-workspace` descriptor the window was opened with, if any
+    workspace_file: Option<String>,
+    files: Vec<FileInfo>,
+}
+
+/// Visual Studio Code detector, also used for VSCode forks that keep the
+/// same state.vscdb layout under their own data directory
 pub struct VSCodeDetector {
-    process_names: Vec<&'static str>,
+    ide_type: SupportedIDE,
+    /// Directory under `~/.config` holding `User/workspaceStorage`
+    data_dir_name: &'static str,
+    /// macOS app bundle (lowercase) whose Electron binary is this editor
+    app_bundle: &'static str,
+    home_dir: Option<String>,
+    config_dir: Option<String>,
+    /// Keep every spelling of a file instead of listing it once
+    raw: bool,
+}
+
+impl Default for VSCodeDetector {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl VSCodeDetector {
     pub fn new() -> Self {
         Self {
-            process_names: vec![
-                "code", 
-                "code-oss", 
-                "codium", 
-                "code-insiders",
-                "Code",
-                "Code.exe",
-                "code.exe"
-            ],
+            ide_type: SupportedIDE::VSCode,
+            data_dir_name: "Code",
+            app_bundle: "visual studio code.app/",
+            home_dir: None,
+            config_dir: None,
+            raw: false,
         }
     }
 
-    /// Get process command line arguments to find workspace/files
-    #[cfg(any(target_os = "linux", target_os = "macos"))]
-    fn get_process_cmdline(&self, pid: u32) -> Option<Vec<String>> {
-        #[cfg(target_os = "linux")]
-        {
-            let cmdline_path = format!("/proc/{}/cmdline", pid);
-            std::fs::read_to_string(&cmdline_path).ok().map(|content| {
-                content
-                    .split('\0')
-                    .filter(|s| !s.is_empty())
-                    .map(|s| s.to_string())
-                    .collect()
-            })
+    /// VSCode Insiders, which runs side by side with stable VSCode under its
+    /// own executable and data directory
+    pub fn insiders() -> Self {
+        Self {
+            ide_type: SupportedIDE::VSCodeInsiders,
+            data_dir_name: "Code - Insiders",
+            app_bundle: "visual studio code - insiders.app/",
+            ..Self::new()
         }
+    }
 
-        #[cfg(target_os = "macos")]
-        {
-            let output = std::process::Command::new("ps")
-                .args(&["-p", &pid.to_string(), "-o", "args="])
-                .output()
-                .ok()?;
-
-            let cmdline = String::from_utf8_lossy(&output.stdout);
-            Some(
-                cmdline
-                    .trim()
-                    .split_whitespace()
-                    .map(|s| s.to_string())
-                    .collect(),
-            )
-        }
-    }
-
-    /// Windows process command line retrieval
-    #[cfg(target_os = "windows")]
-    fn get_process_cmdline(&self, pid: u32) -> Option<Vec<String>> {
-        let output = std::process::Command::new("wmic")
-            .args(&[
-                "process",
-                "where",
-                &format!("ProcessId={}", pid),
-                "get",
-                "CommandLine",
-                "/value",
-            ])
-            .output()
-            .ok()?;
-
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        for line in output_str.lines() {
-            if line.starts_with("CommandLine=") {
-                let cmdline = line.trim_start_matches("CommandLine=");
-                return Some(shell_words::split(cmdline).unwrap_or_default());
-            }
+    /// Cursor, a VSCode fork
+    pub fn cursor() -> Self {
+        Self {
+            ide_type: SupportedIDE::Cursor,
+            data_dir_name: "Cursor",
+            app_bundle: "cursor.app/",
+            ..Self::new()
         }
+    }
 
-        None
+    /// Read another user's editor state (None keeps `$HOME`)
+    pub fn with_home_dir(mut self, home_dir: Option<String>) -> Self {
+        self.home_dir = home_dir;
+        self
+    }
+
+    /// Read state from a VSCode user data directory (as given to VSCode's
+    /// `--user-data-dir`) instead of `~/.config/Code` (or the fork's equivalent)
+    pub fn with_config_dir(mut self, config_dir: Option<String>) -> Self {
+        self.config_dir = config_dir;
+        self
+    }
+
+    /// List files as the command line and session name them, without
+    /// merging different spellings of the same file
+    pub fn with_raw(mut self, raw: bool) -> Self {
+        self.raw = raw;
+        self
+    }
+
+    fn home_dir(&self) -> Option<String> {
+        self.home_dir.clone().or_else(|| env::var("HOME").ok())
+    }
+
+    /// The user data directory, `--user-data-dir` or `~/.config/Code` (or the fork's equivalent)
+    fn user_data_dir(&self) -> Option<String> {
+        match &self.config_dir {
+            Some(config_dir) => Some(config_dir.clone()),
+            None => self
+                .home_dir()
+                .map(|home_dir| format!("{}/.config/{}", home_dir, self.data_dir_name)),
+        }
+    }
+
+    /// Directory holding one state.vscdb per workspace
+    fn workspace_storage_dir(&self) -> Option<String> {
+        self.user_data_dir().map(|data_dir| format!("{}/User/workspaceStorage", data_dir))
+    }
+
+    /// Directory of hot-exit backups, one subdirectory per window
+    fn backups_dir(&self) -> Option<String> {
+        self.user_data_dir().map(|data_dir| format!("{}/Backups", data_dir))
+    }
+
+    /// Files with unsaved changes in any window, as `normalize_path` spells
+    /// them. VSCode backs up a dirty editor to `Backups/<window>/file/<hash>`,
+    /// headed by the file's URI, and deletes the backup once it is saved or
+    /// reverted.
+    fn unsaved_paths(&self) -> HashSet<String> {
+        let Some(backups_dir) = self.backups_dir() else {
+            return HashSet::new();
+        };
+        let Ok(windows) = fs::read_dir(backups_dir) else {
+            return HashSet::new();
+        };
+
+        let backups = windows
+            .flatten()
+            .filter_map(|window| fs::read_dir(window.path().join("file")).ok())
+            .flatten()
+            .flatten();
+        backups
+            .filter_map(|backup| {
+                // "file:///src/main.rs {\"mtime\":...}" (bare URI before VSCode 1.50), then the contents
+                let mut header = Vec::new();
+                BufReader::new(fs::File::open(backup.path()).ok()?)
+                    .read_until(b'\n', &mut header)
+                    .ok()?;
+                let header = String::from_utf8_lossy(&header);
+                let uri = header.split([' ', '\n']).next()?;
+                uri.starts_with("file://").then(|| self.decode_vscode_uri(uri))?
+            })
+            .map(|path| self.normalize_path(&path))
+            .collect()
     }
 
     /// Extract workspace and opened files from command line
-    fn extract_vscode_info(&self, cmdline: &[String]) -> Option<(String, Vec<FileInfo>)> {
+    fn extract_vscode_info(&self, cmdline: &[String]) -> Option<LaunchArgs> {
         if cmdline.is_empty() {
             return None;
         }
 
         let mut workspace_path = None;
+        let mut workspace_file = None;
         let mut files = Vec::new();
+        let mut comparisons = 0;
         
         // Parse VSCode command line arguments
         // Common formats:
@@ -102,6 +188,11 @@ impl VSCodeDetector {
         // 2. Direct file mode:
         //    code /path/to/file1.txt /path/to/file2.txt
         //    code --file-uri file:///path/to/file.txt
+        // 3. Multi-root workspace mode:
+        //    code /path/to/project.code-workspace
+        // 4. Comparison mode:
+        //    code --diff left.txt right.txt
+        //    code --merge input1 input2 base result
 
         let mut i = 1; // Skip executable name
         while i < cmdline.len() {
@@ -115,7 +206,7 @@ impl VSCodeDetector {
                         if arg.starts_with("--folder-uri") {
                             workspace_path = Some(path);
                         } else {
-                            files.push(self.create_file_info(&path, false));
+                            files.push(self.create_file_info(&path, false, FileSource::CommandLine));
                         }
                     }
                 } else if i + 1 < cmdline.len() {
@@ -125,11 +216,39 @@ impl VSCodeDetector {
                         if arg.starts_with("--folder-uri") {
                             workspace_path = Some(path);
                         } else {
-                            files.push(self.create_file_info(&path, false));
+                            files.push(self.create_file_info(&path, false, FileSource::CommandLine));
                         }
                     }
                 }
-            } else if !arg.starts_with('-') && arg.contains('/') {
+            } else if let Some((scheme, roles)) = comparison_roles(arg) {
+                // `--diff left right` and `--merge input1 input2 base result`
+                // open a single comparison editor, not a tab per operand
+                let operands: Vec<&String> = cmdline[i + 1..]
+                    .iter()
+                    .take(roles.len())
+                    .take_while(|operand| !operand.starts_with('-'))
+                    .collect();
+                i += operands.len();
+
+                let group = comparisons;
+                comparisons += 1;
+                for (operand, role) in operands.into_iter().zip(roles) {
+                    let path = operand.strip_prefix("file://").unwrap_or(operand);
+                    if !Path::new(path).is_file() {
+                        continue;
+                    }
+                    // The editable side is the one with focus: the right of a
+                    // diff, the result of a merge
+                    let is_active = group == 0 && (*role == "right" || *role == "result");
+                    let mut file = self.create_file_info(path, is_active, FileSource::CommandLine);
+                    file.comparison = Some(Comparison {
+                        scheme: scheme.to_string(),
+                        group,
+                        role: role.to_string(),
+                    });
+                    files.push(file);
+                }
+            } else if !arg.starts_with('-') && (arg.contains('/') || arg.ends_with(".code-workspace")) {
                 // Regular file/directory path
                 let path = if arg.starts_with("file://") {
                     arg.strip_prefix("file://").unwrap_or(arg).to_string()
@@ -139,6 +258,7 @@ impl VSCodeDetector {
 
                 // Skip VS Code extension and internal files
                 if path.contains("/.vscode/extensions/") || 
+                   path.contains("/.cursor/extensions/") ||
                    path.contains("/resources/app/extensions/") ||
                    path.contains("/CachedExtension") ||
                    path.contains("node_modules") ||
@@ -149,8 +269,15 @@ impl VSCodeDetector {
 
                 if Path::new(&path).is_dir() {
                     workspace_path = Some(path);
+                } else if path.ends_with(".code-workspace") && Path::new(&path).is_file() {
+                    // A workspace descriptor, not an open document: its first
+                    // folder is the project root
+                    if let Some(root) = self.parse_code_workspace(&path).into_iter().next() {
+                        workspace_path = Some(root);
+                    }
+                    workspace_file = Some(path);
                 } else if Path::new(&path).exists() {
-                    files.push(self.create_file_info(&path, false));
+                    files.push(self.create_file_info(&path, false, FileSource::CommandLine));
                 }
             }
             
@@ -161,41 +288,123 @@ impl VSCodeDetector {
         // This avoids duplicate file detection
 
         // If we have workspace or files, return them
-        if workspace_path.is_some() || !files.is_empty() {
-            Some((workspace_path.unwrap_or_default(), files))
+        if workspace_path.is_some() || workspace_file.is_some() || !files.is_empty() {
+            Some(LaunchArgs {
+                workspace: workspace_path.unwrap_or_default(),
+                workspace_file,
+                files,
+            })
         } else {
             None
         }
     }
 
-    /// Decode VSCode URI (file:// format)
+    /// Read the folder roots of a `.code-workspace` file, resolving relative
+    /// paths against the file's directory
+    fn parse_code_workspace(&self, workspace_file: &str) -> Vec<String> {
+        let content = match fs::read_to_string(workspace_file) {
+            Ok(content) => content,
+            Err(_) => return Vec::new(),
+        };
+
+        let json: Value = match serde_json::from_str(&strip_jsonc(&content)) {
+            Ok(json) => json,
+            Err(_) => return Vec::new(),
+        };
+
+        let base_dir = Path::new(workspace_file).parent().unwrap_or(Path::new("/"));
+
+        json.get("folders")
+            .and_then(|v| v.as_array())
+            .map(|folders| {
+                folders
+                    .iter()
+                    .filter_map(|folder| {
+                        if let Some(path) = folder.get("path").and_then(|v| v.as_str()) {
+                            let path = Path::new(path);
+                            let full_path = if path.is_absolute() { path.to_path_buf() } else { base_dir.join(path) };
+                            Some(full_path.to_string_lossy().to_string())
+                        } else {
+                            folder.get("uri").and_then(|v| v.as_str()).and_then(|uri| self.decode_vscode_uri(uri))
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Turn a `file://` or `vscode-remote://` URI into a filesystem path:
+    /// scheme and host dropped, `%XX` escapes decoded, and Windows drive
+    /// paths (`file:///c%3A/src`) given back as `c:\src`. Anything else is
+    /// taken to be a path already.
     fn decode_vscode_uri(&self, uri: &str) -> Option<String> {
-        if uri.starts_with("file://") {
-            Some(uri.strip_prefix("file://").unwrap_or(uri).to_string())
-        } else {
-            Some(uri.to_string())
+        let Some(rest) = uri.strip_prefix("file://").or_else(|| uri.strip_prefix("vscode-remote://")) else {
+            return Some(uri.to_string());
+        };
+        // A literal ? or # starts the query or fragment; in a path they are escaped
+        let rest = rest.split(['?', '#']).next().unwrap_or_default();
+        // The authority (a UNC server, or a remote such as ssh-remote+host) runs to the first slash
+        let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        let path = percent_decode(path);
+
+        let bytes = path.as_bytes();
+        if bytes.len() >= 3 && bytes[0] == b'/' && bytes[1].is_ascii_alphabetic() && bytes[2] == b':' {
+            return Some(path[1..].replace('/', "\\"));
+        }
+        if uri.starts_with("file://") && !host.is_empty() && host != "localhost" {
+            return Some(format!("\\\\{}{}", host, path.replace('/', "\\")));
         }
+        Some(path)
+    }
+
+    /// Reduce a path to a comparable form: URI prefix and percent-encoding
+    /// removed, trailing slashes dropped and symlinks resolved when it exists
+    fn normalize_path(&self, path: &str) -> String {
+        let path = path.strip_prefix("file://").unwrap_or(path);
+        let decoded = percent_decode(path);
+        let trimmed = match decoded.trim_end_matches('/') {
+            "" => "/",
+            trimmed => trimmed,
+        };
+
+        fs::canonicalize(trimmed)
+            .ok()
+            .and_then(|p| p.to_str().map(|s| s.to_string()))
+            .unwrap_or_else(|| trimmed.to_string())
     }
 
     /// Try to get opened files from VSCode workspace state database
-    fn get_vscode_recent_files(&self, workspace_path: &str) -> Result<(Vec<FileInfo>, Option<String>), std::io::Error> {
-        // First try to get files from VSCode workspace database
-        if let Ok((files, detected_workspace)) = self.get_vscode_session_files(workspace_path) {
-            if !files.is_empty() {
-                return Ok((files, detected_workspace));
+    fn get_vscode_recent_files(&self, workspace_path: &str, workspace_file: Option<&str>) -> Result<EditorSession, std::io::Error> {
+        // First try to get files from VSCode workspace database. Multi-root
+        // windows are keyed by their descriptor in workspaceStorage.
+        let session_error = match self.get_vscode_session_files(workspace_file.unwrap_or(workspace_path)) {
+            Ok(session) if !session.files.is_empty() => {
+                return Ok(session);
             }
-        }
+            Ok(_) => None,
+            Err(e) => Some(e),
+        };
 
         // Fallback to heuristic method
-        self.get_vscode_files_heuristic(workspace_path).map(|files| (files, None))
+        let files = self.get_vscode_files_heuristic(workspace_path)?;
+
+        // Report schema drift rather than silently returning nothing
+        match session_error {
+            Some(e) if files.is_empty() && e.kind() == std::io::ErrorKind::Unsupported => Err(e),
+            _ => Ok(EditorSession {
+                files,
+                workspace: None,
+                group_count: None,
+            }),
+        }
     }
 
     /// Get VSCode session files from SQLite database
-    fn get_vscode_session_files(&self, workspace_path: &str) -> Result<(Vec<FileInfo>, Option<String>), std::io::Error> {
-        let home_dir = env::var("HOME").map_err(|e| std::io::Error::new(std::io::ErrorKind::NotFound, e))?;
-        
+    fn get_vscode_session_files(&self, workspace_path: &str) -> Result<EditorSession, std::io::Error> {
         // Find VSCode workspace storage directory
-        let workspace_storage_dir = format!("{}/.config/Code/User/workspaceStorage", home_dir);
+        let workspace_storage_dir = self
+            .workspace_storage_dir()
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "HOME is not set"))?;
         
         // Try to find workspace ID, but if not found, try all workspace directories
         if let Ok(workspace_id) = self.get_workspace_id(workspace_path, &workspace_storage_dir) {
@@ -203,9 +412,9 @@ impl VSCodeDetector {
             let workspace_json_path = format!("{}/{}/workspace.json", workspace_storage_dir, workspace_id);
             
             if Path::new(&db_path).exists() {
-                let files = self.parse_vscode_database(&db_path)?;
-                let detected_workspace = self.extract_workspace_from_json(&workspace_json_path);
-                return Ok((files, detected_workspace));
+                let mut session = self.parse_vscode_database(&db_path)?;
+                session.workspace = self.extract_workspace_from_json(&workspace_json_path);
+                return Ok(session);
             }
         }
         
@@ -227,7 +436,9 @@ impl VSCodeDetector {
     }
 
     /// Scan all VSCode workspace directories for editor sessions
-    fn scan_all_vscode_sessions(&self, storage_dir: &str) -> Result<(Vec<FileInfo>, Option<String>), std::io::Error> {
+    fn scan_all_vscode_sessions(&self, storage_dir: &str) -> Result<EditorSession, std::io::Error> {
+        let mut schema_error = None;
+
         if let Ok(entries) = fs::read_dir(storage_dir) {
             // Get the most recently modified workspace (likely the active one)
             let mut workspace_dirs: Vec<_> = entries
@@ -248,38 +459,46 @@ impl VSCodeDetector {
                 let workspace_json_path = workspace_dir.path().join("workspace.json");
                 
                 if db_path.exists() {
-                    if let Ok(files) = self.parse_vscode_database(&db_path.to_string_lossy()) {
-                        if !files.is_empty() {
-                            let detected_workspace = self.extract_workspace_from_json(&workspace_json_path.to_string_lossy());
-                            return Ok((files, detected_workspace));
+                    match self.parse_vscode_database(&db_path.to_string_lossy()) {
+                        Ok(mut session) if !session.files.is_empty() => {
+                            session.workspace = self.extract_workspace_from_json(&workspace_json_path.to_string_lossy());
+                            return Ok(session);
                         }
+                        Err(e) if e.kind() == std::io::ErrorKind::Unsupported => schema_error = Some(e),
+                        _ => {}
                     }
                 }
             }
         }
         
-        Err(std::io::Error::new(std::io::ErrorKind::NotFound, "No active VSCode sessions found"))
+        Err(schema_error.unwrap_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "No active VSCode sessions found")
+        }))
     }
 
     /// Find workspace ID from VSCode storage directory
     fn get_workspace_id(&self, workspace_path: &str, storage_dir: &str) -> Result<String, std::io::Error> {
-        let workspace_uri = format!("file://{}", workspace_path);
+        // An empty path would make the URI a prefix of every workspace's,
+        // and an unquoted one a prefix of sibling folders ("/a/app-old")
+        let workspace_path = workspace_path.trim_end_matches('/');
+        if workspace_path.is_empty() {
+            return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "No workspace path to look up"));
+        }
+        let workspace_uri = format!("\"file://{}\"", workspace_path);
         
         // Look through workspace storage directories to find matching workspace
         if let Ok(entries) = fs::read_dir(storage_dir) {
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let workspace_dir = entry.path();
-                    if workspace_dir.is_dir() {
-                        // Check workspace.json for matching URI
-                        let workspace_json = workspace_dir.join("workspace.json");
-                        if workspace_json.exists() {
-                            if let Ok(content) = fs::read_to_string(&workspace_json) {
-                                if content.contains(&workspace_uri) {
-                                    if let Some(dir_name) = workspace_dir.file_name() {
-                                        if let Some(name_str) = dir_name.to_str() {
-                                            return Ok(name_str.to_string());
-                                        }
+            for entry in entries.flatten() {
+                let workspace_dir = entry.path();
+                if workspace_dir.is_dir() {
+                    // Check workspace.json for matching URI
+                    let workspace_json = workspace_dir.join("workspace.json");
+                    if workspace_json.exists() {
+                        if let Ok(content) = fs::read_to_string(&workspace_json) {
+                            if content.contains(&workspace_uri) {
+                                if let Some(dir_name) = workspace_dir.file_name() {
+                                    if let Some(name_str) = dir_name.to_str() {
+                                        return Ok(name_str.to_string());
                                     }
                                 }
                             }
@@ -293,75 +512,174 @@ impl VSCodeDetector {
     }
 
     /// Parse VSCode SQLite database for editor state
-    fn parse_vscode_database(&self, db_path: &str) -> Result<Vec<FileInfo>, std::io::Error> {
-        let conn = Connection::open(db_path)
-            .map_err(|e| {
-                std::io::Error::new(std::io::ErrorKind::Other, e)
-            })?;
-
-        let mut stmt = conn.prepare("SELECT value FROM ItemTable WHERE key = 'memento/workbench.parts.editor'")
-            .map_err(|e| {
-                std::io::Error::new(std::io::ErrorKind::Other, e)
-            })?;
-
-        let rows: SqliteResult<Vec<String>> = stmt.query_map([], |row| {
-            Ok(row.get(0)?)
-        }).and_then(|mapped_rows| mapped_rows.collect());
-
-        match rows {
-            Ok(values) if !values.is_empty() => {
-                let editor_state: Value = serde_json::from_str(&values[0])
-                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-                
-                self.parse_editor_state(editor_state)
+    fn parse_vscode_database(&self, db_path: &str) -> Result<EditorSession, std::io::Error> {
+        // Read-only, so a running VSCode is never blocked or locked out.
+        // `mode=ro` still sees writes in the WAL; should it fail (the -shm
+        // file isn't writable, or VSCode holds a lock past the timeout) the
+        // file is read as immutable, without locking or the WAL.
+        let mut rows = read_editor_rows(db_path, "mode=ro")
+            .or_else(|_| read_editor_rows(db_path, "immutable=1"))
+            .map_err(std::io::Error::other)?;
+
+        // Known keys first, then any newer "memento/workbench.parts.editor*"
+        // variant. Several editor-related keys can coexist, so only a value
+        // that actually carries the editor grid is used.
+        rows.sort_by_key(|(key, _)| {
+            EDITOR_STATE_KEYS
+                .iter()
+                .position(|known| known == key)
+                .unwrap_or(EDITOR_STATE_KEYS.len())
+        });
+
+        let cursors = rows
+            .iter()
+            .find(|(key, _)| key == TEXT_EDITOR_STATE_KEY)
+            .and_then(|(_, value)| serde_json::from_str::<Value>(value).ok())
+            .map(|view_state| self.editor_cursors(&view_state))
+            .unwrap_or_default();
+
+        for (_, value) in rows {
+            if let Ok(editor_state) = serde_json::from_str::<Value>(&value) {
+                if Self::has_editor_grid(&editor_state) {
+                    return self.parse_editor_state(editor_state, &cursors);
+                }
             }
-            _ => Err(std::io::Error::new(std::io::ErrorKind::NotFound, "No editor state found in database"))
         }
+
+        // None of the known keys exist: most likely a VSCode schema change
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!(
+                "No editor grid found in {} (tried keys: {} and other memento/workbench.*editor* keys); the VSCode state schema may have changed",
+                db_path,
+                EDITOR_STATE_KEYS.join(", ")
+            ),
+        ))
+    }
+
+    /// Whether a memento value holds the serialized editor grid, either under
+    /// "editorpart.state" or directly (older keys)
+    fn has_editor_grid(editor_state: &Value) -> bool {
+        editor_state
+            .get("editorpart.state")
+            .unwrap_or(editor_state)
+            .get("serializedGrid")
+            .and_then(|grid| grid.get("root"))
+            .is_some()
+    }
+
+    /// The cursor positions in a `textEditorViewState` memento: pairs of a
+    /// file URI and its view state in each editor group it was shown in
+    fn editor_cursors(&self, view_state: &Value) -> EditorCursors {
+        let mut cursors = EditorCursors::new();
+        let entries = view_state.get("textEditorViewState").and_then(|v| v.as_array());
+        for entry in entries.into_iter().flatten() {
+            let (Some(uri), Some(Value::Object(groups))) = (entry.get(0).and_then(|v| v.as_str()), entry.get(1)) else {
+                continue;
+            };
+            let Some(path) = self.decode_vscode_uri(uri) else {
+                continue;
+            };
+            for (group, state) in groups {
+                let position = state.pointer("/cursorState/0/position");
+                let number = |name| position?.get(name)?.as_u64().map(|n| n as usize);
+                if let (Some(line), Some(column)) = (number("lineNumber"), number("column")) {
+                    cursors.entry(path.clone()).or_default().insert(group.clone(), (line, column));
+                }
+            }
+        }
+        cursors
     }
 
     /// Parse VSCode editor state JSON to extract open files
-    fn parse_editor_state(&self, editor_state: Value) -> Result<Vec<FileInfo>, std::io::Error> {
+    fn parse_editor_state(&self, editor_state: Value, cursors: &EditorCursors) -> Result<EditorSession, std::io::Error> {
         let mut files = Vec::new();
+        let mut group_count = None;
         
-        // Navigate through the JSON structure
-        if let Some(editorpart) = editor_state.get("editorpart.state") {
+        // Navigate through the JSON structure. Older keys store the grid
+        // directly instead of under "editorpart.state".
+        if let Some(editorpart) = editor_state.get("editorpart.state").or(Some(&editor_state)) {
             if let Some(serialized_grid) = editorpart.get("serializedGrid") {
                 if let Some(root) = serialized_grid.get("root") {
-                    if let Some(data) = root.get("data") {
-                        if let Some(data_array) = data.as_array() {
-                            for group in data_array {
-                                if let Some(group_data) = group.get("data") {
-                                    if let Some(editors) = group_data.get("editors") {
-                                        if let Some(mru) = group_data.get("mru") {
-                                            files.extend(self.parse_editor_group(editors, mru)?);
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                    let mut groups = Vec::new();
+                    self.collect_editor_groups(root, &mut groups);
+                    group_count = Some(groups.len());
+
+                    for group_data in groups {
+                        files.extend(self.parse_editor_group(group_data, cursors)?);
                     }
                 }
             }
         }
 
-        Ok(files)
+        Ok(EditorSession {
+            files,
+            workspace: None,
+            group_count,
+        })
+    }
+
+    /// Walk the serialized grid, collecting the data of each leaf (editor group).
+    /// Branch nodes hold nested splits in their "data" array.
+    fn collect_editor_groups<'a>(&self, node: &'a Value, groups: &mut Vec<&'a Value>) {
+        match node.get("data") {
+            Some(Value::Array(children)) => {
+                for child in children {
+                    self.collect_editor_groups(child, groups);
+                }
+            }
+            Some(group_data) => groups.push(group_data),
+            None => {}
+        }
     }
 
-    /// Parse individual editor group
-    fn parse_editor_group(&self, editors: &Value, mru: &Value) -> Result<Vec<FileInfo>, std::io::Error> {
+    /// Parse individual editor group. Its first `sticky` editors are pinned,
+    /// and `preview` is the index of its preview (italic) editor, if any.
+    /// A file's cursor is the one saved for this group, else any saved for it.
+    fn parse_editor_group(&self, group_data: &Value, cursors: &EditorCursors) -> Result<Vec<FileInfo>, std::io::Error> {
         let mut files = Vec::new();
-        
-        if let (Some(editors_array), Some(mru_array)) = (editors.as_array(), mru.as_array()) {
+        let editors = group_data.get("editors").and_then(|e| e.as_array());
+        let mru = group_data.get("mru").and_then(|m| m.as_array());
+
+        if let (Some(editors_array), Some(mru_array)) = (editors, mru) {
             // Get active file index (first in MRU order)
-            let active_index = mru_array.get(0).and_then(|v| v.as_u64()).unwrap_or(0) as usize;
-            
+            let active_index = mru_array.first().and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            let sticky = group_data.get("sticky").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            let preview = group_data.get("preview").and_then(|v| v.as_u64()).map(|i| i as usize);
+            let kinds: Vec<TabKind> = (0..editors_array.len())
+                .map(|index| {
+                    if index < sticky {
+                        TabKind::Pinned
+                    } else if Some(index) == preview {
+                        TabKind::Scratch
+                    } else {
+                        TabKind::Regular
+                    }
+                })
+                .collect();
+            let tab_indexes = TabKind::visual_indexes(&kinds);
+
             for (index, editor) in editors_array.iter().enumerate() {
                 if let Some(value_str) = editor.get("value").and_then(|v| v.as_str()) {
                     if let Ok(editor_data) = serde_json::from_str::<Value>(value_str) {
                         if let Some(resource) = editor_data.get("resourceJSON") {
                             if let Some(fs_path) = resource.get("fsPath").and_then(|v| v.as_str()) {
                                 let is_active = index == active_index;
-                                files.push(self.create_file_info(fs_path, is_active));
+                                let mut file = self.create_file_info(fs_path, is_active, FileSource::SessionState);
+                                file.is_pinned = kinds[index] == TabKind::Pinned;
+                                file.is_preview = kinds[index] == TabKind::Scratch;
+                                file.tab_index = Some(tab_indexes[index]);
+                                if let Some(saved) = cursors.get(fs_path) {
+                                    let group_id = group_data.get("id").map(|id| id.to_string());
+                                    let cursor = group_id
+                                        .and_then(|id| saved.get(&id))
+                                        .or_else(|| saved.values().next());
+                                    if let Some(&(line, column)) = cursor {
+                                        file.cursor_line = Some(line);
+                                        file.cursor_column = Some(column);
+                                    }
+                                }
+                                files.push(file);
                             }
                         }
                     }
@@ -379,23 +697,22 @@ impl VSCodeDetector {
         // Try to find common file types in the workspace (simplified heuristic)
         if let Ok(entries) = fs::read_dir(workspace_path) {
             let mut found_files = 0;
-            for entry in entries.take(10) { // Limit to first 10 files
-                if let Ok(entry) = entry {
-                    let path = entry.path();
-                    if path.is_file() && found_files < 5 {
-                        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                            // Only include common development files
-                            if name.ends_with(".js") || name.ends_with(".ts") || 
-                               name.ends_with(".py") || name.ends_with(".rs") ||
-                               name.ends_with(".go") || name.ends_with(".java") ||
-                               name.ends_with(".cpp") || name.ends_with(".c") ||
-                               name.ends_with(".json") || name.ends_with(".md") {
-                                files.push(self.create_file_info(
-                                    &path.to_string_lossy().to_string(),
-                                    found_files == 0 // Mark first file as potentially active
-                                ));
-                                found_files += 1;
-                            }
+            for entry in entries.take(10).flatten() { // Limit to first 10 files
+                let path = entry.path();
+                if path.is_file() && found_files < 5 {
+                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                        // Only include common development files
+                        if name.ends_with(".js") || name.ends_with(".ts") || 
+                           name.ends_with(".py") || name.ends_with(".rs") ||
+                           name.ends_with(".go") || name.ends_with(".java") ||
+                           name.ends_with(".cpp") || name.ends_with(".c") ||
+                           name.ends_with(".json") || name.ends_with(".md") {
+                            files.push(self.create_file_info(
+                                &path.to_string_lossy(),
+                                found_files == 0, // Mark first file as potentially active
+                                FileSource::Heuristic,
+                            ));
+                            found_files += 1;
                         }
                     }
                 }
@@ -406,38 +723,54 @@ impl VSCodeDetector {
     }
 
     /// Create a FileInfo struct from a path
-    fn create_file_info(&self, path: &str, is_active: bool) -> FileInfo {
-        let file_name = Path::new(path)
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or(path)
-            .to_string();
-
+    fn create_file_info(&self, path: &str, is_active: bool, source: FileSource) -> FileInfo {
+        // is_modified is set from the hot-exit backups once all files are known
         FileInfo {
-            path: path.to_string(),
-            name: file_name,
             is_active,
-            is_modified: false, // Cannot easily detect without VSCode API
-            tab_index: None,
-            project_name: None,
+            ..FileInfo::new(path, source)
         }
     }
 }
 
 impl IDEDetector for VSCodeDetector {
     fn ide_type(&self) -> SupportedIDE {
-        SupportedIDE::VSCode
+        self.ide_type
     }
 
     fn is_target_process(&self, process: &ProcessInfo) -> bool {
-        self.process_names.iter().any(|&name| {
-            let process_name = process.name.to_lowercase();
-            let exe_path = process.executable_path.to_lowercase();
-            
-            process_name == name.to_lowercase() || 
-            process_name.starts_with(&name.to_lowercase()) ||
-            exe_path.contains(&name.to_lowercase())
-        })
+        let exe_path = process.executable_path.to_lowercase();
+        let exe_name = Path::new(&exe_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+
+        // macOS bundles run the Electron binary; identify them by the app path.
+        // The path is empty when it couldn't be read: only names count then.
+        if exe_path.contains(self.app_bundle) {
+            return true;
+        }
+
+        // The executable is named exactly; helpers are recognized by process name
+        self.ide_type.matches_process_name(&process.name) || self.ide_type.process_names().contains(&exe_name)
+    }
+
+    fn state_paths(&self, result: &crate::types::DetectionResult) -> Vec<PathBuf> {
+        let workspace_storage_dir = match self.workspace_storage_dir() {
+            Some(workspace_storage_dir) => workspace_storage_dir,
+            None => return Vec::new(),
+        };
+
+        // Only the window's own state.vscdb when we can tell which one it is
+        let workspace_dir = result
+            .project_path
+            .as_deref()
+            .and_then(|project| self.get_workspace_id(project, &workspace_storage_dir).ok())
+            .map(|workspace_id| Path::new(&workspace_storage_dir).join(workspace_id));
+
+        // Backups come and go as editors get dirty and are saved
+        let mut paths = vec![workspace_dir.unwrap_or_else(|| PathBuf::from(workspace_storage_dir))];
+        paths.extend(self.backups_dir().map(PathBuf::from));
+        paths
     }
 
     fn extract_files(&self, processes: &[ProcessInfo]) -> DetectionResult<crate::types::DetectionResult> {
@@ -445,24 +778,38 @@ impl IDEDetector for VSCodeDetector {
         let mut active_file = None;
         let mut project_path = None;
         let mut found_cmdline_files = false;
+        let mut workspace_file = None;
+        let mut group_count = None;
+        let mut seen_paths = HashSet::new();
 
-        // First, check command line arguments for workspace/files
-        for process in processes {
-            if let Some(cmdline) = self.get_process_cmdline(process.pid) {
-                if let Some((workspace, files)) = self.extract_vscode_info(&cmdline) {
-                    if !workspace.is_empty() && project_path.is_none() {
-                        project_path = Some(workspace.clone());
-                    }
-                    
-                    // If files were passed directly via command line
-                    if !files.is_empty() {
-                        found_cmdline_files = true;
-                        for file in files {
-                            if file.is_active && active_file.is_none() {
-                                active_file = Some(file.path.clone());
-                            }
-                            all_files.push(file);
+        // First, check command line arguments for workspace/files. Only the
+        // main process carries them; its Electron helpers match the same names
+        let primary_cmdlines = processes
+            .iter()
+            .filter_map(|process| crate::process::get_process_cmdline(process.pid))
+            .filter(|cmdline| !is_electron_helper(cmdline));
+        for cmdline in primary_cmdlines {
+            if let Some(launch) = self.extract_vscode_info(&cmdline) {
+                if !launch.workspace.is_empty() && project_path.is_none() {
+                    project_path = Some(launch.workspace.clone());
+                }
+                if launch.workspace_file.is_some() && workspace_file.is_none() {
+                    workspace_file = launch.workspace_file;
+                }
+                let files = launch.files;
+                
+                // If files were passed directly via command line
+                if !files.is_empty() {
+                    found_cmdline_files = true;
+                    for file in files {
+                        // Several processes may carry the same file arguments
+                        if !seen_paths.insert(self.normalize_path(&file.path)) && !self.raw {
+                            continue;
                         }
+                        if file.is_active && active_file.is_none() {
+                            active_file = Some(file.path.clone());
+                        }
+                        all_files.push(file);
                     }
                 }
             }
@@ -470,16 +817,26 @@ impl IDEDetector for VSCodeDetector {
 
         // If VSCode opened a folder (no files in cmdline), get files from session database
         // Also try session database if no cmdline files were found
+        let mut session_error = None;
         if !found_cmdline_files {
-            if let Ok((session_files, detected_workspace)) = self.get_vscode_recent_files(project_path.as_deref().unwrap_or("")) {
+            let recent_files = self.get_vscode_recent_files(
+                project_path.as_deref().unwrap_or(""),
+                workspace_file.as_deref(),
+            );
+            if let Err(e) = &recent_files {
+                session_error = Some(e.to_string());
+            }
+
+            if let Ok(session) = recent_files {
                 // Update project path if detected from workspace.json
-                if project_path.is_none() && detected_workspace.is_some() {
-                    project_path = detected_workspace;
+                if project_path.is_none() && session.workspace.is_some() {
+                    project_path = session.workspace;
                 }
+                group_count = session.group_count;
                 
-                for session_file in session_files {
-                    // Avoid duplicates
-                    if !all_files.iter().any(|f| f.path == session_file.path) {
+                for session_file in session.files {
+                    // Avoid duplicates, even when spelled differently
+                    if seen_paths.insert(self.normalize_path(&session_file.path)) || self.raw {
                         if session_file.is_active && active_file.is_none() {
                             active_file = Some(session_file.path.clone());
                         }
@@ -489,19 +846,783 @@ impl IDEDetector for VSCodeDetector {
             }
         }
 
-        if all_files.is_empty() {
+        // In a multi-root workspace each file belongs to one of its folders
+        let roots = match &workspace_file {
+            Some(workspace_file) => self.parse_code_workspace(workspace_file),
+            None => project_path.iter().cloned().collect(),
+        };
+        assign_project_roots(&mut all_files, &roots);
+
+        let unsaved = self.unsaved_paths();
+        for file in &mut all_files {
+            // Command line files may name the file differently from its backup
+            file.is_modified = unsaved.contains(&self.normalize_path(&file.path));
+        }
+
+        // A known workspace with no editors open is still a result, unless
+        // reading its session failed outright
+        if all_files.is_empty() && (project_path.is_none() || session_error.is_some()) {
             return Err(crate::detector::DetectionError::WindowParseError {
-                message: "No workspace or files detected for VSCode".to_string(),
+                message: session_error
+                    .unwrap_or_else(|| format!("No workspace or files detected for {}", self.display_name())),
             });
         }
 
         Ok(crate::types::DetectionResult {
+            format_version: crate::types::FORMAT_VERSION,
             timestamp: chrono::Utc::now().to_rfc3339(),
             ide_name: self.display_name().to_string(),
             ide_version: None, // Could be extracted from process info
             active_file,
             open_files: all_files,
             project_path,
+            group_count,
+            project_language: None,
+            seq: None,
         })
     }
-}
\ No newline at end of file
+}
+/// The `ItemTable` rows that may hold the editor state, from a read-only
+/// connection opened with the URI parameters `params`
+fn read_editor_rows(db_path: &str, params: &str) -> SqliteResult<Vec<(String, String)>> {
+    let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+    let conn = Connection::open_with_flags(format!("{}?{}", sqlite_file_uri(db_path), params), flags)?;
+    conn.busy_timeout(DB_BUSY_TIMEOUT)?;
+
+    let mut stmt = conn.prepare("SELECT key, value FROM ItemTable WHERE key LIKE 'memento/workbench.%editor%'")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// A `file:` URI for SQLite, escaping the characters that would end the path
+fn sqlite_file_uri(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut uri = String::from(if path.starts_with('/') { "file://" } else { "file:///" });
+    for c in path.chars() {
+        match c {
+            '%' | '?' | '#' => uri.push_str(&format!("%{:02X}", c as u32)),
+            c => uri.push(c),
+        }
+    }
+    uri
+}
+
+/// Electron helper processes (renderer, GPU, utility, ...) are started with
+/// `--type=<kind>`; the main/browser process is not
+fn is_electron_helper(cmdline: &[String]) -> bool {
+    cmdline.iter().skip(1).any(|arg| arg.starts_with("--type="))
+}
+
+/// Set each file's `project_path` to the innermost root containing it. Files
+/// outside every root are genuinely open, so they stay, with no project.
+fn assign_project_roots(files: &mut [FileInfo], roots: &[String]) {
+    for file in files {
+        file.project_path = roots
+            .iter()
+            .filter(|root| Path::new(&file.path).starts_with(root))
+            .max_by_key(|root| Path::new(root).components().count())
+            .cloned();
+    }
+}
+
+/// The comparison scheme and operand roles of a `--diff`/`--merge` flag
+fn comparison_roles(arg: &str) -> Option<(&'static str, &'static [&'static str])> {
+    match arg {
+        "-d" | "--diff" => Some(("diff", &["left", "right"])),
+        "-m" | "--merge" => Some(("merge", &["input1", "input2", "base", "result"])),
+        _ => None,
+    }
+}
+
+/// Decode `%XX` escapes as used in file URIs; malformed escapes are kept as-is
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| input.get(i + 1..i + 3))
+            .flatten()
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Strip `//` and `/* */` comments and trailing commas from JSONC, as used by
+/// `.code-workspace` and settings files
+fn strip_jsonc(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    output.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                output.push(c);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            ',' => {
+                // Drop the comma if the next significant character closes a container
+                let next_significant = chars.clone().find(|c| !c.is_whitespace());
+                if !matches!(next_significant, Some('}') | Some(']')) {
+                    output.push(c);
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_target_process_matches_vscode_variants_only() {
+        let detector = VSCodeDetector::new();
+        let cases = [
+            // Linux
+            ("code", "/usr/share/code/code", true),
+            ("code-oss", "/usr/lib/code-oss/code-oss", true),
+            ("codium", "/usr/share/codium/codium", true),
+            ("code-insiders", "/usr/share/code-insiders/code-insiders", false),
+            ("codesign", "/usr/bin/codesign", false),
+            ("codecov", "/usr/local/bin/codecov", false),
+            ("vscode-server", "/usr/bin/node", false),
+            ("chrome_crashpad", "/usr/share/code/chrome_crashpad_handler", false),
+            // macOS
+            ("Code Helper", "/Applications/Visual Studio Code.app/Contents/Frameworks/Code Helper.app/Contents/MacOS/Code Helper", true),
+            ("Code Helper (Renderer)", "", true),
+            ("Electron", "/Applications/Visual Studio Code.app/Contents/MacOS/Electron", true),
+            ("Electron", "/Applications/Slack.app/Contents/MacOS/Electron", false),
+            // Windows
+            ("Code.exe", "C:\\Program Files\\Microsoft VS Code\\Code.exe", true),
+            ("CodeSetup.exe", "C:\\Users\\me\\Downloads\\CodeSetup.exe", false),
+            // Executable path unreadable (permissions, Windows snapshot)
+            ("code", "", true),
+            ("Code.exe", "", true),
+            ("codesign", "", false),
+            ("Electron", "", false),
+            ("node", "", false),
+            ("", "", false),
+        ];
+
+        for (name, exe, expected) in cases {
+            let process = ProcessInfo::for_test(name, exe);
+            assert_eq!(detector.is_target_process(&process), expected, "{} ({})", name, exe);
+        }
+    }
+
+    #[test]
+    fn cursor_reuses_the_vscode_detector_with_its_own_names_and_storage() {
+        let vscode = VSCodeDetector::new().with_home_dir(Some("/home/me".to_string()));
+        let cursor = VSCodeDetector::cursor().with_home_dir(Some("/home/me".to_string()));
+        assert_eq!(cursor.ide_type(), SupportedIDE::Cursor);
+        assert_eq!(
+            cursor.workspace_storage_dir().as_deref(),
+            Some("/home/me/.config/Cursor/User/workspaceStorage")
+        );
+        assert_eq!(
+            vscode.workspace_storage_dir().as_deref(),
+            Some("/home/me/.config/Code/User/workspaceStorage")
+        );
+
+        let cases = [
+            ("cursor", "/opt/cursor/cursor", true, false),
+            ("Cursor Helper (Renderer)", "", true, false),
+            ("Electron", "/Applications/Cursor.app/Contents/MacOS/Electron", true, false),
+            ("Cursor.exe", "", true, false),
+            ("cursorctl", "", false, false),
+            ("code", "/usr/share/code/code", false, true),
+        ];
+        for (name, exe, is_cursor, is_vscode) in cases {
+            let process = ProcessInfo::for_test(name, exe);
+            assert_eq!(cursor.is_target_process(&process), is_cursor, "{}", name);
+            assert_eq!(vscode.is_target_process(&process), is_vscode, "{}", name);
+        }
+    }
+
+    #[test]
+    fn insiders_and_stable_claim_only_their_own_processes() {
+        let stable = VSCodeDetector::new().with_home_dir(Some("/home/me".to_string()));
+        let insiders = VSCodeDetector::insiders().with_home_dir(Some("/home/me".to_string()));
+        assert_eq!(insiders.display_name(), "Visual Studio Code - Insiders");
+        assert_eq!(
+            insiders.workspace_storage_dir().as_deref(),
+            Some("/home/me/.config/Code - Insiders/User/workspaceStorage")
+        );
+
+        let cases = [
+            ("code-insiders", "/usr/share/code-insiders/code-insiders", true, false),
+            ("Electron", "/Applications/Visual Studio Code - Insiders.app/Contents/MacOS/Electron", true, false),
+            ("Code - Insiders Helper (Renderer)", "", true, false),
+            ("Code - Insiders.exe", "C:\\Users\\me\\AppData\\Local\\Programs\\Microsoft VS Code Insiders\\Code - Insiders.exe", true, false),
+            ("code", "/usr/share/code/code", false, true),
+            ("Electron", "/Applications/Visual Studio Code.app/Contents/MacOS/Electron", false, true),
+        ];
+        for (name, exe, is_insiders, is_stable) in cases {
+            let process = ProcessInfo::for_test(name, exe);
+            assert_eq!(insiders.is_target_process(&process), is_insiders, "{} ({})", name, exe);
+            assert_eq!(stable.is_target_process(&process), is_stable, "{} ({})", name, exe);
+        }
+    }
+
+    fn cmdline(args: &[&str]) -> Vec<String> {
+        std::iter::once("code").chain(args.iter().copied()).map(String::from).collect()
+    }
+
+    fn roles(files: &[FileInfo]) -> Vec<(String, String, usize, bool)> {
+        files
+            .iter()
+            .map(|f| {
+                let comparison = f.comparison.as_ref().expect("comparison operand");
+                (f.name.clone(), comparison.role.clone(), comparison.group, f.is_active)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn only_the_main_process_command_line_is_used_for_the_workspace() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let processes: Vec<Vec<String>> = [
+            vec!["/usr/share/code/code", "--type=zygote", "--no-sandbox"],
+            vec!["/usr/share/code/code", "--type=gpu-process", "--crashpad-handler-pid=41"],
+            vec!["/usr/share/code/code", "--type=renderer", "--enable-crash-reporter", "/tmp"],
+            vec!["/usr/share/code/code", "--type=utility", "--utility-sub-type=node.mojom.NodeService"],
+            vec!["/usr/share/code/code", "--unity-launch", dir],
+        ]
+        .into_iter()
+        .map(|args| args.into_iter().map(String::from).collect())
+        .collect();
+
+        let primary: Vec<&Vec<String>> = processes.iter().filter(|cmdline| !is_electron_helper(cmdline)).collect();
+        assert_eq!(primary, [&processes[4]]);
+        let launch = VSCodeDetector::new().extract_vscode_info(primary[0]).unwrap();
+        assert_eq!(launch.workspace, dir);
+        // Only the arguments count, not the program name
+        assert!(!is_electron_helper(&["--type=code".to_string(), "--new-window".to_string()]));
+    }
+
+    #[test]
+    fn diff_operands_form_one_comparison() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let left = format!("{}/Cargo.toml", dir);
+        let right = format!("{}/README.md", dir);
+        let launch = VSCodeDetector::new()
+            .extract_vscode_info(&cmdline(&["--diff", &left, &right]))
+            .unwrap();
+
+        assert_eq!(
+            roles(&launch.files),
+            vec![
+                ("Cargo.toml".to_string(), "left".to_string(), 0, false),
+                ("README.md".to_string(), "right".to_string(), 0, true),
+            ]
+        );
+        assert!(launch.files.iter().all(|f| f.comparison.as_ref().unwrap().scheme == "diff"));
+    }
+
+    #[test]
+    fn code_workspace_argument_is_a_workspace_not_a_file() {
+        let root = std::env::temp_dir().join(format!("ide-files-code-workspace-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("api")).unwrap();
+        fs::write(root.join("notes.md"), "").unwrap();
+        // JSONC, with a comment and a trailing comma as VS Code writes them
+        fs::write(
+            root.join("team.code-workspace"),
+            "{\n  // roots\n  \"folders\": [{ \"path\": \"api\" }, { \"path\": \"/srv/web\" },],\n}\n",
+        )
+        .unwrap();
+        let path = |relative: &str| root.join(relative).to_string_lossy().into_owned();
+
+        let launch = VSCodeDetector::new()
+            .extract_vscode_info(&cmdline(&[&path("team.code-workspace"), &path("notes.md")]))
+            .unwrap();
+
+        assert_eq!(launch.workspace, path("api"));
+        assert_eq!(launch.workspace_file, Some(path("team.code-workspace")));
+        let files: Vec<&str> = launch.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(files, [path("notes.md")]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn five_hundred_argument_launch_still_finds_the_workspace() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        // Hundreds of paths that would each be stat'ed, with the folder last
+        let mut args: Vec<String> = (0..500).map(|n| format!("/nonexistent/lib/dep{}.jar", n)).collect();
+        args.push(dir.to_string());
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+        let started = std::time::Instant::now();
+        let bounded = crate::process::bound_cmdline(cmdline(&args));
+        let launch = VSCodeDetector::new().extract_vscode_info(&bounded).unwrap();
+
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        assert!(bounded.len() < 300);
+        assert_eq!(launch.workspace, dir);
+        assert!(launch.files.is_empty());
+    }
+
+    #[test]
+    fn merge_takes_up_to_four_operands() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let paths: Vec<String> = ["Cargo.toml", "README.md", "CLAUDE.md", "src/main.rs", "src/types.rs"]
+            .iter()
+            .map(|name| format!("{}/{}", dir, name))
+            .collect();
+        let mut args = vec!["--merge"];
+        args.extend(paths.iter().map(|p| p.as_str()));
+        let launch = VSCodeDetector::new().extract_vscode_info(&cmdline(&args)).unwrap();
+
+        assert_eq!(
+            roles(&launch.files[..4]),
+            vec![
+                ("Cargo.toml".to_string(), "input1".to_string(), 0, false),
+                ("README.md".to_string(), "input2".to_string(), 0, false),
+                ("CLAUDE.md".to_string(), "base".to_string(), 0, false),
+                ("main.rs".to_string(), "result".to_string(), 0, true),
+            ]
+        );
+        // A fifth path is an ordinary file argument again
+        assert_eq!(launch.files.len(), 5);
+        assert_eq!(launch.files[4].comparison, None);
+        assert!(launch.files[..4].iter().all(|f| f.comparison.as_ref().unwrap().scheme == "merge"));
+    }
+
+    #[test]
+    fn sticky_editors_lead_and_the_preview_editor_trails_the_tab_strip() {
+        let group = serde_json::json!({
+            "editors": [editor("/p/a.rs"), editor("/p/preview.rs"), editor("/p/b.rs")],
+            "mru": [2, 0, 1],
+            "sticky": 1,
+            "preview": 1
+        });
+
+        let files = VSCodeDetector::new().parse_editor_group(&group, &EditorCursors::new()).unwrap();
+        let tabs: Vec<_> = files
+            .iter()
+            .map(|f| (f.name.as_str(), f.tab_index, f.is_pinned, f.is_preview, f.is_active))
+            .collect();
+
+        assert_eq!(
+            tabs,
+            [
+                ("a.rs", Some(0), true, false, false),
+                ("preview.rs", Some(2), false, true, false),
+                ("b.rs", Some(1), false, false, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn group_count_is_the_number_of_editor_splits() {
+        let detector = VSCodeDetector::new();
+        let group = |paths: &[&str]| {
+            let editors: Vec<Value> = paths.iter().map(|path| editor(path)).collect();
+            serde_json::json!({"type": "leaf", "data": {"editors": editors, "mru": [0]}})
+        };
+        let group_count = |root: Value| {
+            let editor_state = serde_json::json!({"editorpart.state": {"serializedGrid": {"root": root}}});
+            let session = detector.parse_editor_state(editor_state, &EditorCursors::new()).unwrap();
+            (session.group_count, session.files.len())
+        };
+
+        assert_eq!(group_count(group(&["/p/a.rs", "/p/b.rs"])), (Some(1), 2));
+        let split = serde_json::json!({"type": "branch", "data": [group(&["/p/a.rs"]), group(&["/p/c.rs"])]});
+        assert_eq!(group_count(split), (Some(2), 2));
+    }
+
+    #[test]
+    fn cursors_come_from_the_view_state_of_each_group() {
+        let detector = VSCodeDetector::new();
+        let cursor = |line: u64, column: u64| {
+            serde_json::json!({"cursorState": [{
+                "inSelectionMode": false,
+                "selectionStart": {"lineNumber": line, "column": column},
+                "position": {"lineNumber": line, "column": column}
+            }]})
+        };
+        let view_state = serde_json::json!({"textEditorViewState": [
+            ["file:///p/a.rs", {"1": cursor(12, 5), "2": cursor(40, 1)}],
+            ["file:///p/b.rs", {"1": cursor(3, 9)}],
+        ]});
+        let editor_state = serde_json::json!({"editorpart.state": {"serializedGrid": {"root": {
+            "type": "branch",
+            "data": [
+                {"type": "leaf", "data": {"id": 1, "editors": [editor("/p/a.rs"), editor("/p/c.rs")], "mru": [0, 1]}},
+                {"type": "leaf", "data": {"id": 2, "editors": [editor("/p/a.rs"), editor("/p/b.rs")], "mru": [0, 1]}},
+            ]
+        }}}});
+
+        let cursors = detector.editor_cursors(&view_state);
+        let session = detector.parse_editor_state(editor_state, &cursors).unwrap();
+        let positions: Vec<(&str, Option<usize>, Option<usize>)> = session
+            .files
+            .iter()
+            .map(|f| (f.name.as_str(), f.cursor_line, f.cursor_column))
+            .collect();
+
+        // b.rs was only saved in group 1, so that position stands in for group 2
+        assert_eq!(
+            positions,
+            [
+                ("a.rs", Some(12), Some(5)),
+                ("c.rs", None, None),
+                ("a.rs", Some(40), Some(1)),
+                ("b.rs", Some(3), Some(9)),
+            ]
+        );
+    }
+
+    #[test]
+    fn files_backed_up_for_hot_exit_are_unsaved() {
+        let root = std::env::temp_dir().join(format!("ide-files-vscode-backups-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let backup = |window: &str, scheme: &str, name: &str, content: &str| {
+            let dir = root.join("Backups").join(window).join(scheme);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(name), content).unwrap();
+        };
+        backup("1a2b", "file", "51f3", "file:///work/src/main.rs {\"mtime\":1718800000000,\"size\":12}\nfn main() {}\n");
+        backup("1a2b", "untitled", "9c0d", "untitled:Untitled-1 {}\nscratch\n");
+        // Written before VSCode 1.50: the bare URI
+        backup("3c4d", "file", "77e0", "file:///work/notes%20today.md\n# Notes\n");
+        fs::write(root.join("Backups/workspaces.json"), "{}").unwrap();
+
+        let detector = VSCodeDetector::new().with_config_dir(Some(root.to_string_lossy().into_owned()));
+        let mut unsaved: Vec<String> = detector.unsaved_paths().into_iter().collect();
+        fs::remove_dir_all(&root).unwrap();
+        unsaved.sort();
+
+        assert_eq!(unsaved, ["/work/notes today.md", "/work/src/main.rs"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn an_open_file_with_a_backup_is_modified_however_it_is_spelled() {
+        let root = std::env::temp_dir().join(format!("ide-files-vscode-modified-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("project")).unwrap();
+        fs::create_dir_all(root.join("Backups/1a2b/file")).unwrap();
+        fs::write(root.join("project/main.rs"), "").unwrap();
+        fs::write(root.join("project/lib.rs"), "").unwrap();
+        std::os::unix::fs::symlink(root.join("project"), root.join("link")).unwrap();
+        let path = |relative: &str| root.join(relative).to_string_lossy().into_owned();
+        fs::write(
+            root.join("Backups/1a2b/file/51f3"),
+            format!("file://{} {{}}\nfn main() {{}}\n", path("project/main.rs")),
+        )
+        .unwrap();
+
+        // Opened through the symlink, backed up under the real path
+        let (mut child, process) = spawn_code(&[&path("link/main.rs"), &path("project/lib.rs")]);
+        let result = VSCodeDetector::new()
+            .with_config_dir(root.to_str().map(String::from))
+            .extract_files(&[process]);
+        let _ = child.kill();
+        let _ = child.wait();
+        fs::remove_dir_all(&root).unwrap();
+
+        let modified: Vec<(String, bool)> = result
+            .unwrap()
+            .open_files
+            .into_iter()
+            .map(|f| (f.path, f.is_modified))
+            .collect();
+        assert_eq!(modified, [(path("link/main.rs"), true), (path("project/lib.rs"), false)]);
+    }
+
+    #[test]
+    fn file_uris_are_percent_decoded_into_paths() {
+        let detector = VSCodeDetector::new();
+        let cases = [
+            ("file:///home/me/My%20Project/main.rs", "/home/me/My Project/main.rs"),
+            ("file:///home/me/caf%C3%A9/r%C3%A9sum%C3%A9.md", "/home/me/café/résumé.md"),
+            ("file:///c%3A/Users/me/main.rs", "c:\\Users\\me\\main.rs"),
+            ("file:///D:/src/100%25.txt", "D:\\src\\100%.txt"),
+            ("file://server/share/notes%231.txt", "\\\\server\\share\\notes#1.txt"),
+            ("file://localhost/tmp/a.rs", "/tmp/a.rs"),
+            ("vscode-remote://ssh-remote%2Bbuild-box/home/me/src/main%20copy.rs", "/home/me/src/main copy.rs"),
+            ("/already/a/path.rs", "/already/a/path.rs"),
+        ];
+
+        for (uri, path) in cases {
+            assert_eq!(detector.decode_vscode_uri(uri).as_deref(), Some(path), "{}", uri);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_file_spelled_differently_is_listed_once() {
+        let root = std::env::temp_dir().join(format!("ide-files-vscode-dedup-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("My Project")).unwrap();
+        fs::write(root.join("My Project/main.rs"), "").unwrap();
+        fs::write(root.join("My Project/lib.rs"), "").unwrap();
+        std::os::unix::fs::symlink(root.join("My Project"), root.join("link")).unwrap();
+        let dir = root.to_string_lossy();
+
+        let detector = VSCodeDetector::new();
+        let cmdline_file = format!("{}/My Project/main.rs", dir);
+        let spellings = [
+            format!("file://{}/My%20Project/main.rs", dir),
+            format!("{}/My%20Project/main.rs", dir),
+            format!("{}/link/main.rs", dir),
+            format!("{}/My Project/main.rs/", dir),
+        ];
+
+        // The same set extract_files fills from the command line and the session
+        let mut seen_paths = HashSet::new();
+        assert!(seen_paths.insert(detector.normalize_path(&cmdline_file)));
+        for session_file in &spellings {
+            assert!(!seen_paths.insert(detector.normalize_path(session_file)), "{}", session_file);
+        }
+        assert!(seen_paths.insert(detector.normalize_path(&format!("{}/My%20Project/lib.rs", dir))));
+        assert_eq!(seen_paths.len(), 2);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// A running process whose command line is `code <args>`, for
+    /// `extract_files`; kill and wait on the child when done
+    #[cfg(target_os = "linux")]
+    fn spawn_code(args: &[&str]) -> (std::process::Child, ProcessInfo) {
+        let child = std::process::Command::new("sh")
+            .args(["-c", "while sleep 1; do :; done", "code"])
+            .args(args)
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        // Until exec, the child still has the test binary's command line
+        let started = std::time::Instant::now();
+        while crate::process::get_process_cmdline(child.id())
+            .is_some_and(|cmdline| cmdline.first().map(String::as_str) != Some("sh"))
+            && started.elapsed() < std::time::Duration::from_secs(5)
+        {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let process = ProcessInfo {
+            pid: child.id(),
+            ..ProcessInfo::for_test("code", "")
+        };
+        (child, process)
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn raw_keeps_both_spellings_of_a_file() {
+        let root = std::env::temp_dir().join(format!("ide-files-vscode-raw-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("project")).unwrap();
+        fs::write(root.join("project/main.rs"), "").unwrap();
+        std::os::unix::fs::symlink(root.join("project"), root.join("link")).unwrap();
+        let path = |relative: &str| root.join(relative).to_string_lossy().into_owned();
+
+        // `code project/main.rs link/main.rs`: one file, named twice
+        let (mut child, process) = spawn_code(&[&path("project/main.rs"), &path("link/main.rs")]);
+        let detect = |raw: bool| {
+            VSCodeDetector::new()
+                .with_home_dir(Some(path("home")))
+                .with_raw(raw)
+                .extract_files(std::slice::from_ref(&process))
+                .map(|result| result.open_files.into_iter().map(|f| f.path).collect::<Vec<_>>())
+        };
+        let (normalized, raw) = (detect(false), detect(true));
+        let _ = child.kill();
+        let _ = child.wait();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(normalized.unwrap(), [path("project/main.rs")]);
+        assert_eq!(raw.unwrap(), [path("project/main.rs"), path("link/main.rs")]);
+    }
+
+    #[test]
+    fn files_carry_their_own_workspace_root() {
+        let detector = VSCodeDetector::new();
+        let mut files = vec![
+            detector.create_file_info("/work/api/src/main.rs", true, FileSource::SessionState),
+            detector.create_file_info("/work/web/src/app.ts", false, FileSource::SessionState),
+            detector.create_file_info("/work/api-docs/index.md", false, FileSource::SessionState),
+            detector.create_file_info("/tmp/scratch.txt", false, FileSource::SessionState),
+        ];
+        let roots = vec!["/work/api".to_string(), "/work/web".to_string(), "/work".to_string()];
+
+        assign_project_roots(&mut files, &roots);
+
+        let project_paths: Vec<Option<&str>> = files.iter().map(|f| f.project_path.as_deref()).collect();
+        assert_eq!(
+            project_paths,
+            vec![Some("/work/api"), Some("/work/web"), Some("/work"), None]
+        );
+    }
+
+    /// An editor-group entry for the file at `path`
+    fn editor(path: &str) -> Value {
+        let value = serde_json::json!({"resourceJSON": {"fsPath": path}}).to_string();
+        serde_json::json!({"id": "workbench.editors.files.fileEditorInput", "value": value})
+    }
+
+    /// A state.vscdb at `db_path` whose ItemTable holds `rows`
+    fn write_state_db(db_path: &Path, rows: &[(&str, String)]) {
+        let db = Connection::open(db_path).unwrap();
+        db.execute("CREATE TABLE ItemTable (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB)", [])
+            .unwrap();
+        for (key, value) in rows {
+            db.execute("INSERT INTO ItemTable (key, value) VALUES (?1, ?2)", [key, value.as_str()])
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn editor_grid_is_found_under_older_keys_and_its_absence_is_a_schema_error() {
+        let root = std::env::temp_dir().join(format!("ide-files-vscode-keys-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        // Older keys hold the grid directly, without "editorpart.state"
+        let grid = serde_json::json!({ "serializedGrid": { "root": {
+            "type": "leaf",
+            "data": { "editors": [editor("/work/main.rs")], "mru": [0] }
+        } } });
+        let view_state = serde_json::json!({ "textEditorViewState": [] }).to_string();
+
+        let fallback = root.join("fallback.vscdb");
+        write_state_db(
+            &fallback,
+            &[
+                ("memento/workbench.editors.files.textFileEditor", view_state.clone()),
+                ("memento/workbench.editor.parts", grid.to_string()),
+            ],
+        );
+        let missing = root.join("missing.vscdb");
+        write_state_db(&missing, &[("memento/workbench.editors.files.textFileEditor", view_state)]);
+
+        let detector = VSCodeDetector::new();
+        let fallback = detector.parse_vscode_database(&fallback.to_string_lossy());
+        let missing = detector.parse_vscode_database(&missing.to_string_lossy());
+        fs::remove_dir_all(&root).unwrap();
+
+        let paths: Vec<String> = fallback.unwrap().files.into_iter().map(|f| f.path).collect();
+        assert_eq!(paths, ["/work/main.rs"]);
+        assert_eq!(missing.err().map(|e| e.kind()), Some(std::io::ErrorKind::Unsupported));
+    }
+
+    #[test]
+    fn database_is_read_while_vscode_holds_a_write_lock() {
+        // '#' and '?' would end the path in an unescaped SQLite URI
+        let root = std::env::temp_dir().join(format!("ide-files-vscode-locked-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("window #1?")).unwrap();
+        let db_path = root.join("window #1?/state.vscdb");
+
+        let editor_state = serde_json::json!({
+            "editorpart.state": { "serializedGrid": { "root": {
+                "type": "leaf",
+                "data": { "editors": [editor("/work/main.rs")], "mru": [0] }
+            } } }
+        });
+        write_state_db(&db_path, &[("memento/workbench.parts.editor", editor_state.to_string())]);
+        let vscode = Connection::open(&db_path).unwrap();
+        // A write in progress that never finishes
+        vscode.execute_batch("BEGIN EXCLUSIVE; DELETE FROM ItemTable;").unwrap();
+
+        let session = VSCodeDetector::new().parse_vscode_database(&db_path.to_string_lossy());
+        drop(vscode);
+        fs::remove_dir_all(&root).unwrap();
+
+        let paths: Vec<String> = session.unwrap().files.into_iter().map(|f| f.path).collect();
+        assert_eq!(paths, ["/work/main.rs"]);
+    }
+
+    #[test]
+    fn open_files_outside_the_workspace_root_are_still_reported() {
+        let root = std::env::temp_dir().join(format!("ide-files-vscode-outside-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let storage = root.join("config/User/workspaceStorage/window");
+        fs::create_dir_all(&storage).unwrap();
+        let path = |relative: &str| root.join(relative).to_string_lossy().into_owned();
+        let (project, inside, outside) = (path("api"), path("api/src/main.rs"), path("web/notes.md"));
+
+        // The window has since dropped the web folder, but notes.md is still open
+        fs::write(
+            storage.join("workspace.json"),
+            serde_json::json!({ "folder": format!("file://{}", project) }).to_string(),
+        )
+        .unwrap();
+        let editor_state = serde_json::json!({
+            "editorpart.state": { "serializedGrid": { "root": {
+                "type": "leaf",
+                "data": { "editors": [editor(&inside), editor(&outside)], "mru": [1, 0] }
+            } } }
+        });
+        write_state_db(
+            &storage.join("state.vscdb"),
+            &[("memento/workbench.parts.editor", editor_state.to_string())],
+        );
+
+        let detector = VSCodeDetector::new().with_config_dir(Some(path("config")));
+        let session = detector.get_vscode_recent_files(&project, None);
+        fs::remove_dir_all(&root).unwrap();
+
+        let session = session.unwrap();
+        assert_eq!(session.workspace, Some(project.clone()));
+        let mut files = session.files;
+        assign_project_roots(&mut files, std::slice::from_ref(&project));
+        let summary: Vec<(&str, bool, Option<&str>)> = files
+            .iter()
+            .map(|f| (f.path.as_str(), f.is_active, f.project_path.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            [(inside.as_str(), false, Some(project.as_str())), (outside.as_str(), true, None)]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use crate::types::{DetectionResult, FileInfo};
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// Render a detection result as a workspace file for another editor, so the
+/// same project and files can be reopened there. `editor` is "vscode"
+/// (a `.code-workspace`) or "jetbrains" (a `.idea/workspace.xml`).
+pub fn export_workspace(result: &DetectionResult, editor: &str) -> std::io::Result<String> {
+    // Only files that still exist are worth reopening
+    let files: Vec<&FileInfo> = result
+        .open_files
+        .iter()
+        .filter(|f| Path::new(&f.path).is_file())
+        .collect();
+
+    let project_path = result
+        .project_path
+        .as_deref()
+        .filter(|p| Path::new(p).is_dir());
+
+    if files.is_empty() && project_path.is_none() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            "none of the detected files or project directories exist",
+        ));
+    }
+
+    match editor {
+        "vscode" => Ok(code_workspace(project_path, &files)),
+        "jetbrains" => {
+            let project_path = project_path.ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    "a JetBrains workspace needs an existing project directory, and none was detected",
+                )
+            })?;
+            Ok(jetbrains_workspace(project_path, &files))
+        }
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("unknown export target '{}'", editor),
+        )),
+    }
+}
+
+/// A `.code-workspace` with the project as its folder, or the open files'
+/// directories when there is no project
+fn code_workspace(project_path: Option<&str>, files: &[&FileInfo]) -> String {
+    let mut folders: Vec<String> = Vec::new();
+    match project_path {
+        Some(project_path) => folders.push(project_path.to_string()),
+        None => {
+            for file in files {
+                if let Some(dir) = Path::new(&file.path).parent().and_then(|d| d.to_str()) {
+                    if !folders.iter().any(|f| f == dir) {
+                        folders.push(dir.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    let workspace = serde_json::json!({
+        "folders": folders
+            .iter()
+            .map(|path| serde_json::json!({ "path": path }))
+            .collect::<Vec<_>>(),
+        "settings": {},
+    });
+
+    serde_json::to_string_pretty(&workspace).unwrap_or_default() + "\n"
+}
+
+/// A minimal `workspace.xml` whose FileEditorManager lists the open files as
+/// tabs of a single editor group, the active one current
+fn jetbrains_workspace(project_path: &str, files: &[&FileInfo]) -> String {
+    let project_dir = project_path.trim_end_matches('/');
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<project version=\"4\">\n");
+    xml.push_str("  <component name=\"FileEditorManager\">\n");
+    xml.push_str("    <leaf>\n");
+
+    for file in files {
+        let url = match file.path.strip_prefix(&format!("{}/", project_dir)) {
+            Some(relative) => format!("file://$PROJECT_DIR$/{}", relative),
+            None => format!("file://{}", file.path),
+        };
+        xml.push_str(&format!(
+            "      <file current-in-tab=\"{}\">\n        <entry file=\"{}\" />\n      </file>\n",
+            file.is_active,
+            escape_xml(&url)
+        ));
+    }
+
+    xml.push_str("    </leaf>\n");
+    xml.push_str("  </component>\n");
+    xml.push_str("</project>\n");
+    xml
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_workspace_lists_project_folder() {
+        let project = env!("CARGO_MANIFEST_DIR");
+        let main = format!("{}/src/main.rs", project);
+        let result = DetectionResult {
+            project_path: Some(project.to_string()),
+            ..DetectionResult::for_test(
+                "Vim",
+                vec![FileInfo::for_test(&main, true), FileInfo::for_test("/nonexistent/gone.rs", false)],
+            )
+        };
+
+        let workspace: serde_json::Value =
+            serde_json::from_str(&export_workspace(&result, "vscode").unwrap()).unwrap();
+        assert_eq!(workspace["folders"][0]["path"], project);
+        assert_eq!(workspace["folders"].as_array().unwrap().len(), 1);
+
+        // Reopening the project brings back the open file, and only that one
+        let xml = export_workspace(&result, "jetbrains").unwrap();
+        assert!(xml.contains("<file current-in-tab=\"true\">"));
+        assert!(xml.contains("file://$PROJECT_DIR$/src/main.rs"));
+        assert!(!xml.contains("gone.rs"));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::types::FileInfo;
+use serde::Serialize;
+use std::path::Path;
+
+/// Group name for files whose language can't be inferred
+pub const OTHER_LANGUAGE: &str = "other";
+
+/// Files named without a telling extension
+const LANGUAGES_BY_NAME: &[(&str, &str)] = &[
+    ("Makefile", "make"),
+    ("GNUmakefile", "make"),
+    ("Dockerfile", "dockerfile"),
+    ("CMakeLists.txt", "cmake"),
+    ("Rakefile", "ruby"),
+    ("Gemfile", "ruby"),
+];
+
+const LANGUAGES_BY_EXTENSION: &[(&str, &str)] = &[
+    ("rs", "rust"),
+    ("go", "go"),
+    ("py", "python"),
+    ("pyi", "python"),
+    ("js", "javascript"),
+    ("mjs", "javascript"),
+    ("cjs", "javascript"),
+    ("jsx", "javascript"),
+    ("ts", "typescript"),
+    ("tsx", "typescript"),
+    ("java", "java"),
+    ("kt", "kotlin"),
+    ("kts", "kotlin"),
+    ("scala", "scala"),
+    ("c", "c"),
+    ("h", "c"),
+    ("cc", "cpp"),
+    ("cpp", "cpp"),
+    ("cxx", "cpp"),
+    ("hh", "cpp"),
+    ("hpp", "cpp"),
+    ("cs", "csharp"),
+    ("rb", "ruby"),
+    ("php", "php"),
+    ("swift", "swift"),
+    ("dart", "dart"),
+    ("lua", "lua"),
+    ("ex", "elixir"),
+    ("exs", "elixir"),
+    ("hs", "haskell"),
+    ("zig", "zig"),
+    ("nix", "nix"),
+    ("vim", "vim"),
+    ("sh", "shell"),
+    ("bash", "shell"),
+    ("zsh", "shell"),
+    ("sql", "sql"),
+    ("html", "html"),
+    ("htm", "html"),
+    ("css", "css"),
+    ("scss", "css"),
+    ("sass", "css"),
+    ("less", "css"),
+    ("md", "markdown"),
+    ("markdown", "markdown"),
+    ("json", "json"),
+    ("yaml", "yaml"),
+    ("yml", "yaml"),
+    ("toml", "toml"),
+    ("xml", "xml"),
+];
+
+/// Infer a file's language from its name, e.g. "rust" for `main.rs`
+pub fn infer_language(file_name: &str) -> Option<&'static str> {
+    if let Some((_, language)) = LANGUAGES_BY_NAME.iter().find(|(name, _)| *name == file_name) {
+        return Some(language);
+    }
+
+    let extension = Path::new(file_name).extension()?.to_str()?.to_lowercase();
+    LANGUAGES_BY_EXTENSION
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, language)| *language)
+}
+
+/// Open files sharing a language
+#[derive(Serialize, Debug, PartialEq)]
+pub struct LanguageGroup {
+    pub language: String,
+    pub count: usize,
+    pub files: Vec<String>,
+}
+
+/// Group files by inferred language, largest group first (ties by name).
+/// Files keep their order within a group.
+pub fn group_by_language(files: &[&FileInfo]) -> Vec<LanguageGroup> {
+    let mut groups: Vec<LanguageGroup> = Vec::new();
+    for file in files {
+        let language = infer_language(&file.name).unwrap_or(OTHER_LANGUAGE);
+        match groups.iter_mut().find(|g| g.language == language) {
+            Some(group) => {
+                group.count += 1;
+                group.files.push(file.path.clone());
+            }
+            None => groups.push(LanguageGroup {
+                language: language.to_string(),
+                count: 1,
+                files: vec![file.path.clone()],
+            }),
+        }
+    }
+
+    groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.language.cmp(&b.language)));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str) -> FileInfo {
+        FileInfo::for_test(path, false)
+    }
+
+    #[test]
+    fn groups_files_by_language_largest_first() {
+        let files = [
+            file("/api/main.go"),
+            file("/web/app.ts"),
+            file("/api/handler.go"),
+            file("/web/App.TSX"),
+            file("/api/Makefile"),
+            file("/notes/todo.txt"),
+            file("/api/go.mod"),
+            file("/api/server.go"),
+        ];
+        let files: Vec<&FileInfo> = files.iter().collect();
+
+        let group = |language: &str, files: &[&str]| LanguageGroup {
+            language: language.to_string(),
+            count: files.len(),
+            files: files.iter().map(|f| f.to_string()).collect(),
+        };
+        assert_eq!(
+            group_by_language(&files),
+            vec![
+                group("go", &["/api/main.go", "/api/handler.go", "/api/server.go"]),
+                group("other", &["/notes/todo.txt", "/api/go.mod"]),
+                group("typescript", &["/web/app.ts", "/web/App.TSX"]),
+                group("make", &["/api/Makefile"]),
+            ]
+        );
+    }
+}
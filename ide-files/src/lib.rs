@@ -0,0 +1,54 @@
+//! Detect the files open in running IDEs and editors.
+//!
+//! The `ide-files` binary is a thin CLI over this library; programs that want
+//! the same results without parsing its JSON can call it directly:
+//!
+//! ```no_run
+//! use ide_files::{detect_active_files, SupportedIDE};
+//!
+//! match detect_active_files(Some(SupportedIDE::GoLand)) {
+//!     Ok(result) => println!("{:?}", result.active_file),
+//!     Err(e) => eprintln!("{}", e),
+//! }
+//! ```
+
+pub mod cache;
+pub mod detector;
+pub mod detectors;
+pub mod export;
+pub mod language;
+pub mod notification;
+pub mod process;
+pub mod redact;
+pub mod status_file;
+pub mod types;
+pub mod watch;
+
+pub use detector::{DetectionError, IDEDetector, IDEDetectorManager};
+pub use detectors::DetectorOptions;
+pub use types::{DetectionResult, FileInfo, FileSource, ProcessInfo, ProcessStatus, SupportedIDE};
+
+/// Detect the files open in `ide`, or in the first running supported IDE
+/// when `None`, using the built-in detectors with default options, for the
+/// current user's processes only
+pub fn detect_active_files(ide: Option<SupportedIDE>) -> detector::DetectionResult<DetectionResult> {
+    let mut manager = IDEDetectorManager::new().with_owner_uid(process::get_current_uid());
+    detectors::register_all(&mut manager, &DetectorOptions::default());
+
+    match ide {
+        Some(ide) => manager.detect_ide(ide),
+        None => manager.auto_detect(),
+    }
+}
+
+/// Detect the files open in `ide` from processes the caller has already
+/// enumerated (see [`ProcessInfo::new`]), using the built-in detectors with
+/// default options; the system's process table is not scanned
+pub fn detect_from_processes(
+    ide: SupportedIDE,
+    processes: &[ProcessInfo],
+) -> detector::DetectionResult<DetectionResult> {
+    let mut manager = IDEDetectorManager::new();
+    detectors::register_all(&mut manager, &DetectorOptions::default());
+    manager.detect_from_processes(ide, processes)
+}
@@ -1,14 +1,11 @@
-mod detector;
-mod detectors;
-mod process;
-mod types;
-
-use clap::{Arg, Command};
-use detector::IDEDetectorManager;
-use detectors::jetbrains::JetBrainsDetector;
-use detectors::terminal::TerminalEditorDetector;
-use detectors::vscode::VSCodeDetector;
+use clap::{Arg, ArgGroup, Command};
+use ide_files::detector::{self, IDEDetectorManager};
+use ide_files::detectors::{self, DetectorOptions};
+use ide_files::{cache, export, language, notification, process, redact, status_file, types, watch};
+use std::io::Write;
 use std::process::exit;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 use types::SupportedIDE;
 
 fn main() {
@@ -34,12 +31,60 @@ fn main() {
                 .action(clap::ArgAction::SetTrue)
                 .help("Auto-detect any supported IDE"),
         )
+        .arg(
+            Arg::new("all")
+                .long("all")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["ide", "auto", "status-file", "streaming", "export"])
+                .help("Detect every running IDE and print every result (a JSON array by default; by-language and count-by-ext formats count across all IDEs)"),
+        )
+        .arg(
+            Arg::new("editors")
+                .long("editors")
+                .value_name("LIST")
+                .value_parser(parse_editors)
+                .conflicts_with("ide")
+                .help("Only auto-detect these IDEs, in this priority order (e.g. goland,vscode,vim)"),
+        )
+        .arg(
+            Arg::new("status-file")
+                .long("status-file")
+                .value_name("PATH")
+                .help("Read the active file from a status file written by an editor plugin, skipping process scanning"),
+        )
         .arg(
             Arg::new("format")
                 .long("format")
                 .value_name("FORMAT")
                 .default_value("json")
-                .help("Output format: json, plain, or paths"),
+                .help("Output format: json, ndjson, csv, plain, paths, name, summary, fzf, keyvalue, count-by-ext, by-language, or by-language-json"),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(clap::ArgAction::SetTrue)
+                .help("Print the count-by-ext and by-language aggregates as JSON"),
+        )
+        .arg(
+            Arg::new("sort")
+                .long("sort")
+                .value_name("ORDER")
+                .value_parser(["default", "path", "name", "tab"])
+                .help("Order of open files: default (active, tab, source, path), path, name, or tab"),
+        )
+        .arg(
+            Arg::new("since")
+                .long("since")
+                .value_name("DURATION")
+                .value_parser(parse_duration)
+                .help("Only keep files modified within DURATION (e.g. 90s, 30m, 2h, 1d)"),
+        )
+        .arg(
+            Arg::new("since-strict")
+                .long("since-strict")
+                .action(clap::ArgAction::SetTrue)
+                .requires("since")
+                .help("With --since, also drop files whose modification time can't be read"),
         )
         .arg(
             Arg::new("active")
@@ -47,6 +92,150 @@ fn main() {
                 .action(clap::ArgAction::SetTrue)
                 .help("Only return the currently active file"),
         )
+        .arg(
+            Arg::new("relative")
+                .long("relative")
+                .action(clap::ArgAction::SetTrue)
+                .help("Print paths relative to the project root in the json, plain and paths formats (files outside it stay absolute)"),
+        )
+        .arg(
+            Arg::new("with-ids")
+                .long("with-ids")
+                .action(clap::ArgAction::SetTrue)
+                .help("Add a stable per-file id (hash of the canonical path) for caching consumers"),
+        )
+        .arg(
+            Arg::new("redact")
+                .long("redact")
+                .action(clap::ArgAction::SetTrue)
+                .help("Replace home directory and file/project names with placeholders for sharing"),
+        )
+        .arg(
+            Arg::new("notify")
+                .long("notify")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["watch", "poll-active", "track"])
+                .help("Also show the active file and open-file count as a desktop notification (once per run; with --all, for the first IDE listed)"),
+        )
+        .arg(
+            Arg::new("export")
+                .long("export")
+                .value_name("EDITOR")
+                .value_parser(["vscode", "jetbrains"])
+                .conflicts_with_all(["redact", "watch"])
+                .help("Print a workspace file that reopens the detected files in another editor: vscode (.code-workspace) or jetbrains (.idea/workspace.xml)"),
+        )
+        .arg(
+            Arg::new("export-to")
+                .long("export-to")
+                .value_name("PATH")
+                .requires("export")
+                .help("With --export, write the workspace file to PATH instead of stdout (never overwrites)"),
+        )
+        .arg(
+            Arg::new("search-network-fs")
+                .long("search-network-fs")
+                .action(clap::ArgAction::SetTrue)
+                .help("Let the JetBrains project search descend into NFS/SSHFS/SMB mounts"),
+        )
+        .arg(
+            Arg::new("follow-symlinks")
+                .long("follow-symlinks")
+                .action(clap::ArgAction::SetTrue)
+                .help("Let the JetBrains project search follow symlinked directories"),
+        )
+        .arg(
+            Arg::new("no-focus")
+                .long("no-focus")
+                .action(clap::ArgAction::SetTrue)
+                .help("Don't use window focus or tmux panes to pick the IDE to auto-detect or the active terminal editor"),
+        )
+        .arg(
+            Arg::new("ide-config-dir")
+                .long("ide-config-dir")
+                .value_name("DIR")
+                .help("Read VSCode/Onivim state from DIR (VSCode's --user-data-dir, Onivim's config directory)"),
+        )
+        .arg(
+            Arg::new("project-root")
+                .long("project-root")
+                .value_name("DIR")
+                .action(clap::ArgAction::Append)
+                .help("Search DIR for JetBrains projects before the usual places under the home directory (repeatable; also read from $IDE_FILES_PROJECT_ROOTS)"),
+        )
+        .arg(
+            Arg::new("search-depth")
+                .long("search-depth")
+                .value_name("LEVELS")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .default_value("3")
+                .help("How many directory levels the JetBrains project search descends"),
+        )
+        .arg(
+            Arg::new("search-timeout")
+                .long("search-timeout")
+                .value_name("DURATION")
+                .value_parser(parse_interval)
+                .help("Give up the JetBrains project search after DURATION (e.g. 500ms)"),
+        )
+        .arg(
+            Arg::new("user")
+                .long("user")
+                .value_name("NAME")
+                .help("Inspect another user's editors (defaults to $SUDO_USER under sudo)"),
+        )
+        .arg(
+            Arg::new("all-users")
+                .long("all-users")
+                .action(clap::ArgAction::SetTrue)
+                .help("Consider every user's editor processes, not just the target user's"),
+        )
+        .arg(
+            Arg::new("cache")
+                .long("cache")
+                .value_name("DURATION")
+                .value_parser(parse_duration)
+                .conflicts_with_all(["all", "status-file", "streaming", "raw"])
+                .help("Reuse the last result for the same --ide and detection options (--user, --ide-config-dir, ...) if it is younger than DURATION (e.g. 2s), for status bars that poll often"),
+        )
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .action(clap::ArgAction::SetTrue)
+                .help("Keep running and print the result again whenever it changes"),
+        )
+        .arg(
+            Arg::new("poll-active")
+                .long("poll-active")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["watch", "export", "format"])
+                .help("Keep running and print \"<time>\\t<ide>\\t<path>\" each time the active file changes"),
+        )
+        .arg(
+            Arg::new("track")
+                .long("track")
+                .value_name("DURATION")
+                .value_parser(parse_interval)
+                .conflicts_with_all(["watch", "export", "format"])
+                .help("Poll the active file for DURATION (e.g. 30m, 1h), then print the time spent on each file as JSON"),
+        )
+        .arg(
+            Arg::new("interval")
+                .long("interval")
+                .value_name("DURATION")
+                .requires("streaming")
+                .value_parser(parse_interval)
+                .help("How often --poll-active and --track check the active file, or --watch checks for editors starting or exiting (e.g. 500ms, 5, 30s, 1m; default 1s and 2s)"),
+        )
+        .group(ArgGroup::new("streaming").args(["watch", "poll-active", "track"]))
+        .arg(
+            Arg::new("debounce")
+                .long("debounce")
+                .value_name("DURATION")
+                .requires("watch")
+                .value_parser(parse_duration)
+                .help("How long a --watch result must stay unchanged before it is printed (e.g. 0, 300ms; default 150ms)"),
+        )
         .arg(
             Arg::new("verbose")
                 .short('v')
@@ -54,6 +243,12 @@ fn main() {
                 .action(clap::ArgAction::SetTrue)
                 .help("Enable verbose output"),
         )
+        .arg(
+            Arg::new("raw")
+                .long("raw")
+                .action(clap::ArgAction::SetTrue)
+                .help("Print results as the detector produced them: blank paths kept, no sorting or symlink resolution, and VSCode/Sublime Text files listed once per spelling or tab instead of once (for debugging; not a stable format)"),
+        )
         .arg(
             Arg::new("debug-processes")
                 .long("debug-processes")
@@ -68,30 +263,66 @@ fn main() {
         )
         .get_matches();
 
-    // Initialize detector manager
-    let mut manager = IDEDetectorManager::new();
+    // Under sudo or a service account $HOME is not the editor user's home
+    let target_user = match matches.get_one::<String>("user") {
+        Some(name) => match process::lookup_user(name) {
+            Some(user) => Some(user),
+            None => {
+                eprintln!("Error: Unknown user '{}'", name);
+                exit(1);
+            }
+        },
+        None => process::get_sudo_user(),
+    };
+    let home_dir = target_user.as_ref().map(|user| user.home_dir.clone());
+
+    // On shared hosts only the target user's editors are of interest
+    let owner_uid = if matches.get_flag("all-users") {
+        None
+    } else {
+        target_user
+            .as_ref()
+            .map(|user| user.uid)
+            .or_else(process::get_current_uid)
+    };
 
-    // Register JetBrains detectors
-    manager.register_detector(Box::new(JetBrainsDetector::new(SupportedIDE::GoLand)));
-    manager.register_detector(Box::new(JetBrainsDetector::new(SupportedIDE::PyCharm)));
-    manager.register_detector(Box::new(JetBrainsDetector::new(SupportedIDE::IntelliJIDEA)));
-    manager.register_detector(Box::new(JetBrainsDetector::new(SupportedIDE::WebStorm)));
-    manager.register_detector(Box::new(JetBrainsDetector::new(SupportedIDE::PhpStorm)));
-    manager.register_detector(Box::new(JetBrainsDetector::new(SupportedIDE::RubyMine)));
-    manager.register_detector(Box::new(JetBrainsDetector::new(SupportedIDE::CLion)));
+    // Initialize detector manager
+    let mut manager = IDEDetectorManager::new()
+        .with_owner_uid(owner_uid)
+        .with_verbose(matches.get_flag("verbose"))
+        .with_raw(matches.get_flag("raw"))
+        .with_focus_detection(!matches.get_flag("no-focus"));
 
-    // Register terminal editor detectors (for testing)
-    manager.register_detector(Box::new(TerminalEditorDetector::new(SupportedIDE::Vim)));
-    manager.register_detector(Box::new(TerminalEditorDetector::new(SupportedIDE::Nano)));
+    let detector_options = DetectorOptions {
+        home_dir: home_dir.clone(),
+        // systemd's per-user runtime directory, where nvim puts its sockets
+        runtime_dir: target_user.as_ref().map(|user| format!("/run/user/{}", user.uid)),
+        ide_config_dir: matches.get_one::<String>("ide-config-dir").cloned(),
+        search_network_fs: matches.get_flag("search-network-fs"),
+        follow_symlinks: matches.get_flag("follow-symlinks"),
+        project_roots: project_roots(&matches),
+        search_depth: *matches.get_one::<u32>("search-depth").expect("has a default"),
+        search_timeout: matches.get_one::<std::time::Duration>("search-timeout").copied(),
+        focus_detection: !matches.get_flag("no-focus"),
+        raw: matches.get_flag("raw"),
+    };
+    detectors::register_all(&mut manager, &detector_options);
 
-    // Register VSCode detector
-    manager.register_detector(Box::new(VSCodeDetector::new()));
+    let editors = matches.get_one::<Vec<SupportedIDE>>("editors");
+    if let Some(editors) = editors {
+        manager.restrict_to(editors);
+    }
 
     let verbose = matches.get_flag("verbose");
+    if verbose {
+        if let Some(user) = &target_user {
+            eprintln!("Inspecting editors of user {} ({})", user.name, user.home_dir);
+        }
+    }
 
     // Handle debug processes
     if matches.get_flag("debug-processes") {
-        if let Err(e) = crate::process::list_all_processes() {
+        if let Err(e) = process::list_all_processes() {
             eprintln!("Error listing processes: {}", e);
         }
         return;
@@ -99,13 +330,14 @@ fn main() {
 
     // Handle debug specific process
     if let Some(process_name) = matches.get_one::<String>("debug-process") {
-        match crate::process::find_processes_by_name(process_name) {
+        match process::find_processes_by_name(process_name) {
             Ok(processes) => {
                 println!("Processes matching '{}':", process_name);
                 for process in processes {
-                    println!("PID: {}, Name: {}, Executable: {}, Title: {}", 
+                    println!("PID: {}, Name: {}, Status: {:?}, Executable: {}, Title: {}", 
                         process.pid, 
                         process.name, 
+                        process.status,
                         if process.executable_path.is_empty() { "<unknown>" } else { &process.executable_path },
                         if process.window_title.is_empty() { "<no title>" } else { &process.window_title });
                 }
@@ -124,18 +356,51 @@ fn main() {
         return;
     }
 
-    // Execute detection
-    let result = if matches.get_flag("auto") {
-        if verbose {
-            eprintln!("Auto-detecting IDEs...");
+    // A plugin-maintained status file is the most reliable source when present
+    if let Some(status_file) = matches.get_one::<String>("status-file") {
+        match status_file::read_status_file(std::path::Path::new(status_file)) {
+            Ok(detection_result) => print_result(&matches, detection_result, home_dir.as_deref(), verbose),
+            Err(e) => {
+                print_error(&e);
+                exit(1);
+            }
         }
-        manager.auto_detect()
-    } else if let Some(ide_str) = matches.get_one::<String>("ide") {
-        if let Some(ide_type) = SupportedIDE::from_str(ide_str) {
-            if verbose {
-                eprintln!("Detecting {}...", ide_type.display_name());
+        return;
+    }
+
+    if matches.get_flag("all") {
+        let results = match manager.detect_all() {
+            Ok(results) => results,
+            Err(e) => {
+                print_error(&e);
+                exit(1);
             }
-            manager.detect_ide(ide_type)
+        };
+        // One notification per run, for the first (highest priority) IDE
+        let results: Vec<_> = results
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, detection_result)| {
+                let notify = index == 0 && matches.get_flag("notify");
+                prepare_result(&matches, detection_result, home_dir.as_deref(), notify, verbose)
+            })
+            .map(|mut detection_result| {
+                if matches.get_flag("active") {
+                    detection_result.open_files.retain(|f| f.is_active);
+                }
+                detection_result
+            })
+            .collect();
+        output_all(&matches, &results);
+        return;
+    }
+
+    // Resolve which IDE to detect; None means auto-detect
+    let target_ide = if matches.get_flag("auto") {
+        None
+    } else if let Some(ide_str) = matches.get_one::<String>("ide") {
+        if let Some(ide_type) = SupportedIDE::from_str(ide_str).filter(|ide| manager.has_detector(*ide)) {
+            Some(ide_type)
         } else {
             eprintln!(
                 "Error: Unsupported IDE '{}'. Use --list-ides to see supported IDEs.",
@@ -145,27 +410,230 @@ fn main() {
         }
     } else {
         // Default to auto-detect
-        if verbose {
-            eprintln!("Auto-detecting IDEs...");
+        None
+    };
+
+    if verbose {
+        match target_ide {
+            Some(ide_type) => eprintln!("Detecting {}...", ide_type.display_name()),
+            None => eprintln!("Auto-detecting IDEs..."),
+        }
+    }
+
+    if matches.get_flag("watch") {
+        let mut watcher = watch::DetectionWatcher::new(&manager, target_ide).with_stop_flag(stop_on_interrupt());
+        if let Some(interval) = matches.get_one::<std::time::Duration>("interval") {
+            watcher = watcher.with_poll_interval(*interval);
         }
-        manager.auto_detect()
+        if let Some(window) = matches.get_one::<std::time::Duration>("debounce") {
+            watcher = watcher.with_debounce(*window);
+        }
+        // With --format ndjson, report what changed rather than every file again
+        let diff = matches.get_one::<String>("format").map(String::as_str) == Some("ndjson");
+        let mut previous = None;
+        watcher.run(|result| {
+            match result {
+                Ok(detection_result) if diff => {
+                    if let Some(mut current) = prepare_result(&matches, detection_result, home_dir.as_deref(), false, verbose) {
+                        if matches.get_flag("active") {
+                            current.open_files.retain(|f| f.is_active);
+                        }
+                        print_events(previous.as_ref(), Some(&current));
+                        previous = Some(current);
+                    }
+                }
+                Ok(detection_result) => print_result(&matches, detection_result, home_dir.as_deref(), verbose),
+                Err(e) => {
+                    print_error(&e);
+                    if diff {
+                        print_events(previous.take().as_ref(), None);
+                    }
+                }
+            }
+            // Consumers read changes as they happen, even through a pipe
+            std::io::stdout().flush().is_ok()
+        });
+        return;
+    }
+
+    if matches.get_flag("poll-active") {
+        let interval = matches
+            .get_one::<std::time::Duration>("interval")
+            .copied()
+            .unwrap_or(std::time::Duration::from_secs(1));
+        watch::ActiveFilePoller::new(&manager, target_ide, interval)
+            .with_stop_flag(stop_on_interrupt())
+            .run(
+            |active, result| {
+                println!("{}\t{}\t{}", result.timestamp, result.ide_name, active);
+                std::io::stdout().flush().is_ok()
+            },
+            |e| {
+                if verbose {
+                    print_error(e);
+                }
+            },
+        );
+        return;
+    }
+
+    if let Some(duration) = matches.get_one::<std::time::Duration>("track") {
+        let interval = matches
+            .get_one::<std::time::Duration>("interval")
+            .copied()
+            .unwrap_or(std::time::Duration::from_secs(1));
+        // Ctrl-C ends tracking early but still prints the report so far
+        let report = watch::ActiveFilePoller::new(&manager, target_ide, interval)
+            .with_stop_flag(stop_on_interrupt())
+            .track(*duration, |e| {
+                if verbose {
+                    print_error(e);
+                }
+            });
+        println!("{}", to_json_or_exit(&report));
+        return;
+    }
+
+    // A fresh enough cached result saves the process scan; only runs with the
+    // same detection settings share one
+    let cache = matches.get_one::<std::time::Duration>("cache").and_then(|max_age| {
+        let settings = format!("{:?} owner_uid={:?} editors={:?}", detector_options, owner_uid, editors);
+        Some((cache::cache_path(target_ide, &settings)?, *max_age))
+    });
+    if let Some(cached) = cache.as_ref().and_then(|(path, max_age)| cache::read(path, *max_age)) {
+        print_result(&matches, cached, home_dir.as_deref(), verbose);
+        return;
+    }
+
+    // Execute detection
+    let result = match target_ide {
+        Some(ide_type) => manager.detect_ide(ide_type),
+        None => manager.auto_detect(),
     };
+    if let (Some((path, _)), Ok(detection_result)) = (&cache, &result) {
+        if let Err(e) = cache::write(path, detection_result) {
+            eprintln!("Warning: Cannot write cache {}: {}", path.display(), e);
+        }
+    }
 
     match result {
-        Ok(detection_result) => {
-            if verbose {
-                eprintln!(
-                    "Successfully detected {}: {} open files",
-                    detection_result.ide_name,
-                    detection_result.open_files.len()
-                );
-            }
-            output_result(&matches, &detection_result);
+        Ok(detection_result) => print_result(&matches, detection_result, home_dir.as_deref(), verbose),
+        Err(e) => {
+            print_error(&e);
+            exit(1);
+        }
+    }
+}
+
+/// A flag set on SIGINT or SIGTERM, so --watch, --poll-active and --track stop
+/// cleanly (flushing output and exiting 0) instead of being killed mid-line
+fn stop_on_interrupt() -> Arc<AtomicBool> {
+    let stop = Arc::new(AtomicBool::new(false));
+    for signal in [signal_hook::consts::SIGINT, signal_hook::consts::SIGTERM] {
+        if let Err(e) = signal_hook::flag::register(signal, Arc::clone(&stop)) {
+            eprintln!("Warning: Cannot handle signal {}: {}", signal, e);
         }
+    }
+    stop
+}
+
+fn print_error(error: &detector::DetectionError) {
+    eprintln!("Error: {}", error);
+    if let Some(hint) = error.hint() {
+        eprintln!("Hint: {}", hint);
+    }
+}
+
+/// Apply the --sort, --with-ids, --notify and --redact options to the one
+/// result of this run and print it (or --export it)
+fn print_result(
+    matches: &clap::ArgMatches,
+    detection_result: types::DetectionResult,
+    home_dir: Option<&str>,
+    verbose: bool,
+) {
+    let notify = matches.get_flag("notify");
+    if let Some(detection_result) = prepare_result(matches, detection_result, home_dir, notify, verbose) {
+        output_result(matches, &detection_result);
+    }
+}
+
+/// Apply the --since, --sort, --with-ids, --redact and --relative options to
+/// a result, or --export it and return None. `notify` shows it as a desktop
+/// notification; callers set it for at most one result per run.
+fn prepare_result(
+    matches: &clap::ArgMatches,
+    mut detection_result: types::DetectionResult,
+    home_dir: Option<&str>,
+    notify: bool,
+    verbose: bool,
+) -> Option<types::DetectionResult> {
+    if verbose {
+        eprintln!(
+            "Successfully detected {}: {} open files",
+            detection_result.ide_name,
+            detection_result.open_files.len()
+        );
+    }
+    if let Some(window) = matches.get_one::<std::time::Duration>("since") {
+        filter_recent(&mut detection_result, *window, matches.get_flag("since-strict"));
+    }
+    if let Some(order) = matches.get_one::<String>("sort") {
+        sort_files(&mut detection_result.open_files, order);
+    }
+    // Before --redact, so ids identify the real files
+    if matches.get_flag("with-ids") {
+        for file in &mut detection_result.open_files {
+            file.id = Some(types::FileInfo::stable_id(&file.path));
+        }
+    }
+    if notify {
+        if let Err(e) = notification::send(&detection_result) {
+            eprintln!("Warning: Cannot show notification: {}", e);
+        }
+    }
+    if let Some(editor) = matches.get_one::<String>("export") {
+        if matches.get_flag("active") {
+            detection_result.open_files.retain(|f| f.is_active);
+        }
+        export_result(&detection_result, editor, matches.get_one::<String>("export-to"));
+        return None;
+    }
+    if matches.get_flag("redact") {
+        let mut redactor = redact::Redactor::new(detection_result.project_path.as_deref(), home_dir);
+        redactor.redact_result(&mut detection_result);
+    }
+    let format = matches.get_one::<String>("format").map(String::as_str);
+    if matches.get_flag("relative") && matches!(format, Some("json" | "plain" | "paths")) {
+        detection_result.relativize_paths();
+    }
+    Some(detection_result)
+}
+
+/// Write the --export workspace file to stdout or, with --export-to, a new file
+fn export_result(result: &types::DetectionResult, editor: &str, export_to: Option<&String>) {
+    let content = match export::export_workspace(result, editor) {
+        Ok(content) => content,
         Err(e) => {
-            eprintln!("Error: {}", e);
+            eprintln!("Error: Cannot export for {}: {}", editor, e);
             exit(1);
         }
+    };
+
+    let Some(path) = export_to else {
+        print!("{}", content);
+        return;
+    };
+
+    let path = std::path::Path::new(path);
+    let written = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()));
+    if let Err(e) = written {
+        eprintln!("Error: Cannot write {}: {}", path.display(), e);
+        exit(1);
     }
 }
 
@@ -175,36 +643,63 @@ fn output_result(matches: &clap::ArgMatches, data: &types::DetectionResult) {
         .map(|s| s.as_str())
         .unwrap_or("json");
     let active_only = matches.get_flag("active");
+    let files = if active_only {
+        data.open_files
+            .iter()
+            .filter(|f| f.is_active)
+            .collect::<Vec<_>>()
+    } else {
+        data.open_files.iter().collect::<Vec<_>>()
+    };
 
     match format {
         "plain" => {
-            let files = if active_only {
-                data.open_files
-                    .iter()
-                    .filter(|f| f.is_active)
-                    .collect::<Vec<_>>()
-            } else {
-                data.open_files.iter().collect::<Vec<_>>()
-            };
-
             for file in files {
                 println!("{}: {}", if file.is_active { "*" } else { " " }, file.path);
             }
         }
         "paths" => {
-            let files = if active_only {
-                data.open_files
-                    .iter()
-                    .filter(|f| f.is_active)
-                    .collect::<Vec<_>>()
-            } else {
-                data.open_files.iter().collect::<Vec<_>>()
-            };
-
             for file in files {
                 println!("{}", file.path);
             }
         }
+        "summary" => println!("{}", summary_line(data)),
+        "name" => {
+            for line in name_lines(&files, active_only) {
+                println!("{}", line);
+            }
+        }
+        "fzf" => {
+            for line in fzf_lines(data, &files) {
+                println!("{}", line);
+            }
+        }
+        "ndjson" => {
+            // One compact object per file, tagged with the IDE and timestamp,
+            // for jq -c, fzf and log ingestion
+            let lines = ndjson_lines(data, &files).unwrap_or_else(|e| {
+                print_error(&e);
+                exit(1);
+            });
+            for line in lines {
+                println!("{}", line);
+            }
+        }
+        "csv" => {
+            let table = csv_table(data, &files).unwrap_or_else(|e| {
+                print_error(&e);
+                exit(1);
+            });
+            print!("{}", table);
+        }
+        "count-by-ext" | "by-language" | "by-language-json" => {
+            output_aggregate(format, matches.get_flag("json"), &files);
+        }
+        "keyvalue" => {
+            for line in keyvalue_lines(data, &files) {
+                println!("{}", line);
+            }
+        }
         _ => {
             if active_only {
                 if let Some(active_file) = &data.active_file {
@@ -213,16 +708,738 @@ fn output_result(matches: &clap::ArgMatches, data: &types::DetectionResult) {
                         .iter()
                         .find(|f| f.path == *active_file)
                         .cloned();
-                    println!(
-                        "{}",
-                        serde_json::to_string_pretty(&active_file_data).unwrap()
-                    );
+                    println!("{}", to_json_or_exit(&active_file_data));
                 } else {
                     println!("null");
                 }
             } else {
-                println!("{}", serde_json::to_string_pretty(data).unwrap());
+                println!("{}", to_json_or_exit(data));
+            }
+        }
+    }
+}
+
+/// Print the files of one result, or of every --all result, counted by
+/// extension or grouped by language; as JSON with --json
+fn output_aggregate(format: &str, json: bool, files: &[&types::FileInfo]) {
+    match (format, json) {
+        ("count-by-ext", false) => {
+            for (extension, count) in count_by_extension(files) {
+                println!("{}: {}", extension, count);
+            }
+        }
+        ("count-by-ext", true) => println!("{}", extension_counts_json(&count_by_extension(files))),
+        ("by-language", false) => {
+            for group in language::group_by_language(files) {
+                println!("{} ({})", group.language, group.count);
+                for path in group.files {
+                    println!("  {}", path);
+                }
+            }
+        }
+        _ => println!("{}", to_json_or_exit(&language::group_by_language(files))),
+    }
+}
+
+/// Print the --all results: a JSON array by default, one aggregate over every
+/// IDE's files for the count-by-ext and by-language formats, and each result
+/// in turn for the others
+fn output_all(matches: &clap::ArgMatches, results: &[types::DetectionResult]) {
+    let format = matches
+        .get_one::<String>("format")
+        .map(|s| s.as_str())
+        .unwrap_or("json");
+    match format {
+        "json" => println!("{}", to_json_or_exit(&results)),
+        "count-by-ext" | "by-language" | "by-language-json" => {
+            let files: Vec<&types::FileInfo> = results.iter().flat_map(|r| &r.open_files).collect();
+            output_aggregate(format, matches.get_flag("json"), &files);
+        }
+        _ => {
+            for result in results {
+                output_result(matches, result);
+            }
+        }
+    }
+}
+
+/// Pretty-print `value` as JSON
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, detector::DetectionError> {
+    serde_json::to_string_pretty(value).map_err(|e| detector::DetectionError::SystemError {
+        message: format!("cannot serialize result as JSON: {}", e),
+    })
+}
+
+/// A `--format ndjson` line: a file tagged with the result it came from
+#[derive(serde::Serialize)]
+struct NdjsonFile<'a> {
+    ide_name: &'a str,
+    timestamp: &'a str,
+    #[serde(flatten)]
+    file: &'a types::FileInfo,
+}
+
+/// Each file as a single-line JSON object with the result's `ide_name` and `timestamp` added
+fn ndjson_lines(
+    data: &types::DetectionResult,
+    files: &[&types::FileInfo],
+) -> Result<Vec<String>, detector::DetectionError> {
+    files
+        .iter()
+        .map(|file| {
+            let line = NdjsonFile {
+                ide_name: &data.ide_name,
+                timestamp: &data.timestamp,
+                file,
+            };
+            serde_json::to_string(&line).map_err(|e| detector::DetectionError::SystemError {
+                message: format!("cannot serialize result as JSON: {}", e),
+            })
+        })
+        .collect()
+}
+
+/// A `--watch --format ndjson` line: a file opened, closed or activated
+#[derive(serde::Serialize)]
+struct NdjsonEvent<'a> {
+    event: watch::FileEventKind,
+    path: &'a str,
+    ide_name: &'a str,
+    timestamp: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seq: Option<u64>,
+}
+
+/// Each change from `previous` to `current` as a single-line JSON object,
+/// stamped with `current`'s timestamp and seq (or the current time when no IDE was detected)
+fn ndjson_events(
+    previous: Option<&types::DetectionResult>,
+    current: Option<&types::DetectionResult>,
+) -> Result<Vec<String>, detector::DetectionError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let timestamp = current.map_or(now.as_str(), |c| c.timestamp.as_str());
+    watch::diff_results(previous, current)
+        .iter()
+        .map(|event| {
+            let line = NdjsonEvent {
+                event: event.kind,
+                path: &event.path,
+                ide_name: &event.ide_name,
+                timestamp,
+                seq: current.and_then(|c| c.seq),
+            };
+            serde_json::to_string(&line).map_err(|e| detector::DetectionError::SystemError {
+                message: format!("cannot serialize result as JSON: {}", e),
+            })
+        })
+        .collect()
+}
+
+/// Print the `--watch --format ndjson` events between two results
+fn print_events(previous: Option<&types::DetectionResult>, current: Option<&types::DetectionResult>) {
+    let lines = ndjson_events(previous, current).unwrap_or_else(|e| {
+        print_error(&e);
+        exit(1);
+    });
+    for line in lines {
+        println!("{}", line);
+    }
+}
+
+/// One `key=value` per line for grep/awk; missing values are empty so every
+/// key is always present
+fn keyvalue_lines(data: &types::DetectionResult, files: &[&types::FileInfo]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    lines.push(format!("format_version={}", data.format_version));
+    lines.push(format!("ide={}", escape_keyvalue(&data.ide_name)));
+    lines.push(format!("version={}", escape_keyvalue(&optional(data.ide_version.clone()))));
+    lines.push(format!("project={}", escape_keyvalue(&optional(data.project_path.clone()))));
+    lines.push(format!("active={}", escape_keyvalue(&optional(data.active_file.clone()))));
+    lines.push(format!("group_count={}", optional(data.group_count.map(|n| n.to_string()))));
+    if let Some(seq) = data.seq {
+        lines.push(format!("seq={}", seq));
+    }
+    lines.push(format!("file_count={}", files.len()));
+    for (index, file) in files.iter().enumerate() {
+        let source = serde_json::to_value(file.source).unwrap_or_default();
+        lines.push(format!("file.{}.path={}", index, escape_keyvalue(&file.path)));
+        lines.push(format!("file.{}.name={}", index, escape_keyvalue(&file.name)));
+        lines.push(format!("file.{}.active={}", index, file.is_active));
+        lines.push(format!("file.{}.modified={}", index, file.is_modified));
+        lines.push(format!("file.{}.tab_index={}", index, optional(file.tab_index.map(|n| n.to_string()))));
+        lines.push(format!("file.{}.source={}", index, source.as_str().unwrap_or_default()));
+        lines.push(format!("file.{}.cursor_line={}", index, optional(file.cursor_line.map(|n| n.to_string()))));
+        lines.push(format!("file.{}.cursor_column={}", index, optional(file.cursor_column.map(|n| n.to_string()))));
+        lines.push(format!("file.{}.project={}", index, escape_keyvalue(&optional(file.project_path.clone()))));
+        lines.push(format!("file.{}.read_only={}", index, file.read_only));
+        lines.push(format!("file.{}.pinned={}", index, file.is_pinned));
+        lines.push(format!("file.{}.preview={}", index, file.is_preview));
+        lines.push(format!("file.{}.real_path={}", index, escape_keyvalue(&optional(file.real_path.clone()))));
+        let terminal = file.terminal.as_ref();
+        lines.push(format!("file.{}.terminal={}", index, escape_keyvalue(&optional(terminal.map(|t| t.name.clone())))));
+        lines.push(format!("file.{}.terminal_window={}", index, optional(terminal.and_then(|t| t.window_id.clone()))));
+        lines.push(format!("file.{}.id={}", index, optional(file.id.clone())));
+    }
+    lines
+}
+
+/// Most compact output, for status bars: basenames only. With --active
+/// there's a single file, so no marker is needed.
+fn name_lines(files: &[&types::FileInfo], active_only: bool) -> Vec<String> {
+    files
+        .iter()
+        .map(|file| {
+            if active_only {
+                file.name.clone()
+            } else {
+                format!("{} {}", if file.is_active { "*" } else { " " }, file.name)
+            }
+        })
+        .collect()
+}
+
+/// One "display<TAB>path" line per file: pick with
+/// `fzf --ansi --delimiter='\t' --with-nth=1` and read the path back from field 2
+fn fzf_lines(data: &types::DetectionResult, files: &[&types::FileInfo]) -> Vec<String> {
+    let project_dir = data
+        .project_path
+        .as_deref()
+        .and_then(|p| std::path::Path::new(p).file_name())
+        .and_then(|n| n.to_str());
+
+    files
+        .iter()
+        .map(|file| {
+            let marker = if file.is_active { "*" } else { " " };
+            let display = match file.project_name.as_deref().or(project_dir) {
+                Some(project) => format!(
+                    "{} {} \x1b[2m{}\x1b[0m",
+                    marker,
+                    escape_fzf_field(&file.name),
+                    escape_fzf_field(project)
+                ),
+                None => format!("{} {}", marker, escape_fzf_field(&file.name)),
+            };
+            format!("{}\t{}", display, escape_fzf_field(&file.path))
+        })
+        .collect()
+}
+
+/// "<ide> · <project> · <active file> (+N more)" for status lines, from
+/// basenames; the project part is left out when there is none
+fn summary_line(data: &types::DetectionResult) -> String {
+    let basename = |path: &str| {
+        std::path::Path::new(path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(path)
+            .to_string()
+    };
+    let active = data.active_file.as_deref();
+
+    let mut parts = vec![data.ide_name.clone()];
+    let project = data.project_path.as_deref().or_else(|| {
+        let active = data.open_files.iter().find(|f| Some(f.path.as_str()) == active)?;
+        active.project_path.as_deref()
+    });
+    parts.extend(project.map(basename));
+    parts.push(match active {
+        Some(active) => {
+            let others = data.open_files.iter().filter(|f| f.path != active).count();
+            if others == 0 {
+                basename(active)
+            } else {
+                format!("{} (+{} more)", basename(active), others)
+            }
+        }
+        None => "(no active file)".to_string(),
+    });
+    parts.join(" · ")
+}
+
+/// A header row and one row per file, for spreadsheets; missing values are empty cells
+fn csv_table(data: &types::DetectionResult, files: &[&types::FileInfo]) -> Result<String, detector::DetectionError> {
+    let to_error = |e: csv::Error| detector::DetectionError::SystemError {
+        message: format!("cannot write result as CSV: {}", e),
+    };
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(["path", "name", "is_active", "is_modified", "tab_index", "project_name", "ide_name"])
+        .map_err(to_error)?;
+    for file in files {
+        writer
+            .write_record([
+                file.path.as_str(),
+                file.name.as_str(),
+                &file.is_active.to_string(),
+                &file.is_modified.to_string(),
+                &file.tab_index.map(|n| n.to_string()).unwrap_or_default(),
+                file.project_name.as_deref().unwrap_or_default(),
+                data.ide_name.as_str(),
+            ])
+            .map_err(to_error)?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| detector::DetectionError::SystemError {
+        message: format!("cannot write result as CSV: {}", e),
+    })?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Pretty-print `value` as JSON, or report why not and exit
+fn to_json_or_exit<T: serde::Serialize>(value: &T) -> String {
+    to_json(value).unwrap_or_else(|e| {
+        print_error(&e);
+        exit(1);
+    })
+}
+
+/// The `--project-root` directories, then those in `$IDE_FILES_PROJECT_ROOTS`
+/// (separated like `$PATH`)
+fn project_roots(matches: &clap::ArgMatches) -> Vec<String> {
+    let mut roots: Vec<String> = matches
+        .get_many::<String>("project-root")
+        .map(|roots| roots.cloned().collect())
+        .unwrap_or_default();
+    if let Some(env_roots) = std::env::var_os("IDE_FILES_PROJECT_ROOTS") {
+        roots.extend(
+            std::env::split_paths(&env_roots)
+                .filter(|root| !root.as_os_str().is_empty())
+                .map(|root| root.to_string_lossy().into_owned()),
+        );
+    }
+    roots
+}
+
+/// Parse the comma-separated `--editors` list into IDEs, dropping repeats
+fn parse_editors(value: &str) -> Result<Vec<SupportedIDE>, String> {
+    let mut editors = Vec::new();
+    for name in value.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        let ide = SupportedIDE::from_str(name)
+            .ok_or_else(|| format!("unknown IDE '{}' (see --list-ides)", name))?;
+        if !editors.contains(&ide) {
+            editors.push(ide);
+        }
+    }
+    if editors.is_empty() {
+        return Err("expected at least one IDE".to_string());
+    }
+    Ok(editors)
+}
+
+/// Parse a `--since` duration: a number with an optional ms/s/m/h/d unit (seconds by default)
+fn parse_duration(value: &str) -> Result<std::time::Duration, String> {
+    let value = value.trim();
+    let (number, unit_millis) = match value.strip_suffix("ms") {
+        Some(number) => (number, 1),
+        None => match value.char_indices().last() {
+            Some((i, 's')) => (&value[..i], 1000),
+            Some((i, 'm')) => (&value[..i], 60 * 1000),
+            Some((i, 'h')) => (&value[..i], 60 * 60 * 1000),
+            Some((i, 'd')) => (&value[..i], 24 * 60 * 60 * 1000),
+            _ => (value, 1000),
+        },
+    };
+
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(unit_millis))
+        .map(std::time::Duration::from_millis)
+        .ok_or_else(|| format!("invalid duration '{}' (expected e.g. 500ms, 90s, 30m, 2h, 1d)", value))
+}
+
+/// Parse a `--interval` duration, which unlike `--since` can't be zero
+fn parse_interval(value: &str) -> Result<std::time::Duration, String> {
+    parse_duration(value).and_then(|interval| {
+        if interval.is_zero() {
+            Err("interval must be greater than zero".to_string())
+        } else {
+            Ok(interval)
+        }
+    })
+}
+
+/// Keep only files modified within `window`. Files that can't be stat'd
+/// (e.g. on a remote host) are kept unless `strict`.
+fn filter_recent(result: &mut types::DetectionResult, window: std::time::Duration, strict: bool) {
+    let now = std::time::SystemTime::now();
+    result.open_files.retain(|file| {
+        match std::fs::metadata(&file.path).and_then(|m| m.modified()) {
+            // Modification times in the future count as recent
+            Ok(modified) => now.duration_since(modified).map_or(true, |age| age <= window),
+            Err(_) => !strict,
+        }
+    });
+
+    if let Some(active_file) = &result.active_file {
+        if !result.open_files.iter().any(|f| &f.path == active_file) {
+            result.active_file = None;
+        }
+    }
+}
+
+/// Reorder files for an explicit `--sort`; "default" keeps the manager's ordering
+fn sort_files(files: &mut [types::FileInfo], order: &str) {
+    match order {
+        "path" => files.sort_by(|a, b| a.path.cmp(&b.path)),
+        "name" => files.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.path.cmp(&b.path))),
+        "tab" => files.sort_by_key(|f| f.tab_index.unwrap_or(usize::MAX)),
+        _ => {}
+    }
+}
+
+/// Count files per (lowercased) extension, most common first. Files without
+/// an extension are grouped under "(none)".
+fn count_by_extension(files: &[&types::FileInfo]) -> Vec<(String, usize)> {
+    let mut counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    for file in files {
+        let extension = std::path::Path::new(&file.name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_else(|| "(none)".to_string());
+        *counts.entry(extension).or_insert(0) += 1;
+    }
+
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// The extension counts as a JSON object, keeping their order. Built by
+/// hand: serde_json's map would re-sort keys alphabetically.
+fn extension_counts_json(counts: &[(String, usize)]) -> String {
+    let entries: Vec<String> = counts
+        .iter()
+        .map(|(extension, count)| format!("  {}: {}", serde_json::Value::from(extension.as_str()), count))
+        .collect();
+    if entries.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{\n{}\n}}", entries.join(",\n"))
+    }
+}
+
+/// Keep a keyvalue entry on one line
+fn escape_keyvalue(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+/// Escape characters that would break the tab-delimited, line-based fzf format
+fn escape_fzf_field(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stands in for a future field that fails to serialize
+    struct Unserializable;
+
+    impl serde::Serialize for Unserializable {
+        fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("not representable"))
+        }
+    }
+
+    /// The library's test helpers aren't built for the binary's tests
+    fn file(path: &str, is_active: bool) -> types::FileInfo {
+        types::FileInfo {
+            is_active,
+            ..types::FileInfo::new(path, types::FileSource::SessionState)
+        }
+    }
+
+    fn result(ide_name: &str, files: Vec<types::FileInfo>) -> types::DetectionResult {
+        types::DetectionResult {
+            format_version: types::FORMAT_VERSION,
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+            ide_name: ide_name.to_string(),
+            ide_version: None,
+            active_file: files.iter().find(|f| f.is_active).map(|f| f.path.clone()),
+            open_files: files,
+            project_path: None,
+            group_count: None,
+            project_language: None,
+            seq: None,
+        }
+    }
+
+    #[test]
+    fn json_output_carries_the_format_version() {
+        let result = result("Vim", vec![file("/src/main.rs", true)]);
+
+        let json: serde_json::Value = serde_json::from_str(&to_json(&result).unwrap()).unwrap();
+        assert_eq!(json["format_version"], types::FORMAT_VERSION);
+        assert_eq!(types::FORMAT_VERSION, 1);
+    }
+
+    #[test]
+    fn ndjson_prints_one_tagged_object_per_file() {
+        let result = result("Vim", vec![file("/src/Cargo.toml", true), file("/src/README.md", false)]);
+
+        let files: Vec<&types::FileInfo> = result.open_files.iter().collect();
+        let lines = ndjson_lines(&result, &files).unwrap();
+
+        assert_eq!(lines.len(), 2);
+        for (line, file) in lines.iter().zip(&result.open_files) {
+            assert!(!line.contains('\n'));
+            let json: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(json["path"], file.path.as_str());
+            assert_eq!(json["is_active"], file.is_active);
+            assert_eq!(json["ide_name"], "Vim");
+            assert_eq!(json["timestamp"], result.timestamp.as_str());
+        }
+    }
+
+    #[test]
+    fn watch_ndjson_events_carry_the_current_seq() {
+        let mut result = result("Vim", vec![file("/src/Cargo.toml", true)]);
+        result.seq = Some(1);
+
+        let events: Vec<serde_json::Value> = ndjson_events(None, Some(&result))
+            .unwrap()
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["event"], "opened");
+        assert_eq!(events[1]["event"], "activated");
+        for event in &events {
+            assert_eq!(event["path"], "/src/Cargo.toml");
+            assert_eq!(event["seq"], 1);
+        }
+        let closed = ndjson_events(Some(&result), None).unwrap();
+        assert_eq!(closed.len(), 1);
+        assert!(!closed[0].contains("seq"));
+    }
+
+    #[test]
+    fn durations_accept_milliseconds() {
+        assert_eq!(parse_duration("500ms"), Ok(std::time::Duration::from_millis(500)));
+        assert_eq!(parse_duration("2m"), Ok(std::time::Duration::from_secs(120)));
+        assert_eq!(parse_duration("5"), Ok(std::time::Duration::from_secs(5)));
+        assert!(parse_interval("0ms").is_err());
+    }
+
+    #[test]
+    fn summary_names_ide_project_and_active_file() {
+        let mut result = result(
+            "GoLand",
+            vec![
+                file("/src/myproj/main.go", true),
+                file("/src/myproj/go.mod", false),
+                file("/src/myproj/api/handler.go", false),
+                file("/src/myproj/README.md", false),
+            ],
+        );
+        result.project_path = Some("/src/myproj".to_string());
+
+        assert_eq!(summary_line(&result), "GoLand · myproj · main.go (+3 more)");
+        result.open_files.truncate(1);
+        assert_eq!(summary_line(&result), "GoLand · myproj · main.go");
+        result.active_file = None;
+        assert_eq!(summary_line(&result), "GoLand · myproj · (no active file)");
+        result.project_path = None;
+        assert_eq!(summary_line(&result), "GoLand · (no active file)");
+    }
+
+    #[test]
+    fn keyvalue_lists_metadata_then_zero_based_file_keys() {
+        let mut result = result("GoLand", vec![file("/src/api/main.go", true), file("/src/api/go.mod", false)]);
+        result.project_path = Some("/src/api".to_string());
+        result.open_files[0].tab_index = Some(0);
+        result.open_files[0].cursor_line = Some(12);
+        result.open_files[1].is_modified = true;
+        result.open_files[1].path = "/src/api/line\nbreak\\.mod".to_string();
+
+        let files: Vec<&types::FileInfo> = result.open_files.iter().collect();
+        let lines = keyvalue_lines(&result, &files);
+        let keys: Vec<&str> = lines.iter().map(|line| line.split_once('=').unwrap().0).collect();
+        let file_keys = |index: usize| {
+            [
+                "path", "name", "active", "modified", "tab_index", "source", "cursor_line", "cursor_column",
+                "project", "read_only", "pinned", "preview", "real_path", "terminal", "terminal_window", "id",
+            ]
+            .map(|key| format!("file.{}.{}", index, key))
+        };
+        let mut expected: Vec<String> = ["format_version", "ide", "version", "project", "active", "group_count", "file_count"]
+            .map(String::from)
+            .to_vec();
+        expected.extend(file_keys(0));
+        expected.extend(file_keys(1));
+        assert_eq!(keys, expected);
+
+        assert_eq!(
+            &lines[..7],
+            [
+                "format_version=1",
+                "ide=GoLand",
+                "version=",
+                "project=/src/api",
+                "active=/src/api/main.go",
+                "group_count=",
+                "file_count=2",
+            ]
+        );
+        for line in [
+            "file.0.path=/src/api/main.go",
+            "file.0.active=true",
+            "file.0.tab_index=0",
+            "file.0.source=session_state",
+            "file.0.cursor_line=12",
+            "file.0.cursor_column=",
+            "file.1.path=/src/api/line\\nbreak\\\\.mod",
+            "file.1.active=false",
+            "file.1.modified=true",
+            "file.1.tab_index=",
+        ] {
+            assert!(lines.iter().any(|l| l == line), "{}", line);
+        }
+
+        // A --watch seq comes after the metadata it belongs to
+        result.seq = Some(7);
+        assert_eq!(keyvalue_lines(&result, &[])[6..], ["seq=7", "file_count=0"]);
+    }
+
+    #[test]
+    fn name_format_prints_basenames_only() {
+        let files = [file("/src/app/main.rs", false), file("/src/app/Makefile", true)];
+        let files: Vec<&types::FileInfo> = files.iter().collect();
+
+        assert_eq!(name_lines(&files, false), ["  main.rs", "* Makefile"]);
+        assert_eq!(name_lines(&files[1..], true), ["Makefile"]);
+        assert!(name_lines(&files, false).iter().all(|line| !line.contains('/')));
+    }
+
+    #[test]
+    fn fzf_prints_a_display_column_and_an_escaped_path_column() {
+        let mut result = result(
+            "Vim",
+            vec![file("/src/app/main.rs", true), file("/src/app/odd\tname\\.rs", false)],
+        );
+        result.project_path = Some("/src/app".to_string());
+        result.open_files[1].project_name = Some("lib\nrary".to_string());
+
+        let files: Vec<&types::FileInfo> = result.open_files.iter().collect();
+        let lines = fzf_lines(&result, &files);
+        assert_eq!(
+            lines,
+            [
+                "* main.rs \x1b[2mapp\x1b[0m\t/src/app/main.rs",
+                "  odd\\tname\\\\.rs \x1b[2mlib\\nrary\x1b[0m\t/src/app/odd\\tname\\\\.rs",
+            ]
+        );
+        // Exactly two tab-separated columns, whatever the file names hold
+        assert!(lines.iter().all(|line| line.split('\t').count() == 2));
+
+        result.project_path = None;
+        result.open_files.truncate(1);
+        let files: Vec<&types::FileInfo> = result.open_files.iter().collect();
+        assert_eq!(fzf_lines(&result, &files), ["* main.rs\t/src/app/main.rs"]);
+    }
+
+    #[test]
+    fn extensions_are_counted_most_common_first() {
+        let files = [
+            file("/src/main.rs", true),
+            file("/src/Makefile", false),
+            file("/src/lib.RS", false),
+            file("/src/README.md", false),
+            file("/src/LICENSE", false),
+            file("/src/types.rs", false),
+            file("/src/.gitignore", false),
+        ];
+        let files: Vec<&types::FileInfo> = files.iter().collect();
+
+        // Ties are broken by name; dotfiles and bare names have no extension
+        let counts = count_by_extension(&files);
+        let expected = [("(none)", 3), ("rs", 3), ("md", 1)].map(|(ext, n)| (ext.to_string(), n));
+        assert_eq!(counts, expected);
+        assert_eq!(extension_counts_json(&counts), "{\n  \"(none)\": 3,\n  \"rs\": 3,\n  \"md\": 1\n}");
+        assert_eq!(extension_counts_json(&[]), "{}");
+    }
+
+    #[test]
+    fn since_keeps_recently_modified_files_and_unreadable_ones_unless_strict() {
+        use std::time::{Duration, SystemTime};
+
+        let dir = std::env::temp_dir().join(format!("ide-files-since-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let now = SystemTime::now();
+        let touch = |name: &str, modified: SystemTime| {
+            let path = dir.join(name);
+            std::fs::File::create(&path).unwrap().set_modified(modified).unwrap();
+            path.to_string_lossy().into_owned()
+        };
+        let fresh = touch("fresh.rs", now - Duration::from_secs(60));
+        let stale = touch("stale.rs", now - Duration::from_secs(3 * 3600));
+        let future = touch("future.rs", now + Duration::from_secs(3600));
+        let missing = dir.join("missing.rs").to_string_lossy().into_owned();
+        let files = || {
+            vec![file(&stale, true), file(&fresh, false), file(&future, false), file(&missing, false)]
+        };
+        let paths = |result: &types::DetectionResult| -> Vec<String> {
+            result.open_files.iter().map(|f| f.path.clone()).collect()
+        };
+
+        let mut lenient = result("Vim", files());
+        filter_recent(&mut lenient, Duration::from_secs(3600), false);
+        assert_eq!(paths(&lenient), [fresh.as_str(), &future, &missing]);
+        // The active file was filtered out, so nothing is active any more
+        assert_eq!(lenient.active_file, None);
+
+        let mut strict = result("Vim", files());
+        filter_recent(&mut strict, Duration::from_secs(3600), true);
+        assert_eq!(paths(&strict), [fresh.as_str(), &future]);
+
+        let mut wide = result("Vim", files());
+        filter_recent(&mut wide, Duration::from_secs(24 * 3600), true);
+        assert_eq!(wide.active_file.as_ref(), Some(&stale));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn csv_quotes_paths_with_commas_and_quotes() {
+        let mut result = result("Vim", vec![file(r#"/notes/a, "b".md"#, true)]);
+        result.open_files[0].tab_index = Some(2);
+
+        let files: Vec<&types::FileInfo> = result.open_files.iter().collect();
+        assert_eq!(
+            csv_table(&result, &files).unwrap(),
+            concat!(
+                "path,name,is_active,is_modified,tab_index,project_name,ide_name\n",
+                "\"/notes/a, \"\"b\"\".md\",\"a, \"\"b\"\".md\",true,false,2,,Vim\n",
+            )
+        );
+    }
+
+    #[test]
+    fn editors_list_is_validated_and_deduplicated() {
+        assert_eq!(
+            parse_editors("goland, vscode,vim,goland"),
+            Ok(vec![SupportedIDE::GoLand, SupportedIDE::VSCode, SupportedIDE::Vim])
+        );
+        assert!(parse_editors("goland,emacs").unwrap_err().contains("emacs"));
+        assert!(parse_editors(",").is_err());
+    }
+
+    #[test]
+    fn to_json_reports_serialization_errors() {
+        match to_json(&Unserializable) {
+            Err(detector::DetectionError::SystemError { message }) => {
+                assert!(message.contains("not representable"), "{}", message)
             }
+            other => panic!("expected a SystemError, got {:?}", other),
         }
     }
 }
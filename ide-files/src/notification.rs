@@ -0,0 +1,91 @@
+use crate::types::DetectionResult;
+use std::path::Path;
+
+/// Notification title and body for a result: the IDE, then the active file
+/// (with its project) and how many files are open
+pub fn summarize(result: &DetectionResult) -> (String, String) {
+    let active = result.active_file.as_deref().map(|path| {
+        let name = Path::new(path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(path);
+        let project = result
+            .project_path
+            .as_deref()
+            .and_then(|p| Path::new(p).file_name())
+            .and_then(|n| n.to_str());
+        match project {
+            Some(project) => format!("{} ({})", name, project),
+            None => name.to_string(),
+        }
+    });
+
+    let count = result.open_files.len();
+    let body = format!(
+        "{}\n{} open file{}",
+        active.as_deref().unwrap_or("No active file"),
+        count,
+        if count == 1 { "" } else { "s" }
+    );
+
+    (result.ide_name.clone(), body)
+}
+
+/// Show the result's summary as a desktop notification
+pub fn send(result: &DetectionResult) -> Result<(), String> {
+    let (title, body) = summarize(result);
+
+    #[cfg(target_os = "macos")]
+    return {
+        // AppleScript string literals only need quotes and backslashes escaped
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let script = format!("display notification {} with title {}", quote(&body), quote(&title));
+        let status = std::process::Command::new("osascript")
+            .args(["-e", &script])
+            .status()
+            .map_err(|e| format!("cannot run osascript: {}", e))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("osascript exited with {}", status))
+        }
+    };
+
+    #[cfg(not(target_os = "macos"))]
+    return notify_rust::Notification::new()
+        .summary(&title)
+        .body(&body)
+        .show()
+        .map(|_| ())
+        .map_err(|e| e.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FileInfo;
+
+    fn result(active_file: Option<&str>, project_path: Option<&str>, paths: &[&str]) -> DetectionResult {
+        let files = paths.iter().map(|path| FileInfo::for_test(path, Some(*path) == active_file)).collect();
+        DetectionResult {
+            project_path: project_path.map(|p| p.to_string()),
+            ..DetectionResult::for_test("GoLand", files)
+        }
+    }
+
+    #[test]
+    fn summary_names_the_active_file_and_counts_open_files() {
+        let cases = [
+            (
+                result(Some("/src/api/main.go"), Some("/src/api"), &["/src/api/main.go", "/src/api/db.go"]),
+                "main.go (api)\n2 open files",
+            ),
+            (result(Some("/tmp/notes.md"), None, &["/tmp/notes.md"]), "notes.md\n1 open file"),
+            (result(None, Some("/src/api"), &[]), "No active file\n0 open files"),
+        ];
+
+        for (result, body) in cases {
+            assert_eq!(summarize(&result), ("GoLand".to_string(), body.to_string()));
+        }
+    }
+}
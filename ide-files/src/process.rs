@@ -1,19 +1,30 @@
 use crate::detector::DetectionResult;
-use crate::types::ProcessInfo;
+use crate::types::{ProcessInfo, ProcessStatus, SupportedIDE, UserAccount};
 
-pub fn find_all_processes() -> DetectionResult<Vec<ProcessInfo>> {
+/// List running processes; with `owner_uid`, only those owned by that user
+/// (on platforms that expose process ownership). Window titles (and on macOS,
+/// working directories) are looked up only for processes `wants_titles`
+/// accepts, judged on name and executable; the rest get their command line
+/// as a synthetic title (Linux) or none.
+pub fn find_all_processes(
+    owner_uid: Option<u32>,
+    wants_titles: impl Fn(&ProcessInfo) -> bool,
+) -> DetectionResult<Vec<ProcessInfo>> {
     #[cfg(target_os = "windows")]
-    return find_processes_windows();
+    return {
+        let _ = owner_uid;
+        find_processes_windows(wants_titles)
+    };
 
     #[cfg(target_os = "macos")]
-    return find_processes_macos();
+    return find_processes_macos(owner_uid, wants_titles);
 
     #[cfg(target_os = "linux")]
-    return find_processes_linux();
+    return find_processes_linux(owner_uid, wants_titles);
 }
 
 #[cfg(target_os = "windows")]
-fn find_processes_windows() -> DetectionResult<Vec<ProcessInfo>> {
+fn find_processes_windows(wants_titles: impl Fn(&ProcessInfo) -> bool) -> DetectionResult<Vec<ProcessInfo>> {
     use crate::detector::DetectionError;
     use std::ffi::CStr;
     use std::mem;
@@ -40,14 +51,25 @@ fn find_processes_windows() -> DetectionResult<Vec<ProcessInfo>> {
                     .to_string_lossy()
                     .to_string();
 
-                let window_title = get_window_title_by_pid(entry.th32ProcessID);
-
-                processes.push(ProcessInfo {
+                let mut process = ProcessInfo {
                     pid: entry.th32ProcessID,
                     name: process_name,
-                    window_title,
+                    window_title: String::new(),
+                    window_titles: Vec::new(),
+                    title_is_synthetic: false,
                     executable_path: String::new(), // TODO: Get full path
-                });
+                    cwd: String::new(),
+                    status: ProcessStatus::Unknown,
+                    start_time: None,
+                };
+                // Each lookup enumerates every top-level window
+                if wants_titles(&process) {
+                    process.window_title = get_window_title_by_pid(process.pid);
+                    if !process.window_title.is_empty() {
+                        process.window_titles = vec![process.window_title.clone()];
+                    }
+                }
+                processes.push(process);
 
                 if Process32Next(snapshot, &mut entry) != TRUE {
                     break;
@@ -61,94 +83,340 @@ fn find_processes_windows() -> DetectionResult<Vec<ProcessInfo>> {
     Ok(processes)
 }
 
+/// State shared with `enum_windows_proc` through EnumWindows' lparam
+#[cfg(target_os = "windows")]
+struct WindowSearch {
+    pid: u32,
+    /// Title of a visible unowned top-level window
+    title: String,
+    /// Title of a visible owned window (dialog, tool window), used if no
+    /// unowned one has a title
+    owned_title: String,
+}
+
+/// Title of the process's main window, or an empty string if it has none
 #[cfg(target_os = "windows")]
 fn get_window_title_by_pid(pid: u32) -> String {
-    use std::mem;
-    use std::ptr;
-    use winapi::um::winuser::*;
+    use winapi::shared::minwindef::LPARAM;
+    use winapi::um::winuser::EnumWindows;
 
-    unsafe {
-        let mut window_title = String::new();
+    let mut search = WindowSearch {
+        pid,
+        title: String::new(),
+        owned_title: String::new(),
+    };
 
+    unsafe {
         EnumWindows(
             Some(enum_windows_proc),
-            &mut window_title as *mut String as isize,
+            &mut search as *mut WindowSearch as LPARAM,
         );
+    }
 
-        window_title
+    if search.title.is_empty() {
+        search.owned_title
+    } else {
+        search.title
     }
 }
 
 #[cfg(target_os = "windows")]
 unsafe extern "system" fn enum_windows_proc(
     hwnd: winapi::shared::windef::HWND,
-    lparam: isize,
-) -> i32 {
-    // Implement window enumeration logic
-    1
+    lparam: winapi::shared::minwindef::LPARAM,
+) -> winapi::shared::minwindef::BOOL {
+    use winapi::shared::minwindef::{FALSE, TRUE};
+    use winapi::um::winuser::*;
+
+    let search = &mut *(lparam as *mut WindowSearch);
+
+    if IsWindowVisible(hwnd) == FALSE {
+        return TRUE;
+    }
+
+    let mut owner_pid = 0u32;
+    GetWindowThreadProcessId(hwnd, &mut owner_pid);
+    if owner_pid != search.pid {
+        return TRUE;
+    }
+
+    let length = GetWindowTextLengthW(hwnd);
+    if length <= 0 {
+        return TRUE;
+    }
+    let mut buffer = vec![0u16; length as usize + 1];
+    let copied = GetWindowTextW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32);
+    if copied <= 0 {
+        return TRUE;
+    }
+    let title = String::from_utf16_lossy(&buffer[..copied as usize]);
+
+    if GetWindow(hwnd, GW_OWNER).is_null() {
+        search.title = title;
+        // Stop enumerating: this is the main window
+        return FALSE;
+    }
+    if search.owned_title.is_empty() {
+        search.owned_title = title;
+    }
+    TRUE
 }
 
 #[cfg(target_os = "macos")]
-fn find_processes_macos() -> DetectionResult<Vec<ProcessInfo>> {
-    // TODO: Implement macOS version
-    Ok(vec![])
+fn find_processes_macos(
+    owner_uid: Option<u32>,
+    wants_titles: impl Fn(&ProcessInfo) -> bool,
+) -> DetectionResult<Vec<ProcessInfo>> {
+    use crate::detector::DetectionError;
+
+    // comm is the executable path and may contain spaces, so it goes last
+    let output = std::process::Command::new("ps")
+        .args(["-axo", "pid=,uid=,state=,etime=,comm="])
+        .output()
+        .map_err(|e| DetectionError::SystemError {
+            message: format!("Failed to run ps: {}", e),
+        })?;
+    if !output.status.success() {
+        return Err(DetectionError::SystemError {
+            message: format!(
+                "ps failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+
+    let window_titles = get_macos_window_titles();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .ok();
+
+    let mut processes = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(pid), Some(uid), Some(state), Some(elapsed)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let (Ok(pid), Ok(uid)) = (pid.parse::<u32>(), uid.parse::<u32>()) else {
+            continue;
+        };
+        if owner_uid.is_some_and(|owner| owner != uid) {
+            continue;
+        }
+
+        // Rejoin the executable path that split_whitespace took apart
+        let executable_path = fields.collect::<Vec<_>>().join(" ");
+        let name = std::path::Path::new(&executable_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(&executable_path)
+            .to_string();
+
+        let process_window_titles = window_titles.get(&pid).cloned().unwrap_or_default();
+        let window_title = process_window_titles.last().cloned().unwrap_or_default();
+
+        processes.push(ProcessInfo {
+            pid,
+            name,
+            window_title,
+            window_titles: process_window_titles,
+            title_is_synthetic: false,
+            executable_path,
+            cwd: String::new(),
+            status: state
+                .chars()
+                .next()
+                .map(ProcessStatus::from_stat_char)
+                .unwrap_or(ProcessStatus::Unknown),
+            start_time: now
+                .zip(parse_elapsed_time(elapsed))
+                .map(|(now, elapsed)| now.saturating_sub(elapsed)),
+        });
+    }
+
+    // One osascript call lists every window, so only working directories
+    // (an lsof run) are limited to the processes a detector could claim
+    let candidates: Vec<u32> = processes.iter().filter(|p| wants_titles(p)).map(|p| p.pid).collect();
+    let mut cwds = get_macos_cwds(&candidates);
+    for process in &mut processes {
+        if let Some(cwd) = cwds.remove(&process.pid) {
+            process.cwd = cwd;
+        }
+    }
+
+    Ok(processes)
+}
+
+/// Working directories of `pids`, from one `lsof` run (macOS has no /proc)
+#[cfg(target_os = "macos")]
+fn get_macos_cwds(pids: &[u32]) -> std::collections::HashMap<u32, String> {
+    let mut cwds = std::collections::HashMap::new();
+    if pids.is_empty() {
+        return cwds;
+    }
+
+    let pid_list: Vec<String> = pids.iter().map(|pid| pid.to_string()).collect();
+    let output = match std::process::Command::new("lsof")
+        .args(["-a", "-d", "cwd", "-Fn", "-p", &pid_list.join(",")])
+        .output()
+    {
+        Ok(output) => output,
+        Err(_) => return cwds,
+    };
+
+    // -F output: a "p<pid>" line, then "f<fd>" and "n<path>" for each file
+    let mut pid = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(value) = line.strip_prefix('p') {
+            pid = value.parse::<u32>().ok();
+        } else if let (Some(path), Some(pid)) = (line.strip_prefix('n'), pid) {
+            cwds.insert(pid, path.to_string());
+        }
+    }
+    cwds
+}
+
+/// Parse ps's `etime` (`[[dd-]hh:]mm:ss`) into seconds
+#[cfg(target_os = "macos")]
+fn parse_elapsed_time(elapsed: &str) -> Option<u64> {
+    let (days, clock) = match elapsed.split_once('-') {
+        Some((days, clock)) => (days.parse::<u64>().ok()?, clock),
+        None => (0, elapsed),
+    };
+
+    let mut seconds = 0;
+    for part in clock.split(':') {
+        seconds = seconds * 60 + part.parse::<u64>().ok()?;
+    }
+    Some(days * 24 * 60 * 60 + seconds)
+}
+
+/// Window titles of every foreground app, by PID, bottom first like the X11
+/// titles. Asks System Events, which needs the Accessibility permission;
+/// without it no titles are returned and detectors fall back to state files.
+#[cfg(target_os = "macos")]
+fn get_macos_window_titles() -> std::collections::HashMap<u32, Vec<String>> {
+    use std::collections::HashMap;
+
+    const SCRIPT: &str = r#"
+set output to ""
+tell application "System Events"
+    repeat with proc in (processes whose background only is false)
+        set procId to unix id of proc
+        repeat with win in windows of proc
+            set output to output & procId & tab & (name of win as text) & linefeed
+        end repeat
+    end repeat
+end tell
+return output
+"#;
+
+    let mut window_titles: HashMap<u32, Vec<String>> = HashMap::new();
+    let output = match std::process::Command::new("osascript").args(["-e", SCRIPT]).output() {
+        Ok(output) if output.status.success() => output,
+        _ => return window_titles,
+    };
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some((pid, title)) = line.split_once('\t') {
+            if let (Ok(pid), false) = (pid.trim().parse::<u32>(), title.trim().is_empty()) {
+                window_titles.entry(pid).or_default().push(title.trim().to_string());
+            }
+        }
+    }
+
+    // System Events lists windows front to back
+    for titles in window_titles.values_mut() {
+        titles.reverse();
+    }
+
+    window_titles
 }
 
 #[cfg(target_os = "linux")]
-fn find_processes_linux() -> DetectionResult<Vec<ProcessInfo>> {
+fn find_processes_linux(
+    owner_uid: Option<u32>,
+    wants_titles: impl Fn(&ProcessInfo) -> bool,
+) -> DetectionResult<Vec<ProcessInfo>> {
     use crate::detector::DetectionError;
     use std::fs;
     use std::path::Path;
 
     let mut processes = Vec::new();
     
-    // Get window titles from X11
-    let window_titles = get_x11_window_titles();
+    let boot_time = get_boot_time();
 
-    // Read all entries in /proc
-    let proc_dir = Path::new("/proc");
-    let entries = fs::read_dir(proc_dir).map_err(|e| DetectionError::SystemError {
+    let pid_dirs = list_pid_dirs(Path::new("/proc"), owner_uid).map_err(|e| DetectionError::SystemError {
         message: format!("Failed to read /proc: {}", e),
     })?;
 
-    for entry in entries {
-        if let Ok(entry) = entry {
-            let path = entry.path();
+    for (pid, path) in pid_dirs {
+        // Read process info
+        let cmdline_path = path.join("cmdline");
+        let comm_path = path.join("comm");
 
-            // Check if entry is a PID directory
-            if let Some(pid_str) = path.file_name().and_then(|n| n.to_str()) {
-                if let Ok(pid) = pid_str.parse::<u32>() {
-                    // Read process info
-                    let cmdline_path = path.join("cmdline");
-                    let comm_path = path.join("comm");
-
-                    if let Ok(comm) = fs::read_to_string(&comm_path) {
-                        let name = comm.trim().to_string();
-
-                        // Get window title from X11 if available, otherwise use cmdline
-                        let window_title = window_titles.get(&pid)
-                            .cloned()
-                            .unwrap_or_else(|| {
-                                fs::read_to_string(&cmdline_path)
-                                    .unwrap_or_default()
-                                    .replace('\0', " ")
-                                    .trim()
-                                    .to_string()
-                            });
-
-                        let executable_path = fs::read_link(path.join("exe"))
-                            .ok()
-                            .and_then(|p| p.to_str().map(|s| s.to_string()))
-                            .unwrap_or_default();
-
-                        processes.push(ProcessInfo {
-                            pid,
-                            name,
-                            window_title,
-                            executable_path,
-                        });
-                    }
+        if let Ok(comm) = fs::read_to_string(&comm_path) {
+            let name = comm.trim().to_string();
+
+            // The cmdline stands in for a title until windows are read
+            let window_title = fs::read_to_string(&cmdline_path)
+                .unwrap_or_default()
+                .replace('\0', " ")
+                .trim()
+                .to_string();
+
+            let executable_path = fs::read_link(path.join("exe"))
+                .ok()
+                .and_then(|p| p.to_str().map(|s| s.to_string()))
+                .unwrap_or_default();
+            let cwd = fs::read_link(path.join("cwd"))
+                .ok()
+                .and_then(|p| p.to_str().map(|s| s.to_string()))
+                .unwrap_or_default();
+
+            let stat_fields = read_proc_stat_fields(pid).unwrap_or_default();
+            let status = stat_fields
+                .first()
+                .and_then(|s| s.chars().next())
+                .map(ProcessStatus::from_stat_char)
+                .unwrap_or(ProcessStatus::Unknown);
+
+            // starttime (field 22) is in clock ticks since boot
+            let start_time = boot_time.zip(
+                stat_fields.get(19).and_then(|s| s.parse::<u64>().ok()),
+            )
+            .map(|(boot, ticks)| boot + ticks / CLOCK_TICKS_PER_SEC);
+
+            processes.push(ProcessInfo {
+                pid,
+                name,
+                window_title,
+                window_titles: Vec::new(),
+                title_is_synthetic: true,
+                executable_path,
+                cwd,
+                status,
+                start_time,
+            });
+        }
+    }
+
+    // Reading every window's title is slow on busy desktops; only the
+    // processes a detector could claim need theirs
+    let candidates: std::collections::HashSet<u32> =
+        processes.iter().filter(|p| wants_titles(p)).map(|p| p.pid).collect();
+    if !candidates.is_empty() {
+        let mut window_titles = get_window_titles_linux(&candidates);
+        for process in &mut processes {
+            // The topmost window (last in stacking order) is the primary title
+            if let Some(titles) = window_titles.remove(&process.pid) {
+                if let Some(title) = titles.last() {
+                    process.window_title = title.clone();
+                    process.title_is_synthetic = false;
                 }
+                process.window_titles = titles;
             }
         }
     }
@@ -157,14 +425,238 @@ fn find_processes_linux() -> DetectionResult<Vec<ProcessInfo>> {
 }
 
 #[cfg(target_os = "linux")]
-fn get_x11_window_titles() -> std::collections::HashMap<u32, String> {
+/// The PID directories under `proc_root` (normally /proc), with `owner_uid`
+/// only those of processes owned by that user. Other users' processes are
+/// skipped before anything else is read; most of their /proc files aren't
+/// readable anyway, but `status` always is.
+fn list_pid_dirs(proc_root: &std::path::Path, owner_uid: Option<u32>) -> std::io::Result<Vec<(u32, std::path::PathBuf)>> {
+    Ok(std::fs::read_dir(proc_root)?
+        .flatten()
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+            let path = entry.path();
+            if let Some(uid) = owner_uid {
+                let status = std::fs::read_to_string(path.join("status")).ok()?;
+                if parse_status_uid(&status) != Some(uid) {
+                    return None;
+                }
+            }
+            Some((pid, path))
+        })
+        .collect())
+}
+
+#[cfg(target_os = "linux")]
+/// The effective UID from a `/proc/<pid>/status` file ("Uid: real effective
+/// saved fs"), the owner /proc reports for the process
+fn parse_status_uid(status: &str) -> Option<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
+
+#[cfg(target_os = "linux")]
+/// Window titles by PID from the session's display server. Wayland
+/// compositors without a supported IPC fall back to X11, which still sees
+/// XWayland clients (JetBrains IDEs run under XWayland by default).
+fn get_window_titles_linux(pids: &std::collections::HashSet<u32>) -> std::collections::HashMap<u32, Vec<String>> {
+    if std::env::var("XDG_SESSION_TYPE").as_deref() == Ok("wayland") {
+        if let Some(mut titles) = get_wayland_window_titles() {
+            titles.retain(|pid, _| pids.contains(pid));
+            return titles;
+        }
+    }
+    get_x11_window_titles(pids)
+}
+
+#[cfg(target_os = "linux")]
+/// Window titles by PID from the compositor's IPC (Sway or Hyprland), the
+/// focused window last like the X11 stacking order. None if neither is
+/// reachable.
+fn get_wayland_window_titles() -> Option<std::collections::HashMap<u32, Vec<String>>> {
+    if let Ok(socket) = std::env::var("SWAYSOCK") {
+        if let Some(tree) = sway_get_tree(&socket) {
+            return Some(parse_sway_tree(&tree));
+        }
+    }
+
+    if let Some(clients) = hyprland_sockets().iter().find_map(|socket| hyprland_clients(socket)) {
+        return Some(parse_hyprland_clients(&clients));
+    }
+
+    None
+}
+
+#[cfg(target_os = "linux")]
+/// Candidate Hyprland IPC sockets for the running instance, if any
+fn hyprland_sockets() -> Vec<String> {
+    let Ok(signature) = std::env::var("HYPRLAND_INSTANCE_SIGNATURE") else {
+        return Vec::new();
+    };
+
+    // The socket moved from /tmp to $XDG_RUNTIME_DIR in Hyprland 0.40
+    let mut sockets = Vec::new();
+    if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+        sockets.push(format!("{}/hypr/{}/.socket.sock", runtime_dir, signature));
+    }
+    sockets.push(format!("/tmp/hypr/{}/.socket.sock", signature));
+    sockets
+}
+
+#[cfg(target_os = "linux")]
+const COMPOSITOR_IPC_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+#[cfg(target_os = "linux")]
+/// Send GET_TREE over the i3/Sway IPC protocol
+fn sway_get_tree(socket: &str) -> Option<serde_json::Value> {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    const MAGIC: &[u8] = b"i3-ipc";
+    const GET_TREE: u32 = 4;
+
+    let mut stream = UnixStream::connect(socket).ok()?;
+    stream.set_read_timeout(Some(COMPOSITOR_IPC_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(COMPOSITOR_IPC_TIMEOUT)).ok()?;
+
+    let mut request = MAGIC.to_vec();
+    request.extend_from_slice(&0u32.to_ne_bytes());
+    request.extend_from_slice(&GET_TREE.to_ne_bytes());
+    stream.write_all(&request).ok()?;
+
+    let mut header = [0u8; 14];
+    stream.read_exact(&mut header).ok()?;
+    if &header[..6] != MAGIC {
+        return None;
+    }
+    let length = u32::from_ne_bytes(header[6..10].try_into().ok()?) as usize;
+    let mut payload = vec![0u8; length];
+    stream.read_exact(&mut payload).ok()?;
+
+    serde_json::from_slice(&payload).ok()
+}
+
+#[cfg(target_os = "linux")]
+/// Walk a Sway tree for windows (nodes with a pid), focused one last
+fn parse_sway_tree(tree: &serde_json::Value) -> std::collections::HashMap<u32, Vec<String>> {
+    use std::collections::HashMap;
+
+    fn walk(node: &serde_json::Value, windows: &mut Vec<(u32, String, bool)>) {
+        let pid = node.get("pid").and_then(|p| p.as_u64());
+        let title = node.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+        if let Some(pid) = pid {
+            if !title.is_empty() {
+                let focused = node.get("focused").and_then(|f| f.as_bool()).unwrap_or(false);
+                windows.push((pid as u32, title.to_string(), focused));
+            }
+        }
+
+        for children in ["nodes", "floating_nodes"] {
+            if let Some(children) = node.get(children).and_then(|c| c.as_array()) {
+                for child in children {
+                    walk(child, windows);
+                }
+            }
+        }
+    }
+
+    let mut windows = Vec::new();
+    walk(tree, &mut windows);
+    // Stable sort: the focused window moves to the end, the rest keep tree order
+    windows.sort_by_key(|(_, _, focused)| *focused);
+
+    let mut window_titles: HashMap<u32, Vec<String>> = HashMap::new();
+    for (pid, title, _) in windows {
+        window_titles.entry(pid).or_default().push(title);
+    }
+    window_titles
+}
+
+#[cfg(target_os = "linux")]
+/// The con_id of the first Sway window owned by `pid`
+fn sway_window_id(node: &serde_json::Value, pid: u32) -> Option<String> {
+    if node.get("pid").and_then(|p| p.as_u64()) == Some(pid as u64) {
+        return node.get("id").and_then(|id| id.as_u64()).map(|id| id.to_string());
+    }
+
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|children| node.get(children).and_then(|c| c.as_array()))
+        .flatten()
+        .find_map(|child| sway_window_id(child, pid))
+}
+
+#[cfg(target_os = "linux")]
+/// Ask Hyprland for its client list as JSON
+fn hyprland_clients(socket: &str) -> Option<serde_json::Value> {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket).ok()?;
+    stream.set_read_timeout(Some(COMPOSITOR_IPC_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(COMPOSITOR_IPC_TIMEOUT)).ok()?;
+    stream.write_all(b"j/clients").ok()?;
+
+    // Hyprland closes the connection after replying
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).ok()?;
+
+    serde_json::from_slice(&response).ok()
+}
+
+#[cfg(target_os = "linux")]
+/// The address of the first Hyprland client owned by `pid`
+fn hyprland_window_id(clients: &serde_json::Value, pid: u32) -> Option<String> {
+    clients
+        .as_array()?
+        .iter()
+        .find(|client| client.get("pid").and_then(|p| p.as_i64()) == Some(pid as i64))
+        .and_then(|client| client.get("address")?.as_str())
+        .map(|address| address.to_string())
+}
+
+#[cfg(target_os = "linux")]
+/// Group Hyprland clients by pid, least recently focused first
+fn parse_hyprland_clients(clients: &serde_json::Value) -> std::collections::HashMap<u32, Vec<String>> {
+    use std::collections::HashMap;
+
+    let mut windows: Vec<(u32, String, i64)> = clients
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|client| {
+            let pid = client.get("pid")?.as_i64().filter(|p| *p > 0)?;
+            let title = client.get("title")?.as_str().filter(|t| !t.is_empty())?;
+            // 0 is the focused window, -1 one never focused
+            let focus = client.get("focusHistoryID").and_then(|f| f.as_i64()).unwrap_or(-1);
+            let recency = if focus < 0 { i64::MAX } else { focus };
+            Some((pid as u32, title.to_string(), recency))
+        })
+        .collect();
+    windows.sort_by_key(|(_, _, recency)| std::cmp::Reverse(*recency));
+
+    let mut window_titles: HashMap<u32, Vec<String>> = HashMap::new();
+    for (pid, title, _) in windows {
+        window_titles.entry(pid).or_default().push(title);
+    }
+    window_titles
+}
+
+#[cfg(target_os = "linux")]
+/// Titles of the top-level windows owned by `pids`, by PID, in stacking
+/// order (bottom first). A process can own several, e.g. one per IDE frame.
+fn get_x11_window_titles(pids: &std::collections::HashSet<u32>) -> std::collections::HashMap<u32, Vec<String>> {
     use std::collections::HashMap;
     use x11::xlib::*;
     use std::ffi::CString;
     use std::ffi::CStr;
     use std::ptr;
     
-    let mut window_titles = HashMap::new();
+    let mut window_titles: HashMap<u32, Vec<String>> = HashMap::new();
     
     unsafe {
         let display = XOpenDisplay(ptr::null());
@@ -207,6 +699,10 @@ fn get_x11_window_titles() -> std::collections::HashMap<u32, String> {
                     &mut prop
                 ) == Success as i32 && !prop.is_null() {
                     let pid = *(prop as *const u32);
+                    if !pids.contains(&pid) {
+                        XFree(prop as *mut _);
+                        continue;
+                    }
                     
                     // Get window name - try both WM_NAME and _NET_WM_NAME
                     let mut window_name: *mut i8 = ptr::null_mut();
@@ -238,9 +734,9 @@ fn get_x11_window_titles() -> std::collections::HashMap<u32, String> {
                         &mut bytes_after_name,
                         &mut prop_name
                     ) == Success as i32 && !prop_name.is_null() && nitems_name > 0 {
-                        let title = String::from_utf8_lossy(std::slice::from_raw_parts(prop_name, nitems_name as usize)).to_string();
+                        let title = clean_window_title(std::slice::from_raw_parts(prop_name, nitems_name as usize));
                         if !title.is_empty() {
-                            window_titles.insert(pid, title);
+                            window_titles.entry(pid).or_default().push(title);
                             got_title = true;
                         }
                         XFree(prop_name as *mut _);
@@ -248,9 +744,9 @@ fn get_x11_window_titles() -> std::collections::HashMap<u32, String> {
                     
                     // Fall back to WM_NAME if needed
                     if !got_title && XFetchName(display, window, &mut window_name) != 0 && !window_name.is_null() {
-                        let title = CStr::from_ptr(window_name).to_string_lossy().to_string();
+                        let title = clean_window_title(CStr::from_ptr(window_name).to_bytes());
                         if !title.is_empty() {
-                            window_titles.insert(pid, title);
+                            window_titles.entry(pid).or_default().push(title);
                         }
                         XFree(window_name as *mut _);
                     }
@@ -270,25 +766,671 @@ fn get_x11_window_titles() -> std::collections::HashMap<u32, String> {
     window_titles
 }
 
+#[cfg(target_os = "linux")]
+/// The first window in the window manager's `_NET_CLIENT_LIST` owned by `pid`
+fn get_x11_window_id(pid: u32) -> Option<u64> {
+    use std::ffi::CString;
+    use std::ptr;
+    use x11::xlib::*;
+
+    unsafe {
+        let display = XOpenDisplay(ptr::null());
+        if display.is_null() {
+            return None;
+        }
+
+        // Read up to `length` 32-bit values of a window property
+        let read_cardinals = |window: Window, name: &str, length: i64| -> Vec<u64> {
+            let Ok(atom_name) = CString::new(name) else {
+                return Vec::new();
+            };
+            let atom = XInternAtom(display, atom_name.as_ptr(), 0);
+
+            let mut actual_type = 0;
+            let mut actual_format = 0;
+            let mut nitems = 0;
+            let mut bytes_after = 0;
+            let mut prop: *mut u8 = ptr::null_mut();
+
+            if XGetWindowProperty(
+                display,
+                window,
+                atom,
+                0,
+                length,
+                0,
+                AnyPropertyType as u64,
+                &mut actual_type,
+                &mut actual_format,
+                &mut nitems,
+                &mut bytes_after,
+                &mut prop,
+            ) == Success as i32
+                && !prop.is_null()
+            {
+                // Xlib returns format-32 properties as C longs
+                let values = std::slice::from_raw_parts(prop as *const u64, nitems as usize).to_vec();
+                XFree(prop as *mut _);
+                values
+            } else {
+                Vec::new()
+            }
+        };
+
+        let root = XDefaultRootWindow(display);
+        let window = read_cardinals(root, "_NET_CLIENT_LIST", 4096)
+            .into_iter()
+            .find(|&window| read_cardinals(window, "_NET_WM_PID", 1).first() == Some(&(pid as u64)));
+
+        XCloseDisplay(display);
+        window
+    }
+}
+
+#[cfg(target_os = "linux")]
+/// Decode a raw title property. Some window managers pad `_NET_WM_NAME` with
+/// a trailing NUL or whitespace, which would otherwise end up in the file name.
+fn clean_window_title(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches(|c: char| c == '\0' || c.is_whitespace())
+        .to_string()
+}
+
+/// Leading command line arguments kept for parsing. Electron and JetBrains
+/// launchers can pass hundreds of arguments that every detector would
+/// otherwise walk (and stat) one by one.
+const CMDLINE_HEAD_ARGS: usize = 256;
+
+/// Trailing arguments kept as well: launchers put the project path last,
+/// after the long classpath and VM options
+const CMDLINE_TAIL_ARGS: usize = 16;
+
+/// Get a process's command line arguments, including the program name.
+/// Very long command lines keep only their first and last arguments.
+pub fn get_process_cmdline(pid: u32) -> Option<Vec<String>> {
+    read_process_cmdline(pid).map(bound_cmdline)
+}
+
+/// Drop the middle of an overly long command line
+pub(crate) fn bound_cmdline(mut args: Vec<String>) -> Vec<String> {
+    if args.len() > CMDLINE_HEAD_ARGS + CMDLINE_TAIL_ARGS {
+        let tail_start = args.len() - CMDLINE_TAIL_ARGS;
+        args.drain(CMDLINE_HEAD_ARGS..tail_start);
+    }
+    args
+}
+
+fn read_process_cmdline(pid: u32) -> Option<Vec<String>> {
+    #[cfg(target_os = "linux")]
+    {
+        let cmdline_path = format!("/proc/{}/cmdline", pid);
+        std::fs::read_to_string(&cmdline_path).ok().map(|content| {
+            content
+                .split('\0')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect()
+        })
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(args) = read_procargs(pid) {
+            return Some(args);
+        }
+
+        // Fallback for processes sysctl won't show us. ps prints arguments
+        // space-joined, so arguments containing spaces come out split.
+        let output = std::process::Command::new("ps")
+            .args(["-p", &pid.to_string(), "-o", "args="])
+            .output()
+            .ok()?;
+
+        let cmdline = String::from_utf8_lossy(&output.stdout);
+        Some(cmdline.split_whitespace().map(|s| s.to_string()).collect())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // Reading the process's memory is fast; wmic is the fallback for
+        // processes we can't open (access denied) and is absent on newer Windows
+        if let Some(args) = read_native_cmdline(pid) {
+            return Some(args);
+        }
+
+        let output = std::process::Command::new("wmic")
+            .args([
+                "process",
+                "where",
+                &format!("ProcessId={}", pid),
+                "get",
+                "CommandLine",
+                "/value",
+            ])
+            .output()
+            .ok()?;
+
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        for line in output_str.lines() {
+            if let Some(cmdline) = line.strip_prefix("CommandLine=") {
+                // Malformed quoting (unbalanced quotes in a long command
+                // line) shouldn't lose the whole command line
+                return Some(shell_words::split(cmdline).unwrap_or_else(|_| {
+                    cmdline.split_whitespace().map(|s| s.to_string()).collect()
+                }));
+            }
+        }
+
+        None
+    }
+}
+
+/// Read a process's arguments with `sysctl(KERN_PROCARGS2)`, which keeps
+/// them separate (unlike `ps`). Only the user's own processes are readable
+/// unless running as root.
+#[cfg(target_os = "macos")]
+fn read_procargs(pid: u32) -> Option<Vec<String>> {
+    let mut mib = [libc::CTL_KERN, libc::KERN_PROCARGS2, pid as libc::c_int];
+    let mut size: libc::size_t = 0;
+    unsafe {
+        // The first call only reports the buffer size
+        let sized = libc::sysctl(mib.as_mut_ptr(), 3, std::ptr::null_mut(), &mut size, std::ptr::null_mut(), 0);
+        if sized != 0 || size == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u8; size];
+        let read = libc::sysctl(
+            mib.as_mut_ptr(),
+            3,
+            buffer.as_mut_ptr().cast(),
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        );
+        if read != 0 {
+            return None;
+        }
+        buffer.truncate(size);
+        parse_procargs(&buffer)
+    }
+}
+
+/// Split a `KERN_PROCARGS2` buffer: argc (a native-endian i32), the
+/// executable path, NUL padding, then argc NUL-terminated arguments
+/// (followed by the environment, which is ignored)
+#[cfg(any(target_os = "macos", test))]
+fn parse_procargs(buffer: &[u8]) -> Option<Vec<String>> {
+    let argc = i32::from_ne_bytes(buffer.get(..4)?.try_into().ok()?);
+    let rest = &buffer[4..];
+    let exec_path_end = rest.iter().position(|&b| b == 0)?;
+    let args_start = exec_path_end + rest[exec_path_end..].iter().position(|&b| b != 0)?;
+    Some(
+        rest[args_start..]
+            .split(|&b| b == 0)
+            .take(usize::try_from(argc).ok()?)
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect(),
+    )
+}
+
+/// Read a process's command line from `ProcessParameters->CommandLine` in
+/// its PEB. Needs PROCESS_VM_READ access to the target.
+#[cfg(target_os = "windows")]
+fn read_native_cmdline(pid: u32) -> Option<Vec<String>> {
+    use winapi::shared::minwindef::FALSE;
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::OpenProcess;
+    use winapi::um::winnt::{PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_VM_READ};
+
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ, FALSE, pid);
+        if process.is_null() {
+            return None;
+        }
+        let args = read_peb_cmdline(process);
+        CloseHandle(process);
+        args
+    }
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn read_peb_cmdline(process: winapi::um::winnt::HANDLE) -> Option<Vec<String>> {
+    use std::mem::{size_of, zeroed};
+    use std::ptr;
+    use winapi::um::memoryapi::ReadProcessMemory;
+    use winapi::um::processthreadsapi::GetCurrentProcess;
+    use ntapi::ntpebteb::PEB;
+    use ntapi::ntpsapi::{NtQueryInformationProcess, ProcessBasicInformation, PROCESS_BASIC_INFORMATION};
+    use ntapi::ntrtl::RTL_USER_PROCESS_PARAMETERS;
+    use winapi::um::wow64apiset::IsWow64Process;
+
+    // The PEB layout follows our own bitness. A 64-bit build reads any
+    // process (WOW64 ones included, through their native PEB); a 32-bit
+    // build only other WOW64 processes.
+    if cfg!(target_pointer_width = "32") {
+        let (mut ours, mut theirs) = (0, 0);
+        if IsWow64Process(GetCurrentProcess(), &mut ours) == 0
+            || IsWow64Process(process, &mut theirs) == 0
+            || ours != theirs
+        {
+            return None;
+        }
+    }
+
+    let mut info: PROCESS_BASIC_INFORMATION = zeroed();
+    let status = NtQueryInformationProcess(
+        process,
+        ProcessBasicInformation,
+        &mut info as *mut _ as *mut _,
+        size_of::<PROCESS_BASIC_INFORMATION>() as u32,
+        ptr::null_mut(),
+    );
+    if status < 0 || info.PebBaseAddress.is_null() {
+        return None;
+    }
+
+    let peb: PEB = read_remote(process, info.PebBaseAddress)?;
+    let parameters: RTL_USER_PROCESS_PARAMETERS = read_remote(process, peb.ProcessParameters)?;
+
+    let units = parameters.CommandLine.Length as usize / 2;
+    if units == 0 {
+        return None;
+    }
+    let mut command_line = vec![0u16; units];
+    let mut read = 0;
+    if ReadProcessMemory(
+        process,
+        parameters.CommandLine.Buffer as *const _,
+        command_line.as_mut_ptr() as *mut _,
+        units * 2,
+        &mut read,
+    ) == 0
+        || read != units * 2
+    {
+        return None;
+    }
+
+    split_windows_cmdline(&command_line)
+}
+
+/// Copy a `T` out of another process's memory
+#[cfg(target_os = "windows")]
+unsafe fn read_remote<T>(process: winapi::um::winnt::HANDLE, address: *const T) -> Option<T> {
+    use std::mem::{size_of, zeroed};
+    use winapi::um::memoryapi::ReadProcessMemory;
+
+    let mut value: T = zeroed();
+    let mut read = 0;
+    if address.is_null()
+        || ReadProcessMemory(
+            process,
+            address as *const _,
+            &mut value as *mut T as *mut _,
+            size_of::<T>(),
+            &mut read,
+        ) == 0
+        || read != size_of::<T>()
+    {
+        return None;
+    }
+    Some(value)
+}
+
+/// Split a UTF-16 command line with Windows' own quoting rules
+#[cfg(target_os = "windows")]
+unsafe fn split_windows_cmdline(command_line: &[u16]) -> Option<Vec<String>> {
+    use winapi::um::shellapi::CommandLineToArgvW;
+    use winapi::um::winbase::LocalFree;
+
+    let mut wide = command_line.to_vec();
+    wide.push(0);
+
+    let mut argc = 0;
+    let argv = CommandLineToArgvW(wide.as_ptr(), &mut argc);
+    if argv.is_null() {
+        return None;
+    }
+
+    let args = (0..argc.max(0) as usize)
+        .map(|i| {
+            let arg = *argv.add(i);
+            let len = (0..).take_while(|&j| *arg.add(j) != 0).count();
+            String::from_utf16_lossy(std::slice::from_raw_parts(arg, len))
+        })
+        .collect();
+    LocalFree(argv as *mut _);
+
+    Some(args)
+}
+
+/// USER_HZ, which Linux fixes at 100 for userspace on all mainstream architectures
+#[cfg(target_os = "linux")]
+const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+/// Get the system boot time as Unix seconds from `/proc/stat`
+#[cfg(target_os = "linux")]
+fn get_boot_time() -> Option<u64> {
+    std::fs::read_to_string("/proc/stat")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// Read the fields of `/proc/<pid>/stat` that follow the command name,
+/// starting with the state letter
+#[cfg(target_os = "linux")]
+fn read_proc_stat_fields(pid: u32) -> Option<Vec<String>> {
+    // "pid (comm) state ppid ...", comm may contain spaces and parentheses
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let after_comm = &stat[stat.rfind(')')? + 1..];
+    Some(after_comm.split_whitespace().map(|s| s.to_string()).collect())
+}
+
+/// Get the parent PID of a process
+pub fn get_parent_pid(pid: u32) -> Option<u32> {
+    #[cfg(target_os = "linux")]
+    {
+        read_proc_stat_fields(pid)?.get(1)?.parse().ok()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Get the PIDs of a process and all of its ancestors, nearest first
+pub fn get_ancestor_pids(pid: u32) -> Vec<u32> {
+    let mut ancestors = vec![pid];
+    let mut current = pid;
+
+    while let Some(parent) = get_parent_pid(current) {
+        if parent == 0 || ancestors.contains(&parent) {
+            break;
+        }
+        ancestors.push(parent);
+        current = parent;
+    }
+
+    ancestors
+}
+
+/// Get a process's short name (`/proc/<pid>/comm`, at most 15 characters)
+pub fn get_process_name(pid: u32) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let comm = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+        Some(comm.trim_end().to_string())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Get a variable from the environment a process was started with
+pub fn get_process_env_var(pid: u32, name: &str) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let environ = std::fs::read(format!("/proc/{}/environ", pid)).ok()?;
+        environ.split(|&b| b == 0).find_map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            let (key, value) = entry.split_once('=')?;
+            (key == name && !value.is_empty()).then(|| value.to_string())
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (pid, name);
+        None
+    }
+}
+
+/// An identifier of a top-level window owned by `pid` that window managers
+/// accept for focusing it: a hex X11 window id, or on Wayland the Sway
+/// con_id or Hyprland address
+pub fn get_window_id_by_pid(pid: u32) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        if std::env::var("XDG_SESSION_TYPE").as_deref() == Ok("wayland") {
+            if let Some(tree) = std::env::var("SWAYSOCK").ok().and_then(|socket| sway_get_tree(&socket)) {
+                return sway_window_id(&tree, pid);
+            }
+            if let Some(clients) = hyprland_sockets().iter().find_map(|socket| hyprland_clients(socket)) {
+                return hyprland_window_id(&clients, pid);
+            }
+        }
+        get_x11_window_id(pid).map(|window| format!("{:#x}", window))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Get the PID owning the currently focused window, if it can be determined
+pub fn get_focused_window_pid() -> Option<u32> {
+    #[cfg(target_os = "linux")]
+    {
+        // Native Wayland windows are invisible to X11; XWayland ones are not
+        if std::env::var("XDG_SESSION_TYPE").as_deref() == Ok("wayland") {
+            if let Some(pid) = get_wayland_focused_window_pid() {
+                return Some(pid);
+            }
+        }
+        get_x11_focused_window_pid()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use winapi::um::winuser::{GetForegroundWindow, GetWindowThreadProcessId};
+
+        let mut pid = 0u32;
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            if hwnd.is_null() {
+                return None;
+            }
+            GetWindowThreadProcessId(hwnd, &mut pid);
+        }
+        Some(pid).filter(|pid| *pid != 0)
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    None
+}
+
+#[cfg(target_os = "linux")]
+/// The PID owning the focused window according to the compositor's IPC
+/// (Sway or Hyprland)
+fn get_wayland_focused_window_pid() -> Option<u32> {
+    if let Ok(socket) = std::env::var("SWAYSOCK") {
+        if let Some(tree) = sway_get_tree(&socket) {
+            return sway_focused_pid(&tree);
+        }
+    }
+
+    hyprland_sockets()
+        .iter()
+        .find_map(|socket| hyprland_clients(socket))
+        .and_then(|clients| hyprland_focused_pid(&clients))
+}
+
+#[cfg(target_os = "linux")]
+/// The pid of the focused window in a Sway tree
+fn sway_focused_pid(node: &serde_json::Value) -> Option<u32> {
+    if node.get("focused").and_then(|f| f.as_bool()) == Some(true) {
+        if let Some(pid) = node.get("pid").and_then(|p| p.as_u64()) {
+            return Some(pid as u32);
+        }
+    }
+
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|children| node.get(children).and_then(|c| c.as_array()))
+        .flatten()
+        .find_map(sway_focused_pid)
+}
+
+#[cfg(target_os = "linux")]
+/// The pid of the focused Hyprland client (focusHistoryID 0)
+fn hyprland_focused_pid(clients: &serde_json::Value) -> Option<u32> {
+    clients
+        .as_array()?
+        .iter()
+        .find(|client| client.get("focusHistoryID").and_then(|f| f.as_i64()) == Some(0))
+        .and_then(|client| client.get("pid")?.as_i64())
+        .filter(|pid| *pid > 0)
+        .map(|pid| pid as u32)
+}
+
+#[cfg(target_os = "linux")]
+fn get_x11_focused_window_pid() -> Option<u32> {
+    use std::ffi::CString;
+    use std::ptr;
+    use x11::xlib::*;
+
+    unsafe {
+        let display = XOpenDisplay(ptr::null());
+        if display.is_null() {
+            return None;
+        }
+
+        let root = XDefaultRootWindow(display);
+        let mut pid = None;
+
+        // Read a single 32-bit property value from a window
+        let read_cardinal = |window: Window, name: &str| -> Option<u64> {
+            let atom_name = CString::new(name).ok()?;
+            let atom = XInternAtom(display, atom_name.as_ptr(), 0);
+
+            let mut actual_type = 0;
+            let mut actual_format = 0;
+            let mut nitems = 0;
+            let mut bytes_after = 0;
+            let mut prop: *mut u8 = ptr::null_mut();
+
+            if XGetWindowProperty(
+                display,
+                window,
+                atom,
+                0,
+                1,
+                0,
+                AnyPropertyType as u64,
+                &mut actual_type,
+                &mut actual_format,
+                &mut nitems,
+                &mut bytes_after,
+                &mut prop,
+            ) == Success as i32
+                && !prop.is_null()
+            {
+                // Xlib returns format-32 properties as C longs
+                let value = if nitems > 0 { Some(*(prop as *const u64)) } else { None };
+                XFree(prop as *mut _);
+                value
+            } else {
+                None
+            }
+        };
+
+        if let Some(active_window) = read_cardinal(root, "_NET_ACTIVE_WINDOW") {
+            if active_window != 0 {
+                pid = read_cardinal(active_window, "_NET_WM_PID").map(|p| p as u32);
+            }
+        }
+
+        XCloseDisplay(display);
+        pid
+    }
+}
+
+/// The local passwd database
+const PASSWD_PATH: &str = "/etc/passwd";
+
+/// Look up a user account by name in the passwd database
+pub fn lookup_user(name: &str) -> Option<UserAccount> {
+    lookup_user_in(std::path::Path::new(PASSWD_PATH), name)
+}
+
+/// Look up a user account by name in the passwd file at `passwd_path`
+fn lookup_user_in(passwd_path: &std::path::Path, name: &str) -> Option<UserAccount> {
+    let passwd = std::fs::read_to_string(passwd_path).ok()?;
+    passwd
+        .lines()
+        .filter_map(parse_passwd_entry)
+        .find(|user| user.name == name)
+}
+
+/// Parse a `name:password:uid:gid:gecos:home:shell` line
+fn parse_passwd_entry(line: &str) -> Option<UserAccount> {
+    let fields: Vec<&str> = line.split(':').collect();
+    if fields.len() < 7 || line.starts_with('#') {
+        return None;
+    }
+
+    Some(UserAccount {
+        name: fields[0].to_string(),
+        uid: fields[2].parse().ok()?,
+        home_dir: fields[5].to_string(),
+    })
+}
+
+/// UID of the user running this process
+pub fn get_current_uid() -> Option<u32> {
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::MetadataExt;
+        std::fs::metadata("/proc/self").map(|m| m.uid()).ok()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    None
+}
+
+/// The user whose editors to inspect when running under `sudo`, whose
+/// `$HOME` and `$USER` point at root rather than the person who invoked it
+pub fn get_sudo_user() -> Option<UserAccount> {
+    sudo_user_in(std::path::Path::new(PASSWD_PATH), std::env::var("SUDO_USER").ok().as_deref())
+}
+
+/// The account named by `$SUDO_USER` (`sudo_user`) in the passwd file at `passwd_path`
+fn sudo_user_in(passwd_path: &std::path::Path, sudo_user: Option<&str>) -> Option<UserAccount> {
+    sudo_user
+        .filter(|name| !name.is_empty() && *name != "root")
+        .and_then(|name| lookup_user_in(passwd_path, name))
+}
+
 pub fn find_processes_by_name(name: &str) -> DetectionResult<Vec<ProcessInfo>> {
-    let all_processes = find_all_processes()?;
+    let all_processes = find_all_processes(None, |process| {
+        !name.is_empty() && process.name.to_lowercase().contains(&name.to_lowercase())
+    })?;
 
     Ok(all_processes
         .into_iter()
-        .filter(|p| p.name.to_lowercase().contains(&name.to_lowercase()))
+        .filter(|p| !name.is_empty() && p.name.to_lowercase().contains(&name.to_lowercase()))
         .collect())
 }
 
 pub fn list_all_processes() -> DetectionResult<()> {
-    let processes = find_all_processes()?;
+    let processes = find_all_processes(None, |_| true)?;
 
     println!("All running processes:");
     for process in processes {
         if !process.name.is_empty() {
             println!(
-                "PID: {}, Name: {}, Title: {}",
+                "PID: {}, Name: {}, IDE: {}, Title: {}",
                 process.pid,
                 process.name,
+                SupportedIDE::from_process_name(&process.name).map_or("-", |ide| ide.as_str()),
                 if process.window_title.is_empty() {
                     "<no title>"
                 } else {
@@ -300,3 +1442,216 @@ pub fn list_all_processes() -> DetectionResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn reads_own_command_line_natively() {
+        let expected: Vec<String> = std::env::args().collect();
+        assert_eq!(read_native_cmdline(std::process::id()), Some(expected));
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    #[test]
+    fn shared_cmdline_reader_returns_own_arguments() {
+        let expected: Vec<String> = std::env::args().collect();
+        assert_eq!(get_process_cmdline(std::process::id()), Some(expected));
+        assert_eq!(get_process_cmdline(u32::MAX), None);
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[test]
+    fn scan_reports_each_process_working_directory() {
+        let pid = std::process::id();
+        let processes = find_all_processes(None, |process| process.pid == pid).unwrap();
+        let own = processes.iter().find(|process| process.pid == pid).unwrap();
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(std::path::Path::new(&own.cwd).canonicalize().unwrap(), cwd.canonicalize().unwrap());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn only_the_owners_processes_are_listed() {
+        let proc_root = std::env::temp_dir().join(format!("ide-files-proc-{}", std::process::id()));
+        for (pid, uid) in [(100, 1000), (200, 0), (300, 1000), (400, 1001)] {
+            let dir = proc_root.join(pid.to_string());
+            std::fs::create_dir_all(&dir).unwrap();
+            let status = format!("Name:\tvim\nUid:\t{uid}\t{uid}\t{uid}\t{uid}\nGid:\t0\t0\t0\t0\n");
+            std::fs::write(dir.join("status"), status).unwrap();
+        }
+        // A process that exited between listing and reading, and non-PID entries
+        std::fs::create_dir_all(proc_root.join("500")).unwrap();
+        std::fs::create_dir_all(proc_root.join("sys")).unwrap();
+        std::fs::write(proc_root.join("uptime"), "1.0 1.0\n").unwrap();
+
+        let pids = |owner_uid| {
+            let mut pids: Vec<u32> = list_pid_dirs(&proc_root, owner_uid)
+                .unwrap()
+                .into_iter()
+                .map(|(pid, _)| pid)
+                .collect();
+            pids.sort_unstable();
+            pids
+        };
+        assert_eq!(pids(Some(1000)), [100, 300]);
+        assert_eq!(pids(Some(0)), [200]);
+        assert_eq!(pids(None), [100, 200, 300, 400, 500]);
+
+        std::fs::remove_dir_all(&proc_root).unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn target_user_home_replaces_the_current_one_for_lookups() {
+        let root = std::env::temp_dir().join(format!("ide-files-passwd-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let alice = root.join("home/alice");
+        let local = alice.join(".config/sublime-text/Local");
+        std::fs::create_dir_all(&local).unwrap();
+        let cargo_toml = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        let session = serde_json::json!({
+            "windows": [{"buffers": [{"file": cargo_toml}], "groups": [{"selected": 0, "sheets": [{"buffer": 0}]}]}]
+        });
+        std::fs::write(local.join("Session.sublime_session"), session.to_string()).unwrap();
+        let passwd = root.join("passwd");
+        std::fs::write(
+            &passwd,
+            format!(
+                "# local accounts\nroot:x:0:0:root:/root:/bin/bash\nalice:x:1000:1000:Alice:{}:/bin/zsh\nbob:x:1001:1001::{}:/bin/sh\n",
+                alice.display(),
+                root.join("home/bob").display(),
+            ),
+        )
+        .unwrap();
+
+        let user = lookup_user_in(&passwd, "alice").unwrap();
+        assert_eq!((user.name.as_str(), user.uid), ("alice", 1000));
+        assert_eq!(user.home_dir, alice.to_string_lossy());
+        assert!(lookup_user_in(&passwd, "carol").is_none());
+        // Under sudo the invoking user is inspected, but never root
+        assert_eq!(sudo_user_in(&passwd, Some("bob")).map(|u| u.uid), Some(1001));
+        assert!(sudo_user_in(&passwd, Some("root")).is_none());
+        assert!(sudo_user_in(&passwd, Some("")).is_none());
+        assert!(sudo_user_in(&passwd, None).is_none());
+
+        // The state files are read from the target user's home, not $HOME
+        let detect = |home_dir: String| {
+            let mut manager = crate::detector::IDEDetectorManager::new();
+            crate::detectors::register_all(
+                &mut manager,
+                &crate::detectors::DetectorOptions {
+                    home_dir: Some(home_dir),
+                    ..Default::default()
+                },
+            );
+            let processes = [ProcessInfo::new(200, "sublime_text", "/opt/sublime_text/sublime_text")];
+            manager.detect_from_processes(SupportedIDE::Sublime, &processes)
+        };
+        let found = detect(user.home_dir);
+        let missing = detect(lookup_user_in(&passwd, "bob").unwrap().home_dir);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(found.unwrap().active_file.as_deref(), Some(cargo_toml));
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn procargs_keep_arguments_with_spaces_whole() {
+        let mut buffer = 3i32.to_ne_bytes().to_vec();
+        buffer.extend_from_slice(b"/Applications/GoLand.app/Contents/MacOS/goland\0\0\0\0");
+        buffer.extend_from_slice(b"goland\0--wait\0/Users/me/My Project\0");
+        buffer.extend_from_slice(b"HOME=/Users/me\0\0");
+
+        assert_eq!(
+            parse_procargs(&buffer).unwrap(),
+            ["goland", "--wait", "/Users/me/My Project"]
+        );
+        assert_eq!(parse_procargs(&buffer[..2]), None);
+    }
+
+    #[test]
+    fn clean_window_title_strips_trailing_nul_and_whitespace() {
+        assert_eq!(clean_window_title(b"main.go - my-service\0"), "main.go - my-service");
+        assert_eq!(clean_window_title(b"main.go - my-service \0\n"), "main.go - my-service");
+        assert_eq!(clean_window_title(b"\0"), "");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn compositor_window_titles_put_the_focused_window_last() {
+        let tree = serde_json::json!({
+            "type": "root",
+            "nodes": [{
+                "type": "workspace",
+                "nodes": [
+                    {"pid": 100, "name": "main.go - api", "focused": true},
+                    {"pid": 100, "name": "app.ts - web", "focused": false},
+                    {"pid": 200, "name": "", "focused": false}
+                ],
+                "floating_nodes": [{"pid": 300, "name": "Settings", "focused": false}]
+            }]
+        });
+        let titles = parse_sway_tree(&tree);
+        assert_eq!(titles[&100], ["app.ts - web", "main.go - api"]);
+        assert_eq!(titles[&300], ["Settings"]);
+        assert!(!titles.contains_key(&200));
+
+        let clients = serde_json::json!([
+            {"pid": 100, "title": "main.go - api", "focusHistoryID": 0},
+            {"pid": 100, "title": "app.ts - web", "focusHistoryID": 2},
+            {"pid": 100, "title": "lib.rs - core", "focusHistoryID": -1},
+            {"pid": -1, "title": "stale", "focusHistoryID": 1}
+        ]);
+        let titles = parse_hyprland_clients(&clients);
+        assert_eq!(titles[&100], ["lib.rs - core", "app.ts - web", "main.go - api"]);
+        assert_eq!(titles.len(), 1);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn compositor_focused_window_gives_its_pid() {
+        let tree = serde_json::json!({
+            "type": "root",
+            "nodes": [{
+                "type": "workspace",
+                "nodes": [
+                    {"pid": 100, "name": "main.go - api", "focused": false},
+                    {"pid": 200, "name": "vim", "focused": true}
+                ],
+                "floating_nodes": [{"pid": 300, "name": "Settings", "focused": false}]
+            }]
+        });
+        assert_eq!(sway_focused_pid(&tree), Some(200));
+        assert_eq!(sway_focused_pid(&serde_json::json!({"type": "root", "nodes": []})), None);
+
+        let clients = serde_json::json!([
+            {"pid": 100, "title": "main.go - api", "focusHistoryID": 1},
+            {"pid": 300, "title": "app.ts - web", "focusHistoryID": 0}
+        ]);
+        assert_eq!(hyprland_focused_pid(&clients), Some(300));
+        assert_eq!(hyprland_focused_pid(&serde_json::json!([])), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn compositor_window_ids_are_found_by_pid() {
+        let tree = serde_json::json!({
+            "id": 1,
+            "nodes": [{"id": 4, "nodes": [{"id": 7, "pid": 100}]}],
+            "floating_nodes": [{"id": 9, "pid": 300}]
+        });
+        assert_eq!(sway_window_id(&tree, 100).as_deref(), Some("7"));
+        assert_eq!(sway_window_id(&tree, 300).as_deref(), Some("9"));
+        assert_eq!(sway_window_id(&tree, 200), None);
+
+        let clients = serde_json::json!([
+            {"pid": 100, "address": "0x5d3e1f0"},
+            {"pid": 300, "address": "0x5d3e2a0"}
+        ]);
+        assert_eq!(hyprland_window_id(&clients, 300).as_deref(), Some("0x5d3e2a0"));
+        assert_eq!(hyprland_window_id(&clients, 200), None);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::types::DetectionResult;
+use std::collections::HashMap;
+
+/// Path separators: Windows paths (e.g. from VSCode's `file:///c%3A/...`
+/// URIs) use '\\', and are redacted on every platform
+const SEPARATORS: [char; 2] = ['/', '\\'];
+
+/// A Windows drive (`C:`), which says nothing about the user
+fn is_drive(component: &str) -> bool {
+    let bytes = component.as_bytes();
+    bytes.len() == 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+/// Replaces real path components with stable placeholders so detection output
+/// can be shared without leaking names, while keeping its shape
+/// (e.g. `/home/me/work/secret.rs` -> `~/<dir1>/<file1>.rs`). Windows paths
+/// keep their `\` separators and drive letter.
+pub struct Redactor {
+    home: Option<String>,
+    project_name: Option<String>,
+    dirs: HashMap<String, String>,
+    files: HashMap<String, String>,
+}
+
+impl Redactor {
+    /// `home_dir` is the home to collapse to `~`; None uses `$HOME`, or
+    /// `%USERPROFILE%` on Windows
+    pub fn new(project_path: Option<&str>, home_dir: Option<&str>) -> Self {
+        let home = home_dir
+            .map(|h| h.to_string())
+            .or_else(|| std::env::var("HOME").ok())
+            .or_else(|| std::env::var("USERPROFILE").ok())
+            .map(|h| h.trim_end_matches(SEPARATORS).to_string())
+            .filter(|h| !h.is_empty());
+
+        // Path::file_name only splits on '\\' when built for Windows
+        let project_name = project_path
+            .map(|p| p.trim_end_matches(SEPARATORS))
+            .and_then(|p| p.rsplit(SEPARATORS).next())
+            .filter(|n| !n.is_empty())
+            .map(|n| n.to_string());
+
+        Self {
+            home,
+            project_name,
+            dirs: HashMap::new(),
+            files: HashMap::new(),
+        }
+    }
+
+    /// Redact every path and name in a detection result in place
+    pub fn redact_result(&mut self, result: &mut DetectionResult) {
+        result.project_path = result.project_path.as_deref().map(|p| self.redact_dir(p));
+        result.active_file = result.active_file.as_deref().map(|p| self.redact_path(p));
+
+        for file in &mut result.open_files {
+            file.path = self.redact_path(&file.path);
+            file.name = self.redact_file_name(&file.name);
+            file.project_name = file.project_name.as_deref().map(|n| self.redact_dir_name(n));
+            file.project_path = file.project_path.as_deref().map(|p| self.redact_dir(p));
+            file.real_path = file.real_path.as_deref().map(|p| self.redact_path(p));
+        }
+    }
+
+    /// Redact a file path: directories and the basename are replaced
+    fn redact_path(&mut self, path: &str) -> String {
+        match path.rfind(SEPARATORS) {
+            Some(index) => {
+                let (dir, name) = (&path[..index], &path[index + 1..]);
+                let separator = &path[index..index + 1];
+                format!("{}{}{}", self.redact_dir(dir), separator, self.redact_file_name(name))
+            }
+            None => self.redact_file_name(path),
+        }
+    }
+
+    /// Redact a directory path, collapsing the home directory to `~`
+    fn redact_dir(&mut self, dir: &str) -> String {
+        let (prefix, rest) = match &self.home {
+            Some(home) if dir == home => return "~".to_string(),
+            Some(home) if dir.starts_with(home.as_str()) && dir[home.len()..].starts_with(SEPARATORS) => {
+                ("~", &dir[home.len()..])
+            }
+            _ => ("", dir),
+        };
+
+        let mut redacted = prefix.to_string();
+        for component in rest.split_inclusive(SEPARATORS) {
+            let name = component.trim_end_matches(SEPARATORS);
+            if !name.is_empty() && !is_drive(name) {
+                redacted.push_str(&self.redact_dir_name(name));
+            } else {
+                redacted.push_str(name);
+            }
+            redacted.push_str(&component[name.len()..]);
+        }
+        redacted
+    }
+
+    fn redact_dir_name(&mut self, name: &str) -> String {
+        if self.project_name.as_deref() == Some(name) {
+            return "<proj>".to_string();
+        }
+
+        let next = self.dirs.len() + 1;
+        self.dirs
+            .entry(name.to_string())
+            .or_insert_with(|| format!("<dir{}>", next))
+            .clone()
+    }
+
+    /// Redact a file name, keeping its extension
+    fn redact_file_name(&mut self, name: &str) -> String {
+        let (stem, extension) = match name.rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() => (stem, Some(ext)),
+            _ => (name, None),
+        };
+
+        let next = self.files.len() + 1;
+        let placeholder = self
+            .files
+            .entry(stem.to_string())
+            .or_insert_with(|| format!("<file{}>", next))
+            .clone();
+
+        match extension {
+            Some(ext) => format!("{}.{}", placeholder, ext),
+            None => placeholder,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FileInfo;
+
+    fn file(path: &str, is_active: bool, is_modified: bool) -> FileInfo {
+        FileInfo {
+            name: path.rsplit(SEPARATORS).next().unwrap().to_string(),
+            is_modified,
+            ..FileInfo::for_test(path, is_active)
+        }
+    }
+
+    fn result(project_path: &str, files: Vec<FileInfo>) -> DetectionResult {
+        DetectionResult {
+            project_path: Some(project_path.to_string()),
+            ..DetectionResult::for_test("Visual Studio Code", files)
+        }
+    }
+
+    #[test]
+    fn names_are_replaced_while_extensions_and_flags_survive() {
+        let mut unix = result(
+            "/home/alice/work/payroll",
+            vec![
+                file("/home/alice/work/payroll/src/salaries.rs", true, false),
+                file("/home/alice/work/payroll/Makefile", false, true),
+            ],
+        );
+        Redactor::new(unix.project_path.as_deref(), Some("/home/alice")).redact_result(&mut unix);
+
+        assert_eq!(unix.project_path.as_deref(), Some("~/<dir1>/<proj>"));
+        assert_eq!(unix.active_file.as_deref(), Some("~/<dir1>/<proj>/<dir2>/<file1>.rs"));
+        let files: Vec<_> = unix
+            .open_files
+            .iter()
+            .map(|f| (f.path.as_str(), f.name.as_str(), f.is_active, f.is_modified))
+            .collect();
+        assert_eq!(
+            files,
+            [
+                ("~/<dir1>/<proj>/<dir2>/<file1>.rs", "<file1>.rs", true, false),
+                ("~/<dir1>/<proj>/<file2>", "<file2>", false, true),
+            ]
+        );
+
+        let mut windows = result(
+            "C:\\Users\\alice\\payroll",
+            vec![file("C:\\Users\\alice\\payroll\\salaries.py", true, true)],
+        );
+        Redactor::new(windows.project_path.as_deref(), Some("C:\\Users\\alice")).redact_result(&mut windows);
+        assert_eq!(windows.project_path.as_deref(), Some("~\\<proj>"));
+        assert_eq!(windows.open_files[0].path, "~\\<proj>\\<file1>.py");
+        assert!(windows.open_files[0].is_active && windows.open_files[0].is_modified);
+
+        // Outside the home directory only the drive is kept
+        let mut other_drive = result("D:\\src\\payroll", vec![file("D:\\src\\payroll\\salaries.py", true, false)]);
+        Redactor::new(other_drive.project_path.as_deref(), Some("C:\\Users\\alice")).redact_result(&mut other_drive);
+        assert_eq!(other_drive.open_files[0].path, "D:\\<dir1>\\<proj>\\<file1>.py");
+
+        for path in [unix.open_files[0].path.as_str(), windows.open_files[0].path.as_str()] {
+            assert!(!path.contains("alice") && !path.contains("payroll") && !path.contains("salaries"), "{}", path);
+        }
+    }
+}
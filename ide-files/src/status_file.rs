@@ -0,0 +1,80 @@
+use crate::detector::{DetectionError, DetectionResult};
+use crate::types::{FileInfo, FileSource};
+use std::fs;
+use std::path::Path;
+
+/// Build a result from a status file that an editor plugin keeps updated with
+/// the path of the current file (first non-empty line), without scanning
+/// processes at all
+pub fn read_status_file(status_file: &Path) -> DetectionResult<crate::types::DetectionResult> {
+    let content = fs::read_to_string(status_file).map_err(|e| DetectionError::InvalidStatusFile {
+        message: format!("failed to read {}: {}", status_file.display(), e),
+    })?;
+
+    let active_path = content
+        .lines()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty())
+        .ok_or_else(|| DetectionError::InvalidStatusFile {
+            message: format!("{} is empty", status_file.display()),
+        })?;
+
+    if !Path::new(active_path).is_file() {
+        return Err(DetectionError::InvalidStatusFile {
+            message: format!(
+                "{} names {}, which is not an existing file",
+                status_file.display(),
+                active_path
+            ),
+        });
+    }
+
+    Ok(crate::types::DetectionResult {
+        format_version: crate::types::FORMAT_VERSION,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        ide_name: "Status file".to_string(),
+        ide_version: None,
+        active_file: Some(active_path.to_string()),
+        open_files: vec![FileInfo {
+            is_active: true,
+            ..FileInfo::new(active_path, FileSource::SessionState)
+        }],
+        project_path: None,
+        group_count: None,
+        project_language: None,
+        seq: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_non_empty_line_names_the_active_file() {
+        let dir = std::env::temp_dir().join(format!("ide-files-status-file-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let active = dir.join("main.rs");
+        fs::write(&active, "").unwrap();
+        let status_file = dir.join("status");
+
+        fs::write(&status_file, format!("\n  {}  \n/ignored/second/line\n", active.display())).unwrap();
+        let result = read_status_file(&status_file).unwrap();
+        let active = active.to_string_lossy();
+        assert_eq!(result.active_file.as_deref(), Some(&*active));
+        assert_eq!(result.open_files.len(), 1);
+        assert_eq!(result.open_files[0].name, "main.rs");
+        assert!(result.open_files[0].is_active);
+
+        for content in ["\n \n", "/no/such/file.rs\n"] {
+            fs::write(&status_file, content).unwrap();
+            assert!(matches!(
+                read_status_file(&status_file),
+                Err(DetectionError::InvalidStatusFile { .. })
+            ));
+        }
+        assert!(read_status_file(&dir.join("missing")).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
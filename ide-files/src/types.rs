@@ -8,16 +8,262 @@ pub struct FileInfo {
     pub is_modified: bool,
     pub tab_index: Option<usize>,
     pub project_name: Option<String>,
+    pub source: FileSource,
+    /// Line the cursor starts on (1-based), when the editor was told to jump there
+    pub cursor_line: Option<usize>,
+    /// Column of the cursor on `cursor_line` (1-based), when known
+    pub cursor_column: Option<usize>,
+    /// Set when the file was opened as one side of a diff or merge editor
+    pub comparison: Option<Comparison>,
+    /// Root of the project this file belongs to, when a result spans several
+    pub project_path: Option<String>,
+    /// Opened read-only, e.g. with `view` or `vim -R`
+    pub read_only: bool,
+    /// Tab pinned in the editor, so it stays open when others are closed
+    pub is_pinned: bool,
+    /// Preview (italic) tab, replaced by the next file opened unless edited or kept
+    pub is_preview: bool,
+    /// Where `path` leads when it is a symlink to a different file; `path`
+    /// itself stays as the editor reported it
+    pub real_path: Option<String>,
+    /// Terminal emulator window a terminal editor runs in, or the application
+    /// embedding a headless Neovim
+    pub terminal: Option<TerminalHost>,
+    /// Stable short hash of the canonical path (`--with-ids`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
 }
 
+impl FileInfo {
+    /// A file named after the last component of `path`, with every flag unset
+    /// and nothing else known; detectors set the rest with struct update syntax
+    pub fn new(path: &str, source: FileSource) -> Self {
+        let name = std::path::Path::new(path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(path)
+            .to_string();
+
+        Self {
+            path: path.to_string(),
+            name,
+            is_active: false,
+            is_modified: false,
+            tab_index: None,
+            project_name: None,
+            source,
+            cursor_line: None,
+            cursor_column: None,
+            comparison: None,
+            project_path: None,
+            read_only: false,
+            is_pinned: false,
+            is_preview: false,
+            real_path: None,
+            terminal: None,
+            id: None,
+        }
+    }
+
+    /// An open file from session state, for tests across the crate
+    #[cfg(test)]
+    pub fn for_test(path: &str, is_active: bool) -> Self {
+        Self {
+            is_active,
+            ..Self::new(path, FileSource::SessionState)
+        }
+    }
+
+    /// A short identifier for a file that stays the same across runs, so
+    /// consumers can key cached state by file without storing its path
+    pub fn stable_id(path: &str) -> String {
+        let canonical = std::fs::canonicalize(path)
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_else(|_| path.to_string());
+        blake3::hash(canonical.as_bytes()).to_hex()[..16].to_string()
+    }
+
+    /// The fully resolved target of `path` if `path` is itself a symlink
+    pub fn symlink_target(path: &str) -> Option<String> {
+        if !std::fs::symlink_metadata(path).ok()?.file_type().is_symlink() {
+            return None;
+        }
+        let target = std::fs::canonicalize(path).ok()?;
+        let target = target.to_str()?;
+        (target != path).then(|| target.to_string())
+    }
+}
+
+/// A file's part in a comparison editor (`code --diff` / `code --merge`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    /// "diff" or "merge"
+    pub scheme: String,
+    /// Files shown in the same comparison editor share a group
+    pub group: usize,
+    /// left/right for a diff; input1/input2/base/result for a merge
+    pub role: String,
+}
+
+/// The terminal emulator (or embedding application) hosting a terminal
+/// editor, for focusing its window
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TerminalHost {
+    /// Emulator process name, e.g. "kitty"
+    pub name: String,
+    /// X11 window id (hex), Sway con_id or Hyprland window address
+    pub window_id: Option<String>,
+}
+
+/// Where a tab sits in the tab strip, by how it is kept open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TabKind {
+    Pinned,
+    Regular,
+    /// A preview/scratch tab, replaced by the next file opened
+    Scratch,
+}
+
+impl TabKind {
+    /// The `tab_index` of each tab recorded (in document order) as `kinds`,
+    /// matching the tab strip: pinned tabs first, then regular ones, then
+    /// scratch ones, each in their recorded order
+    pub fn visual_indexes(kinds: &[TabKind]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..kinds.len()).collect();
+        order.sort_by_key(|&position| kinds[position]);
+
+        let mut indexes = vec![0; kinds.len()];
+        for (index, position) in order.into_iter().enumerate() {
+            indexes[position] = index;
+        }
+        indexes
+    }
+}
+
+/// Where a detector learned about an open file
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileSource {
+    /// The IDE's own persisted tab/session state (workspace.xml, state.vscdb)
+    SessionState,
+    /// The window title of a running IDE
+    WindowTitle,
+    /// A file argument on the editor's command line
+    CommandLine,
+    /// The IDE's history of recently opened editors, which may since have
+    /// been closed
+    RecentHistory,
+    /// Guessed from the project directory contents
+    Heuristic,
+}
+
+impl FileSource {
+    /// Rank by how reliably the source reflects an actually open tab (0 = most reliable)
+    pub fn confidence_rank(&self) -> u8 {
+        match self {
+            FileSource::SessionState => 0,
+            FileSource::WindowTitle => 1,
+            FileSource::CommandLine => 2,
+            FileSource::RecentHistory => 3,
+            FileSource::Heuristic => 4,
+        }
+    }
+}
+
+/// Version of the output schema. Bumped only for incompatible changes
+/// (removed, renamed or retyped fields); new fields don't bump it.
+pub const FORMAT_VERSION: u32 = 1;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DetectionResult {
+    /// Always `FORMAT_VERSION`, so consumers can detect breaking changes
+    pub format_version: u32,
     pub timestamp: String,
     pub ide_name: String,
     pub ide_version: Option<String>,
     pub active_file: Option<String>,
     pub open_files: Vec<FileInfo>,
     pub project_path: Option<String>,
+    /// Number of editor groups/splits shown, when the detector can tell
+    pub group_count: Option<usize>,
+    /// Main language of the project (e.g. "go", "python", "java"), from the
+    /// IDE's project settings, even when no files are open
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_language: Option<String>,
+    /// Position in a `--watch` stream (1 for the first result), so consumers
+    /// can spot dropped or repeated updates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+}
+
+impl DetectionResult {
+    /// A result holding `open_files`, with the active one (if any) as
+    /// `active_file`, for tests across the crate
+    #[cfg(test)]
+    pub fn for_test(ide_name: &str, open_files: Vec<FileInfo>) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+            ide_name: ide_name.to_string(),
+            ide_version: None,
+            active_file: open_files.iter().find(|f| f.is_active).map(|f| f.path.clone()),
+            open_files,
+            project_path: None,
+            group_count: None,
+            project_language: None,
+            seq: None,
+        }
+    }
+
+    /// Default `open_files` ordering: the active file first, then by tab
+    /// index (files without one last), then by source confidence, then by path
+    pub fn sort_files_default(&mut self) {
+        self.open_files.sort_by(|a, b| {
+            b.is_active
+                .cmp(&a.is_active)
+                .then_with(|| match (a.tab_index, b.tab_index) {
+                    (Some(a), Some(b)) => a.cmp(&b),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                })
+                .then_with(|| a.source.confidence_rank().cmp(&b.source.confidence_rank()))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+    }
+
+    /// Rewrite file paths, and `active_file`, relative to their project root
+    /// (the file's own `project_path`, else the result's). Files outside it,
+    /// or without a known project, keep their absolute path.
+    pub fn relativize_paths(&mut self) {
+        let relative = |path: &str, root: Option<&str>| -> Option<String> {
+            let rest = std::path::Path::new(path).strip_prefix(root?).ok()?;
+            rest.to_str().filter(|rest| !rest.is_empty()).map(str::to_string)
+        };
+
+        if let Some(active_file) = &self.active_file {
+            let root = self
+                .open_files
+                .iter()
+                .find(|f| f.path == *active_file)
+                .and_then(|f| f.project_path.as_deref())
+                .or(self.project_path.as_deref());
+            if let Some(path) = relative(active_file, root) {
+                self.active_file = Some(path);
+            }
+        }
+        for file in &mut self.open_files {
+            let root = file.project_path.as_deref().or(self.project_path.as_deref());
+            if let Some(path) = relative(&file.path, root) {
+                file.path = path;
+            }
+        }
+    }
+
+    /// Consume the result, yielding its open files one at a time
+    pub fn into_files(self) -> impl Iterator<Item = FileInfo> {
+        self.open_files.into_iter()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -25,7 +271,90 @@ pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
     pub window_title: String,
+    /// Titles of all the process's windows (e.g. one per IDE frame), bottom
+    /// of the stacking order first; `window_title` is the topmost
+    pub window_titles: Vec<String>,
+    /// True when `window_title` is not a real window title but a fallback
+    /// (e.g. the process command line when no X11 window was found)
+    pub title_is_synthetic: bool,
     pub executable_path: String,
+    /// Working directory, which relative paths on its command line are
+    /// relative to; empty when it couldn't be read
+    pub cwd: String,
+    pub status: ProcessStatus,
+    /// Process start time as Unix seconds, when the platform reports it
+    pub start_time: Option<u64>,
+}
+
+impl ProcessInfo {
+    /// A running process with no window titles or start time, for callers
+    /// that enumerate processes themselves; set the other fields as known
+    pub fn new(pid: u32, name: &str, executable_path: &str) -> Self {
+        Self {
+            pid,
+            name: name.to_string(),
+            window_title: String::new(),
+            window_titles: Vec::new(),
+            title_is_synthetic: false,
+            executable_path: executable_path.to_string(),
+            cwd: String::new(),
+            status: ProcessStatus::Running,
+            start_time: None,
+        }
+    }
+
+    /// Build a process with the given name and executable for detector tests
+    #[cfg(test)]
+    pub fn for_test(name: &str, executable_path: &str) -> Self {
+        Self {
+            status: ProcessStatus::Sleeping,
+            ..Self::new(0, name, executable_path)
+        }
+    }
+
+    /// Seconds since the process started, if its start time is known
+    pub fn age_secs(&self) -> Option<u64> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_secs();
+        self.start_time.map(|start| now.saturating_sub(start))
+    }
+}
+
+/// A user account whose editors are inspected
+#[derive(Debug, Clone)]
+pub struct UserAccount {
+    pub name: String,
+    pub uid: u32,
+    pub home_dir: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProcessStatus {
+    Running,
+    Sleeping,
+    Stopped,
+    Zombie,
+    Unknown,
+}
+
+impl ProcessStatus {
+    /// Map the state letter from `/proc/<pid>/stat`
+    pub fn from_stat_char(state: char) -> ProcessStatus {
+        match state {
+            'R' => ProcessStatus::Running,
+            'S' | 'D' | 'I' => ProcessStatus::Sleeping,
+            'T' | 't' => ProcessStatus::Stopped,
+            'Z' | 'X' | 'x' => ProcessStatus::Zombie,
+            _ => ProcessStatus::Unknown,
+        }
+    }
+
+    /// Zombie and stopped processes can't be showing anything to the user
+    pub fn is_stale(&self) -> bool {
+        matches!(self, ProcessStatus::Zombie | ProcessStatus::Stopped)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -39,8 +368,21 @@ pub enum SupportedIDE {
     PhpStorm,
     RubyMine,
     CLion,
+    AndroidStudio,
+    DataGrip,
+    Rider,
+    RustRover,
+    AppCode,
+    DataSpell,
     Vim,
     Nano,
+    Onivim,
+    Helix,
+    Kakoune,
+    Ed,
+    Sublime,
+    Cursor,
+    VSCodeInsiders,
 }
 
 impl SupportedIDE {
@@ -55,8 +397,21 @@ impl SupportedIDE {
             SupportedIDE::PhpStorm => "phpstorm",
             SupportedIDE::RubyMine => "rubymine",
             SupportedIDE::CLion => "clion",
+            SupportedIDE::AndroidStudio => "androidstudio",
+            SupportedIDE::DataGrip => "datagrip",
+            SupportedIDE::Rider => "rider",
+            SupportedIDE::RustRover => "rustrover",
+            SupportedIDE::AppCode => "appcode",
+            SupportedIDE::DataSpell => "dataspell",
             SupportedIDE::Vim => "vim",
             SupportedIDE::Nano => "nano",
+            SupportedIDE::Onivim => "onivim",
+            SupportedIDE::Helix => "helix",
+            SupportedIDE::Kakoune => "kakoune",
+            SupportedIDE::Ed => "ed",
+            SupportedIDE::Sublime => "sublime",
+            SupportedIDE::Cursor => "cursor",
+            SupportedIDE::VSCodeInsiders => "vscode-insiders",
         }
     }
 
@@ -71,8 +426,21 @@ impl SupportedIDE {
             SupportedIDE::PhpStorm => "PhpStorm",
             SupportedIDE::RubyMine => "RubyMine",
             SupportedIDE::CLion => "CLion",
+            SupportedIDE::AndroidStudio => "Android Studio",
+            SupportedIDE::DataGrip => "DataGrip",
+            SupportedIDE::Rider => "Rider",
+            SupportedIDE::RustRover => "RustRover",
+            SupportedIDE::AppCode => "AppCode",
+            SupportedIDE::DataSpell => "DataSpell",
             SupportedIDE::Vim => "Vim",
             SupportedIDE::Nano => "Nano",
+            SupportedIDE::Onivim => "Onivim 2",
+            SupportedIDE::Helix => "Helix",
+            SupportedIDE::Kakoune => "Kakoune",
+            SupportedIDE::Ed => "ed",
+            SupportedIDE::Sublime => "Sublime Text",
+            SupportedIDE::Cursor => "Cursor",
+            SupportedIDE::VSCodeInsiders => "Visual Studio Code - Insiders",
         }
     }
 
@@ -87,14 +455,206 @@ impl SupportedIDE {
             SupportedIDE::PhpStorm,
             SupportedIDE::RubyMine,
             SupportedIDE::CLion,
+            SupportedIDE::AndroidStudio,
+            SupportedIDE::DataGrip,
+            SupportedIDE::Rider,
+            SupportedIDE::RustRover,
+            SupportedIDE::AppCode,
+            SupportedIDE::DataSpell,
             SupportedIDE::Vim,
             SupportedIDE::Nano,
+            SupportedIDE::Onivim,
+            SupportedIDE::Helix,
+            SupportedIDE::Kakoune,
+            SupportedIDE::Ed,
+            SupportedIDE::Sublime,
+            SupportedIDE::Cursor,
+            SupportedIDE::VSCodeInsiders,
         ]
     }
 
+    /// Look up an IDE by its `--ide` name, ignoring case
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<SupportedIDE> {
         SupportedIDE::all()
             .into_iter()
             .find(|ide| ide.as_str().eq_ignore_ascii_case(s))
     }
+
+    /// The process names (lowercase) this IDE runs under, before the
+    /// variants `matches_process_name` also accepts
+    pub fn process_names(&self) -> &'static [&'static str] {
+        match self {
+            SupportedIDE::GoLand => &["goland", "goland.exe", "goland64.exe"],
+            SupportedIDE::PyCharm => &["pycharm", "pycharm.exe", "pycharm64.exe"],
+            SupportedIDE::IntelliJIDEA => &["idea", "idea.exe", "idea64.exe"],
+            SupportedIDE::WebStorm => &["webstorm", "webstorm.exe", "webstorm64.exe"],
+            SupportedIDE::PhpStorm => &["phpstorm", "phpstorm.exe", "phpstorm64.exe"],
+            SupportedIDE::RubyMine => &["rubymine", "rubymine.exe", "rubymine64.exe"],
+            SupportedIDE::CLion => &["clion", "clion.exe", "clion64.exe"],
+            SupportedIDE::AndroidStudio => &["studio", "studio.exe", "studio64.exe"],
+            SupportedIDE::DataGrip => &["datagrip", "datagrip.exe", "datagrip64.exe"],
+            SupportedIDE::Rider => &["rider", "rider.exe", "rider64.exe"],
+            SupportedIDE::RustRover => &["rustrover", "rustrover.exe", "rustrover64.exe"],
+            // AppCode only ever shipped for macOS
+            SupportedIDE::AppCode => &["appcode"],
+            SupportedIDE::DataSpell => &["dataspell", "dataspell.exe", "dataspell64.exe"],
+            SupportedIDE::VSCode => &["code", "code-oss", "codium", "code.exe"],
+            SupportedIDE::VSCodeInsiders => &["code-insiders", "code - insiders", "code - insiders.exe"],
+            SupportedIDE::Cursor => &["cursor", "cursor.exe"],
+            // `view` is Vim in read-only mode and `ex` Vim in Ex mode
+            SupportedIDE::Vim => &["vim", "nvim", "gvim", "view", "ex"],
+            SupportedIDE::Nano => &["nano"],
+            SupportedIDE::Helix => &["hx", "helix"],
+            SupportedIDE::Kakoune => &["kak"],
+            SupportedIDE::Ed => &["ed"],
+            SupportedIDE::Onivim => &["oni2", "onivim2", "oni2.exe", "onivim2.exe"],
+            SupportedIDE::Sublime => &["sublime_text", "subl", "sublime_text.exe", "sublime text"],
+            // No detector yet
+            SupportedIDE::VisualStudio => &[],
+        }
+    }
+
+    /// Whether a process name is one of this IDE's, ignoring case. Names
+    /// match exactly, so tools sharing a prefix ("ideapad", "vimdiff",
+    /// "codesign") don't count, but for:
+    /// - JetBrains IDEs, their `.sh` launchers ("goland.sh")
+    /// - terminal editors, distribution builds ("vim.basic", "nano.exe")
+    /// - VSCode and its forks, their macOS helpers ("Code Helper (Renderer)")
+    pub fn matches_process_name(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.process_names().iter().any(|&known| {
+            name == known
+                || match self {
+                    SupportedIDE::Vim
+                    | SupportedIDE::Nano
+                    | SupportedIDE::Helix
+                    | SupportedIDE::Kakoune
+                    | SupportedIDE::Ed => name.starts_with(&format!("{}.", known)),
+                    SupportedIDE::VSCode | SupportedIDE::VSCodeInsiders | SupportedIDE::Cursor => {
+                        name.starts_with(&format!("{} helper", known))
+                    }
+                    SupportedIDE::GoLand
+                    | SupportedIDE::PyCharm
+                    | SupportedIDE::IntelliJIDEA
+                    | SupportedIDE::WebStorm
+                    | SupportedIDE::PhpStorm
+                    | SupportedIDE::RubyMine
+                    | SupportedIDE::CLion
+                    | SupportedIDE::AndroidStudio
+                    | SupportedIDE::DataGrip
+                    | SupportedIDE::Rider
+                    | SupportedIDE::RustRover
+                    | SupportedIDE::AppCode
+                    | SupportedIDE::DataSpell => name == format!("{}.sh", known),
+                    SupportedIDE::Onivim | SupportedIDE::Sublime | SupportedIDE::VisualStudio => false,
+                }
+        })
+    }
+
+    /// The IDE a process with this name belongs to, if any
+    pub fn from_process_name(name: &str) -> Option<SupportedIDE> {
+        SupportedIDE::all()
+            .into_iter()
+            .find(|ide| ide.matches_process_name(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, is_active: bool, tab_index: Option<usize>, source: FileSource) -> FileInfo {
+        FileInfo {
+            is_active,
+            tab_index,
+            ..FileInfo::new(path, source)
+        }
+    }
+
+    #[test]
+    fn default_order_is_active_then_tab_then_confidence_then_path() {
+        use FileSource::*;
+        let mut result = DetectionResult::for_test(
+            "GoLand",
+            vec![
+                file("/p/guess.go", false, None, Heuristic),
+                file("/p/b.go", false, None, CommandLine),
+                file("/p/tab1.go", false, Some(1), RecentHistory),
+                file("/p/a.go", false, None, CommandLine),
+                file("/p/title.go", false, None, WindowTitle),
+                file("/p/z.go", true, Some(3), WindowTitle),
+                file("/p/tab0.go", false, Some(0), SessionState),
+                file("/p/history.go", false, None, RecentHistory),
+                file("/p/session.go", false, None, SessionState),
+            ],
+        );
+
+        result.sort_files_default();
+        let paths: Vec<&str> = result.open_files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/p/z.go",
+                "/p/tab0.go",
+                "/p/tab1.go",
+                "/p/session.go",
+                "/p/title.go",
+                "/p/a.go",
+                "/p/b.go",
+                "/p/history.go",
+                "/p/guess.go",
+            ]
+        );
+    }
+
+    #[test]
+    fn pinned_tabs_come_first_and_scratch_tabs_last() {
+        use TabKind::*;
+        let kinds = [Regular, Scratch, Pinned, Regular, Pinned];
+
+        assert_eq!(TabKind::visual_indexes(&kinds), [2, 4, 0, 3, 1]);
+        assert_eq!(TabKind::visual_indexes(&[Regular, Regular]), [0, 1]);
+        assert_eq!(TabKind::visual_indexes(&[]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn process_names_map_to_their_ide() {
+        let cases = [
+            ("goland", Some(SupportedIDE::GoLand)),
+            ("GoLand.sh", Some(SupportedIDE::GoLand)),
+            ("idea64.exe", Some(SupportedIDE::IntelliJIDEA)),
+            ("ideapad-daemon", None),
+            ("Code", Some(SupportedIDE::VSCode)),
+            ("Code Helper (Renderer)", Some(SupportedIDE::VSCode)),
+            ("Code - Insiders", Some(SupportedIDE::VSCodeInsiders)),
+            ("codesign", None),
+            ("Cursor", Some(SupportedIDE::Cursor)),
+            ("nvim", Some(SupportedIDE::Vim)),
+            ("vim.basic", Some(SupportedIDE::Vim)),
+            ("vimdiff", None),
+            ("nano.exe", Some(SupportedIDE::Nano)),
+            ("hx", Some(SupportedIDE::Helix)),
+            ("Onivim2", Some(SupportedIDE::Onivim)),
+            ("Sublime Text", Some(SupportedIDE::Sublime)),
+            ("sublime_text.sh", None),
+            ("bash", None),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(SupportedIDE::from_process_name(name), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn stable_id_depends_only_on_the_canonical_path() {
+        let manifest = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        let spelled_differently = concat!(env!("CARGO_MANIFEST_DIR"), "/src/../Cargo.toml");
+
+        let id = FileInfo::stable_id(manifest);
+        assert_eq!(id.len(), 16);
+        assert_eq!(FileInfo::stable_id(manifest), id);
+        assert_eq!(FileInfo::stable_id(spelled_differently), id);
+        assert_ne!(FileInfo::stable_id(concat!(env!("CARGO_MANIFEST_DIR"), "/README.md")), id);
+    }
 }
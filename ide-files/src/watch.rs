@@ -0,0 +1,712 @@
+use crate::detector::{DetectionError, IDEDetectorManager};
+use crate::types::{DetectionResult, ProcessInfo, SupportedIDE};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default for how often to check for IDE processes starting or exiting, and
+/// to re-detect outright when no state file could be watched
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Longest a watcher or poller waits before noticing its stop flag
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Default for how long a result must hold still before it is reported.
+/// IDEs rewrite their state in bursts (several writes per tab switch), and
+/// in between may record a stale or missing active file.
+pub const DEBOUNCE_WINDOW: Duration = Duration::from_millis(150);
+
+/// Re-runs detection when the IDE's state files change (via inotify and
+/// friends) or its processes start or exit, instead of re-scanning on a timer
+pub struct DetectionWatcher<'a> {
+    manager: &'a IDEDetectorManager,
+    ide_type: Option<SupportedIDE>,
+    poll_interval: Duration,
+    debounce: Duration,
+    stop: Option<Arc<AtomicBool>>,
+}
+
+impl<'a> DetectionWatcher<'a> {
+    /// Watch `ide_type`, or whatever `auto_detect` finds when None
+    pub fn new(manager: &'a IDEDetectorManager, ide_type: Option<SupportedIDE>) -> Self {
+        Self {
+            manager,
+            ide_type,
+            poll_interval: POLL_INTERVAL,
+            debounce: DEBOUNCE_WINDOW,
+            stop: None,
+        }
+    }
+
+    /// Check for processes starting or exiting every `interval` instead of every [`POLL_INTERVAL`]
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Report a result once it has been stable for `window` instead of
+    /// [`DEBOUNCE_WINDOW`]; zero reports every change at once
+    pub fn with_debounce(mut self, window: Duration) -> Self {
+        self.debounce = window;
+        self
+    }
+
+    /// Return from `run` soon after `stop` is set (e.g. by a SIGINT handler)
+    pub fn with_stop_flag(mut self, stop: Arc<AtomicBool>) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    /// Call `on_change` with the first result and again each time the result
+    /// changes, until it returns false
+    pub fn run<F>(&self, mut on_change: F)
+    where
+        F: FnMut(Result<DetectionResult, DetectionError>) -> bool,
+    {
+        let (sender, receiver) = mpsc::channel();
+        // Keep our own sender alive so the channel never disconnects, even
+        // when no watcher could be created and we fall back to polling
+        let mut watcher = notify::recommended_watcher(sender.clone()).ok();
+        let mut watched: Vec<PathBuf> = Vec::new();
+        let mut pids = self.manager.target_pids(self.ide_type);
+        let mut last_fingerprint = None;
+        let mut sequence = Sequence::default();
+        let mut settler = Settler::new(self.debounce);
+        let mut redetect = true;
+        // The process poll's scan, reused when it triggers the next detection
+        let mut scan = None;
+        let mut next_poll = Instant::now() + self.poll_interval;
+
+        loop {
+            if stopped(&self.stop) {
+                return;
+            }
+            if redetect {
+                let result = self.detect(scan.take());
+                if let (Some(watcher), Ok(result)) = (watcher.as_mut(), &result) {
+                    self.update_watches(watcher, &mut watched, self.manager.state_paths(result));
+                }
+                settler.observe(fingerprint(&result), result, Instant::now());
+            }
+
+            if let Some((fingerprint, result)) = settler.settled(Instant::now()) {
+                if last_fingerprint.as_ref() != Some(&fingerprint) {
+                    last_fingerprint = Some(fingerprint);
+                    let result = result.map(|mut result| {
+                        sequence.stamp(&mut result);
+                        result
+                    });
+                    if !on_change(result) {
+                        return;
+                    }
+                }
+            }
+
+            scan = None;
+            // Wake for the next process poll, or sooner to report a settled result
+            let wake = settler.deadline().map_or(next_poll, |deadline| deadline.min(next_poll));
+            let Some(event) = wait(&receiver, wake.saturating_duration_since(Instant::now()), &self.stop) else {
+                return;
+            };
+            redetect = match event {
+                // Our own reads show up as access events; only writes matter
+                Ok(Ok(event)) if event.kind.is_access() => false,
+                Ok(_) => {
+                    while receiver.try_recv().is_ok() {}
+                    true
+                }
+                Err(RecvTimeoutError::Timeout) if Instant::now() < next_poll => false,
+                Err(RecvTimeoutError::Timeout) => {
+                    next_poll = Instant::now() + self.poll_interval;
+                    // A failed scan is left for detection to rescan and report
+                    scan = self.manager.scan().ok();
+                    let processes = scan.as_deref().unwrap_or_default();
+                    let current = self.manager.target_pids_in(processes, self.ide_type);
+                    let processes_changed = current != pids;
+                    pids = current;
+                    processes_changed || watched.is_empty()
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    if !sleep(self.poll_interval, &self.stop) {
+                        return;
+                    }
+                    true
+                }
+            };
+        }
+    }
+
+    /// Detect over `processes` when a fresh scan is at hand, otherwise scan anew
+    fn detect(&self, processes: Option<Vec<ProcessInfo>>) -> Result<DetectionResult, DetectionError> {
+        match processes {
+            Some(processes) => self.manager.detect_in(&processes, self.ide_type),
+            None => match self.ide_type {
+                Some(ide_type) => self.manager.detect_ide(ide_type),
+                None => self.manager.auto_detect(),
+            },
+        }
+    }
+
+    /// Swap the watched paths for `paths`, keeping only those that could be watched
+    fn update_watches(
+        &self,
+        watcher: &mut RecommendedWatcher,
+        watched: &mut Vec<PathBuf>,
+        paths: Vec<PathBuf>,
+    ) {
+        if *watched == paths {
+            return;
+        }
+
+        for path in watched.drain(..) {
+            let _ = watcher.unwatch(&path);
+        }
+
+        for path in paths {
+            let mode = if path.is_dir() {
+                RecursiveMode::Recursive
+            } else {
+                RecursiveMode::NonRecursive
+            };
+            if watcher.watch(&path, mode).is_ok() {
+                watched.push(path);
+            }
+        }
+    }
+}
+
+/// Holds back a changing value until it has had the same fingerprint for
+/// `window`, so a burst of changes is reported once, as what it settled on
+struct Settler<T> {
+    window: Duration,
+    pending: Option<(String, T, Instant)>,
+}
+
+impl<T> Settler<T> {
+    fn new(window: Duration) -> Self {
+        Self { window, pending: None }
+    }
+
+    /// Note the latest value; a new fingerprint restarts the window
+    fn observe(&mut self, fingerprint: String, value: T, now: Instant) {
+        let since = match &self.pending {
+            Some((pending, _, since)) if *pending == fingerprint => *since,
+            _ => now,
+        };
+        self.pending = Some((fingerprint, value, since));
+    }
+
+    /// When the pending value, if any, will have settled
+    fn deadline(&self) -> Option<Instant> {
+        self.pending.as_ref().map(|(_, _, since)| *since + self.window)
+    }
+
+    /// The pending value and its fingerprint, once it has held still for the window
+    fn settled(&mut self, now: Instant) -> Option<(String, T)> {
+        if self.deadline()? > now {
+            return None;
+        }
+        self.pending.take().map(|(fingerprint, value, _)| (fingerprint, value))
+    }
+}
+
+/// Numbers the results a watcher emits: 1, 2, 3, ...
+#[derive(Default)]
+struct Sequence {
+    last: u64,
+}
+
+impl Sequence {
+    fn stamp(&mut self, result: &mut DetectionResult) {
+        self.last += 1;
+        result.seq = Some(self.last);
+    }
+}
+
+/// Re-runs detection every `interval` and reports only when the active file
+/// changes, for building a focus history
+pub struct ActiveFilePoller<'a> {
+    manager: &'a IDEDetectorManager,
+    ide_type: Option<SupportedIDE>,
+    interval: Duration,
+    stop: Option<Arc<AtomicBool>>,
+}
+
+impl<'a> ActiveFilePoller<'a> {
+    /// Poll `ide_type`, or whatever `auto_detect` finds when None
+    pub fn new(manager: &'a IDEDetectorManager, ide_type: Option<SupportedIDE>, interval: Duration) -> Self {
+        Self {
+            manager,
+            ide_type,
+            interval,
+            stop: None,
+        }
+    }
+
+    /// Return from `run` soon after `stop` is set (e.g. by a SIGINT handler)
+    pub fn with_stop_flag(mut self, stop: Arc<AtomicBool>) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    /// Call `on_change` with each newly active file and the result it came
+    /// from, until it returns false. Failed detections (no IDE running) are
+    /// passed to `on_error` and otherwise skipped.
+    pub fn run<F, E>(&self, mut on_change: F, mut on_error: E)
+    where
+        F: FnMut(&str, &DetectionResult) -> bool,
+        E: FnMut(&DetectionError),
+    {
+        let mut changes = ActiveFileChanges::default();
+        loop {
+            let result = match self.ide_type {
+                Some(ide_type) => self.manager.detect_ide(ide_type),
+                None => self.manager.auto_detect(),
+            };
+            match result {
+                Ok(result) => {
+                    if let Some(active) = changes.observe(result.active_file.as_deref()) {
+                        if !on_change(active, &result) {
+                            return;
+                        }
+                    }
+                }
+                Err(e) => on_error(&e),
+            }
+            if !sleep(self.interval, &self.stop) {
+                return;
+            }
+        }
+    }
+
+    /// Poll for `duration` (or until stopped) and report how long each file
+    /// was active. Failed detections count as idle time, after `on_error`.
+    pub fn track<E>(&self, duration: Duration, mut on_error: E) -> Vec<FocusTime>
+    where
+        E: FnMut(&DetectionError),
+    {
+        let mut tracker = FocusTracker::default();
+        let deadline = Instant::now() + duration;
+        loop {
+            // Stamped when the poll starts, so the report spans the whole run
+            let polled_at = Instant::now();
+            let result = match self.ide_type {
+                Some(ide_type) => self.manager.detect_ide(ide_type),
+                None => self.manager.auto_detect(),
+            };
+            match result {
+                Ok(result) => tracker.observe(
+                    polled_at,
+                    result.active_file.as_deref(),
+                    result.project_path.as_deref(),
+                ),
+                Err(e) => {
+                    on_error(&e);
+                    tracker.observe(polled_at, None, None);
+                }
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() || !sleep(self.interval.min(left), &self.stop) {
+                break;
+            }
+        }
+        tracker.finish(Instant::now())
+    }
+}
+
+/// Time spent with one file active, for `--track`. The idle bucket (no
+/// file active, or no IDE running) has neither file nor project.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FocusTime {
+    pub file: Option<String>,
+    pub project: Option<String>,
+    pub seconds: f64,
+}
+
+/// Sums the intervals between successive observations of the active file:
+/// each one is credited to the file seen at its start
+#[derive(Default)]
+struct FocusTracker {
+    /// When the current interval started, and the file and project active then
+    current: Option<(Instant, Option<String>, Option<String>)>,
+    totals: Vec<FocusTime>,
+}
+
+impl FocusTracker {
+    fn observe(&mut self, at: Instant, file: Option<&str>, project: Option<&str>) {
+        let file = file.map(str::to_string);
+        // A project with no active file is still idle
+        let project = file.as_ref().and(project.map(str::to_string));
+        if let Some((_, current_file, current_project)) = &self.current {
+            if *current_file == file && *current_project == project {
+                return;
+            }
+        }
+        self.close(at);
+        self.current = Some((at, file, project));
+    }
+
+    fn close(&mut self, at: Instant) {
+        let Some((since, file, project)) = self.current.take() else {
+            return;
+        };
+        let seconds = at.saturating_duration_since(since).as_secs_f64();
+        match self.totals.iter_mut().find(|t| t.file == file && t.project == project) {
+            Some(total) => total.seconds += seconds,
+            None => self.totals.push(FocusTime { file, project, seconds }),
+        }
+    }
+
+    /// The totals up to `at`, longest first
+    fn finish(mut self, at: Instant) -> Vec<FocusTime> {
+        self.close(at);
+        self.totals.sort_by(|a, b| b.seconds.total_cmp(&a.seconds));
+        self.totals
+    }
+}
+
+/// Consecutive-duplicate filter over successive active files
+#[derive(Default)]
+struct ActiveFileChanges {
+    last: Option<String>,
+}
+
+impl ActiveFileChanges {
+    /// The active file if it differs from the last one reported. No active
+    /// file (nothing focused, IDE closed) is not a change, so focus history
+    /// doesn't record A twice for A, none, A.
+    fn observe<'f>(&mut self, active: Option<&'f str>) -> Option<&'f str> {
+        let active = active?;
+        if self.last.as_deref() == Some(active) {
+            return None;
+        }
+        self.last = Some(active.to_string());
+        Some(active)
+    }
+}
+
+/// Whether the stop flag, if any, has been set
+fn stopped(stop: &Option<Arc<AtomicBool>>) -> bool {
+    stop.as_ref().is_some_and(|stop| stop.load(Ordering::Relaxed))
+}
+
+/// `receiver.recv_timeout(timeout)`, checking `stop` along the way; None once it's set
+fn wait<T>(
+    receiver: &Receiver<T>,
+    timeout: Duration,
+    stop: &Option<Arc<AtomicBool>>,
+) -> Option<Result<T, RecvTimeoutError>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if stopped(stop) {
+            return None;
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Some(Err(RecvTimeoutError::Timeout));
+        }
+        match receiver.recv_timeout(left.min(STOP_CHECK_INTERVAL)) {
+            Err(RecvTimeoutError::Timeout) => continue,
+            received => return Some(received),
+        }
+    }
+}
+
+/// Sleep for `duration` unless `stop` is set first; false if it was
+fn sleep(duration: Duration, stop: &Option<Arc<AtomicBool>>) -> bool {
+    let deadline = Instant::now() + duration;
+    loop {
+        if stopped(stop) {
+            return false;
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        thread::sleep(left.min(STOP_CHECK_INTERVAL));
+    }
+}
+
+/// How an open file changed between two successive results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileEventKind {
+    Opened,
+    Closed,
+    Activated,
+}
+
+/// One file's change between two successive results, for `--watch --format ndjson`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEvent {
+    pub kind: FileEventKind,
+    pub path: String,
+    pub ide_name: String,
+}
+
+/// The files closed, opened and newly activated going from `previous` to
+/// `current`, where None means no IDE was detected (so every file closed)
+pub fn diff_results(previous: Option<&DetectionResult>, current: Option<&DetectionResult>) -> Vec<FileEvent> {
+    let paths = |result: Option<&DetectionResult>| -> HashSet<String> {
+        result
+            .map(|r| r.open_files.iter().map(|f| f.path.clone()).collect())
+            .unwrap_or_default()
+    };
+    let (before, after) = (paths(previous), paths(current));
+    let event = |kind, path: &str, result: &DetectionResult| FileEvent {
+        kind,
+        path: path.to_string(),
+        ide_name: result.ide_name.clone(),
+    };
+
+    let mut events = Vec::new();
+    if let Some(previous) = previous {
+        for file in previous.open_files.iter().filter(|f| !after.contains(&f.path)) {
+            events.push(event(FileEventKind::Closed, &file.path, previous));
+        }
+    }
+    if let Some(current) = current {
+        for file in current.open_files.iter().filter(|f| !before.contains(&f.path)) {
+            events.push(event(FileEventKind::Opened, &file.path, current));
+        }
+        let was_active = previous.and_then(|p| p.active_file.as_deref());
+        if let Some(active) = current.active_file.as_deref().filter(|a| Some(*a) != was_active) {
+            events.push(event(FileEventKind::Activated, active, current));
+        }
+    }
+    events
+}
+
+/// What a result looks like to a consumer, ignoring its timestamp
+fn fingerprint(result: &Result<DetectionResult, DetectionError>) -> String {
+    match result {
+        Ok(result) => serde_json::to_string(&(
+            &result.ide_name,
+            &result.active_file,
+            &result.open_files,
+            &result.project_path,
+        ))
+        .unwrap_or_default(),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FileInfo;
+
+    fn result(active_file: Option<&str>, paths: &[&str]) -> DetectionResult {
+        let files = paths.iter().map(|path| FileInfo::for_test(path, Some(*path) == active_file)).collect();
+        DetectionResult::for_test("Zed", files)
+    }
+
+    #[test]
+    fn emitted_results_are_numbered_consecutively() {
+        let mut sequence = Sequence::default();
+        let seqs: Vec<Option<u64>> = (0..3)
+            .map(|_| {
+                let mut result = result(None, &[]);
+                sequence.stamp(&mut result);
+                result.seq
+            })
+            .collect();
+
+        assert_eq!(seqs, [Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn focus_time_is_summed_per_file_with_idle_gaps_apart() {
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+        let mut tracker = FocusTracker::default();
+        for (seconds, file) in [
+            (0, Some("/p/a.rs")),
+            (5, Some("/p/a.rs")),
+            (10, Some("/p/b.rs")),
+            (12, None),
+            (20, Some("/p/a.rs")),
+            (27, None),
+        ] {
+            tracker.observe(at(seconds), file, file.map(|_| "/p"));
+        }
+
+        let report = tracker.finish(at(30));
+        let totals: Vec<(Option<&str>, Option<&str>, f64)> = report
+            .iter()
+            .map(|t| (t.file.as_deref(), t.project.as_deref(), t.seconds))
+            .collect();
+
+        assert_eq!(
+            totals,
+            [
+                (Some("/p/a.rs"), Some("/p"), 17.0),
+                (None, None, 11.0),
+                (Some("/p/b.rs"), Some("/p"), 2.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn active_file_changes_skip_repeats_and_gaps() {
+        let mut changes = ActiveFileChanges::default();
+        let observed: Vec<Option<&str>> = [
+            Some("/p/a.rs"),
+            Some("/p/a.rs"),
+            Some("/p/b.rs"),
+            None,
+            Some("/p/b.rs"),
+            Some("/p/a.rs"),
+            Some("/p/a.rs"),
+        ]
+        .into_iter()
+        .map(|active| changes.observe(active))
+        .collect();
+
+        assert_eq!(
+            observed,
+            [Some("/p/a.rs"), None, Some("/p/b.rs"), None, None, Some("/p/a.rs"), None]
+        );
+    }
+
+    #[test]
+    fn diffs_report_closed_opened_and_activated_files() {
+        let event = |kind, path: &str| FileEvent {
+            kind,
+            path: path.to_string(),
+            ide_name: "Zed".to_string(),
+        };
+        let first = result(Some("/p/a.rs"), &["/p/a.rs", "/p/b.rs"]);
+        let second = result(Some("/p/c.rs"), &["/p/b.rs", "/p/c.rs"]);
+
+        assert_eq!(
+            diff_results(None, Some(&first)),
+            [
+                event(FileEventKind::Opened, "/p/a.rs"),
+                event(FileEventKind::Opened, "/p/b.rs"),
+                event(FileEventKind::Activated, "/p/a.rs"),
+            ]
+        );
+        assert_eq!(
+            diff_results(Some(&first), Some(&second)),
+            [
+                event(FileEventKind::Closed, "/p/a.rs"),
+                event(FileEventKind::Opened, "/p/c.rs"),
+                event(FileEventKind::Activated, "/p/c.rs"),
+            ]
+        );
+        assert_eq!(diff_results(Some(&second), Some(&second)), []);
+        assert_eq!(
+            diff_results(Some(&second), None),
+            [
+                event(FileEventKind::Closed, "/p/b.rs"),
+                event(FileEventKind::Closed, "/p/c.rs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_burst_of_changes_reports_only_the_settled_active_file() {
+        let window = Duration::from_millis(150);
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut settler = Settler::new(window);
+        let mut emitted = Vec::new();
+
+        // Switching a.rs -> c.rs, with the IDE briefly recording no active
+        // file and then b.rs as it rewrites its state
+        let burst = [(0, Some("/p/a.rs")), (40, None), (60, Some("/p/b.rs")), (100, Some("/p/c.rs"))];
+        for (millis, active) in burst {
+            settler.observe(format!("{:?}", active), active, at(millis));
+            emitted.extend(settler.settled(at(millis)).map(|(_, active)| active));
+        }
+        // A rewrite with the same content doesn't restart the window
+        settler.observe(format!("{:?}", Some("/p/c.rs")), Some("/p/c.rs"), at(200));
+        assert_eq!(settler.settled(at(249)), None);
+        assert_eq!(settler.deadline(), Some(at(250)));
+        emitted.extend(settler.settled(at(250)).map(|(_, active)| active));
+
+        assert_eq!(emitted, [Some("/p/c.rs")]);
+        assert_eq!(settler.deadline(), None);
+    }
+
+    /// Reports this test process as a running Sublime Text whose active file
+    /// is whatever path its state file holds
+    struct StateFileDetector {
+        state: PathBuf,
+    }
+
+    impl crate::detector::IDEDetector for StateFileDetector {
+        fn ide_type(&self) -> SupportedIDE {
+            SupportedIDE::Sublime
+        }
+
+        fn is_target_process(&self, process: &ProcessInfo) -> bool {
+            process.pid == std::process::id()
+        }
+
+        fn extract_files(&self, _processes: &[ProcessInfo]) -> crate::detector::DetectionResult<DetectionResult> {
+            let active = std::fs::read_to_string(&self.state).map_err(|e| DetectionError::SystemError {
+                message: e.to_string(),
+            })?;
+            Ok(DetectionResult {
+                ide_name: self.display_name().to_string(),
+                ..result(Some(active.trim()), &[active.trim()])
+            })
+        }
+
+        fn state_paths(&self, _result: &DetectionResult) -> Vec<PathBuf> {
+            vec![self.state.clone()]
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[test]
+    fn writing_a_watched_state_file_triggers_a_redetection() {
+        let dir = std::env::temp_dir().join(format!("ide-files-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let state = dir.join("session");
+        std::fs::write(&state, "/p/a.rs").unwrap();
+
+        let mut manager = IDEDetectorManager::new();
+        manager.register_detector(Box::new(StateFileDetector { state: state.clone() }));
+        let stop = Arc::new(AtomicBool::new(false));
+        let timeout = {
+            let stop = stop.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_secs(10));
+                stop.store(true, Ordering::SeqCst);
+            })
+        };
+
+        // Process polls are an hour apart, so only the file event can cause
+        // the second detection
+        let mut seen = Vec::new();
+        DetectionWatcher::new(&manager, Some(SupportedIDE::Sublime))
+            .with_poll_interval(Duration::from_secs(3600))
+            .with_debounce(Duration::from_millis(10))
+            .with_stop_flag(stop.clone())
+            .run(|result| {
+                let result = result.unwrap();
+                seen.push((result.seq, result.active_file));
+                if seen.len() == 1 {
+                    std::fs::write(&state, "/p/b.rs").unwrap();
+                }
+                seen.len() < 2
+            });
+        stop.store(true, Ordering::SeqCst);
+        std::fs::remove_dir_all(&dir).unwrap();
+        drop(timeout);
+
+        assert_eq!(
+            seen,
+            [(Some(1), Some("/p/a.rs".to_string())), (Some(2), Some("/p/b.rs".to_string()))]
+        );
+    }
+}
@@ -0,0 +1,472 @@
+//! End-to-end runs of the built binary against fixture IDE state and fake
+//! IDE processes (copies of `sh` named like the IDE, with the project path on
+//! their command line)
+
+#![cfg(target_os = "linux")]
+
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output};
+
+/// A scratch directory removed on drop
+struct Fixture {
+    root: PathBuf,
+}
+
+impl Fixture {
+    fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("ide-files-cli-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        Self {
+            root: root.canonicalize().unwrap(),
+        }
+    }
+
+    fn path(&self, relative: &str) -> String {
+        self.root.join(relative).to_str().unwrap().to_string()
+    }
+
+    fn write(&self, relative: &str, content: &str) -> String {
+        let path = self.root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    /// Start a process named `name` that stays alive with `args` on its command line
+    fn spawn_ide(&self, name: &str, args: &[&str]) -> IdeProcess {
+        let executable = self.root.join(name);
+        fs::copy("/bin/sh", &executable).unwrap();
+        let child = Command::new(&executable)
+            .args(["-c", "sleep 30; :"])
+            .args(args)
+            .spawn()
+            .unwrap();
+        IdeProcess(child)
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+/// A fake IDE process, killed on drop
+struct IdeProcess(Child);
+
+impl Drop for IdeProcess {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn run(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ide-files"))
+        .args(args)
+        .env("HOME", home)
+        .env_remove("DISPLAY")
+        .env_remove("SUDO_USER")
+        .env_remove("XDG_CACHE_HOME")
+        .output()
+        .unwrap()
+}
+
+/// Parse a successful JSON result, minus its timestamp
+fn parse_result(output: &Output) -> Value {
+    assert!(
+        output.status.success(),
+        "exit {:?}: {}",
+        output.status.code(),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let mut result: Value = serde_json::from_slice(&output.stdout).unwrap();
+    result.as_object_mut().unwrap().remove("timestamp");
+    result
+}
+
+fn file(path: &str, is_active: bool, source: &str, project_name: Value, project_path: &str) -> Value {
+    json!({
+        "path": path,
+        "name": Path::new(path).file_name().unwrap().to_str().unwrap(),
+        "is_active": is_active,
+        "is_modified": false,
+        "tab_index": null,
+        "project_name": project_name,
+        "source": source,
+        "cursor_line": null,
+        "cursor_column": null,
+        "comparison": null,
+        "project_path": project_path,
+        "read_only": false,
+        "is_pinned": false,
+        "is_preview": false,
+        "real_path": null,
+        "terminal": null,
+    })
+}
+
+/// `file` for an editor that records tab positions
+fn with_tab_index(mut file: Value, tab_index: usize) -> Value {
+    file["tab_index"] = json!(tab_index);
+    file
+}
+
+/// A VSCode workspaceStorage entry at `storage` (relative to the fixture)
+/// for the folder `project`, whose one editor group has `files` open with
+/// the last one focused
+fn write_vscode_state(fixture: &Fixture, storage: &str, project: &str, files: &[&str]) {
+    fixture.write(
+        &format!("{}/workspace.json", storage),
+        &json!({ "folder": format!("file://{}", project) }).to_string(),
+    );
+    let editor = |path: &&str| {
+        json!({
+            "id": "workbench.editors.files.fileEditorInput",
+            "value": json!({ "resourceJSON": { "fsPath": path } }).to_string(),
+        })
+    };
+    let editors: Vec<Value> = files.iter().map(editor).collect();
+    let mru: Vec<usize> = (0..files.len()).rev().collect();
+    let editor_state = json!({
+        "editorpart.state": {
+            "serializedGrid": {
+                "root": { "type": "leaf", "data": { "editors": editors, "mru": mru } }
+            }
+        }
+    });
+    let db = rusqlite::Connection::open(Path::new(&fixture.path(storage)).join("state.vscdb")).unwrap();
+    db.execute("CREATE TABLE ItemTable (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB)", [])
+        .unwrap();
+    db.execute(
+        "INSERT INTO ItemTable (key, value) VALUES ('memento/workbench.parts.editor', ?1)",
+        [editor_state.to_string()],
+    )
+    .unwrap();
+}
+
+#[test]
+fn detects_vscode_session_from_config_dir() {
+    let fixture = Fixture::new("vscode");
+    let project = fixture.path("project");
+    let main = fixture.write("project/src/main.rs", "fn main() {}\n");
+    let lib = fixture.write("project/src/lib.rs", "\n");
+
+    // One workspace whose editor group has main.rs and lib.rs open, lib.rs focused
+    write_vscode_state(&fixture, "config/User/workspaceStorage/fixture", &project, &[&main, &lib]);
+
+    let _code = fixture.spawn_ide("code", &[&project]);
+    let output = run(
+        &fixture.root,
+        &["--ide", "vscode", "--format", "json", "--ide-config-dir", &fixture.path("config")],
+    );
+
+    assert_eq!(
+        parse_result(&output),
+        json!({
+            "format_version": 1,
+            "ide_name": "Visual Studio Code",
+            "ide_version": null,
+            "active_file": lib,
+            "open_files": [
+                with_tab_index(file(&lib, true, "session_state", Value::Null, &project), 1),
+                with_tab_index(file(&main, false, "session_state", Value::Null, &project), 0),
+            ],
+            "project_path": project,
+            "group_count": 1,
+        })
+    );
+}
+
+#[test]
+fn stable_and_insiders_vscode_are_reported_separately() {
+    let fixture = Fixture::new("vscode-insiders");
+    let stable_project = fixture.path("stable");
+    let insiders_project = fixture.path("insiders");
+    let stable_file = fixture.write("stable/main.go", "package main\n");
+    let insiders_file = fixture.write("insiders/app.ts", "\n");
+
+    // Each variant keeps its own state under ~/.config
+    write_vscode_state(&fixture, ".config/Code/User/workspaceStorage/a", &stable_project, &[&stable_file]);
+    write_vscode_state(
+        &fixture,
+        ".config/Code - Insiders/User/workspaceStorage/b",
+        &insiders_project,
+        &[&insiders_file],
+    );
+
+    let _code = fixture.spawn_ide("code", &[&stable_project]);
+    let _insiders = fixture.spawn_ide("code-insiders", &[&insiders_project]);
+    let stable = parse_result(&run(&fixture.root, &["--ide", "vscode", "--format", "json"]));
+    let insiders = parse_result(&run(&fixture.root, &["--ide", "vscode-insiders", "--format", "json"]));
+
+    let summary = |result: &Value| {
+        (
+            result["ide_name"].clone(),
+            result["project_path"].clone(),
+            result["active_file"].clone(),
+            result["open_files"].as_array().unwrap().len(),
+        )
+    };
+    assert_eq!(summary(&stable), (json!("Visual Studio Code"), json!(stable_project), json!(stable_file), 1));
+    assert_eq!(
+        summary(&insiders),
+        (json!("Visual Studio Code - Insiders"), json!(insiders_project), json!(insiders_file), 1)
+    );
+}
+
+#[test]
+fn detects_goland_project_from_command_line() {
+    let fixture = Fixture::new("goland");
+    let project = fixture.path("service");
+    let main = fixture.write("service/main.go", "package main\n");
+    let handler = fixture.write("service/api/handler.go", "package api\n");
+    fixture.write("service/.idea/.name", "Fixture Service\n");
+    fixture.write(
+        "service/.idea/workspace.xml",
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<project version="4">
+  <component name="FileEditorManager">
+    <leaf>
+      <file current-in-tab="false">
+        <entry file="file://$PROJECT_DIR$/api/handler.go" />
+      </file>
+      <file current-in-tab="true">
+        <entry file="file://$PROJECT_DIR$/main.go" />
+      </file>
+    </leaf>
+  </component>
+</project>
+"#,
+    );
+
+    let _goland = fixture.spawn_ide("goland", &[&project]);
+    let output = run(
+        &fixture.root,
+        &["--ide", "goland", "--format", "json", "--ide-config-dir", &fixture.path("config")],
+    );
+
+    let name = json!("Fixture Service");
+    assert_eq!(
+        parse_result(&output),
+        json!({
+            "format_version": 1,
+            "ide_name": "GoLand",
+            "ide_version": null,
+            "active_file": main,
+            "open_files": [
+                with_tab_index(file(&main, true, "session_state", name.clone(), &project), 1),
+                with_tab_index(file(&handler, false, "session_state", name, &project), 0),
+            ],
+            "project_path": project,
+            "group_count": 1,
+        })
+    );
+}
+
+#[test]
+fn exits_with_failure_when_the_ide_is_not_running() {
+    let fixture = Fixture::new("none");
+    let output = run(&fixture.root, &["--ide", "clion", "--format", "json"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No CLion processes found"));
+}
+
+#[test]
+fn notify_is_rejected_with_watch() {
+    let fixture = Fixture::new("notify-watch");
+    let output = run(&fixture.root, &["--auto", "--watch", "--notify"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+}
+
+#[test]
+fn tracking_without_an_ide_reports_only_idle_time() {
+    let fixture = Fixture::new("track");
+    let output = run(&fixture.root, &["--ide", "clion", "--track", "300ms", "--interval", "100ms"]);
+
+    assert!(output.status.success());
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let buckets = report.as_array().unwrap();
+    assert_eq!(buckets.len(), 1);
+    assert_eq!((&buckets[0]["file"], &buckets[0]["project"]), (&Value::Null, &Value::Null));
+    assert!(buckets[0]["seconds"].as_f64().unwrap() >= 0.3);
+}
+
+#[test]
+fn all_reports_every_running_ide() {
+    let fixture = Fixture::new("all");
+    let code_project = fixture.path("web");
+    let goland_project = fixture.path("service");
+    let app = fixture.write("web/app.ts", "\n");
+    fixture.write("service/main.go", "package main\n");
+    write_vscode_state(&fixture, ".config/Code/User/workspaceStorage/a", &code_project, &[&app]);
+
+    let _code = fixture.spawn_ide("code", &[&code_project]);
+    let _goland = fixture.spawn_ide("goland", &[&goland_project]);
+    let output = run(&fixture.root, &["--all"]);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let results: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let mut found: Vec<(Value, Value)> = results
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| (result["ide_name"].clone(), result["project_path"].clone()))
+        .collect();
+    found.sort_by_key(|(ide_name, _)| ide_name.to_string());
+    assert_eq!(
+        found,
+        [
+            (json!("GoLand"), json!(goland_project)),
+            (json!("Visual Studio Code"), json!(code_project)),
+        ]
+    );
+}
+
+#[test]
+fn relative_paths_are_printed_against_the_project_root() {
+    let fixture = Fixture::new("relative");
+    let project = fixture.path("service");
+    fixture.write("service/main.go", "package main\n");
+    fixture.write("service/api/handler.go", "package api\n");
+    let outside = fixture.write("shared/util.go", "package shared\n");
+    fixture.write(
+        "service/.idea/workspace.xml",
+        &format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<project version="4">
+  <component name="FileEditorManager">
+    <leaf>
+      <file current-in-tab="true">
+        <entry file="file://$PROJECT_DIR$/api/handler.go" />
+      </file>
+      <file current-in-tab="false">
+        <entry file="file://{}" />
+      </file>
+    </leaf>
+  </component>
+</project>
+"#,
+            outside
+        ),
+    );
+
+    let _goland = fixture.spawn_ide("goland", &[&project]);
+    let paths = run(&fixture.root, &["--ide", "goland", "--relative", "--format", "paths"]);
+    assert!(paths.status.success(), "{}", String::from_utf8_lossy(&paths.stderr));
+    assert_eq!(String::from_utf8_lossy(&paths.stdout), format!("api/handler.go\n{}\n", outside));
+
+    let result = parse_result(&run(&fixture.root, &["--ide", "goland", "--relative"]));
+    assert_eq!(result["active_file"], json!("api/handler.go"));
+    assert_eq!(result["project_path"], json!(project));
+}
+
+/// The cache files written so far under the fixture's `~/.cache`
+fn cache_files(fixture: &Fixture) -> Vec<PathBuf> {
+    fs::read_dir(fixture.root.join(".cache/ide-files"))
+        .map(|entries| entries.map(|entry| entry.unwrap().path()).collect())
+        .unwrap_or_default()
+}
+
+#[test]
+fn cached_result_is_reused_and_a_truncated_cache_is_a_miss() {
+    let fixture = Fixture::new("cache");
+    let project = fixture.path("project");
+    let main = fixture.write("project/main.c", "\n");
+    write_vscode_state(&fixture, "config/User/workspaceStorage/fixture", &project, &[&main]);
+    let args = ["--ide", "vscode", "--cache", "1m", "--ide-config-dir", &fixture.path("config")];
+
+    let code = fixture.spawn_ide("code", &[&project]);
+    assert_eq!(parse_result(&run(&fixture.root, &args))["active_file"], json!(main));
+    drop(code);
+
+    // VSCode is no longer running: only the cache can have produced this
+    assert_eq!(parse_result(&run(&fixture.root, &args))["active_file"], json!(main));
+
+    let cached = cache_files(&fixture);
+    assert_eq!(cached.len(), 1);
+    let json = fs::read(&cached[0]).unwrap();
+    fs::write(&cached[0], &json[..json.len() / 2]).unwrap();
+    let output = run(&fixture.root, &args);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No Visual Studio Code processes found"));
+}
+
+#[test]
+fn runs_with_different_config_dirs_do_not_share_a_cache() {
+    let fixture = Fixture::new("cache-config");
+    let project = fixture.path("project");
+    let main = fixture.write("project/main.c", "\n");
+    let util = fixture.write("project/util.c", "\n");
+    write_vscode_state(&fixture, "first/User/workspaceStorage/fixture", &project, &[&main]);
+    write_vscode_state(&fixture, "second/User/workspaceStorage/fixture", &project, &[&util]);
+
+    let _code = fixture.spawn_ide("code", &[&project]);
+    let active_file = |config: &str| {
+        let output = run(
+            &fixture.root,
+            &["--ide", "vscode", "--cache", "1m", "--ide-config-dir", &fixture.path(config)],
+        );
+        parse_result(&output)["active_file"].clone()
+    };
+
+    assert_eq!(active_file("first"), json!(main));
+    assert_eq!(active_file("second"), json!(util));
+    assert_eq!(active_file("first"), json!(main));
+    assert_eq!(cache_files(&fixture).len(), 2);
+}
+
+#[test]
+fn all_groups_files_by_language_across_ides() {
+    let fixture = Fixture::new("all-by-language");
+    let code_project = fixture.path("web");
+    let goland_project = fixture.path("service");
+    let app = fixture.write("web/app.ts", "\n");
+    let util = fixture.write("web/util.ts", "\n");
+    let main = fixture.write("service/main.go", "package main\n");
+    write_vscode_state(&fixture, ".config/Code/User/workspaceStorage/a", &code_project, &[&app, &util]);
+    fixture.write(
+        "service/.idea/workspace.xml",
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<project version="4">
+  <component name="FileEditorManager">
+    <leaf>
+      <file current-in-tab="true">
+        <entry file="file://$PROJECT_DIR$/main.go" />
+      </file>
+    </leaf>
+  </component>
+</project>
+"#,
+    );
+
+    let _code = fixture.spawn_ide("code", &[&code_project]);
+    let _goland = fixture.spawn_ide("goland", &[&goland_project]);
+    let output = run(&fixture.root, &["--all", "--format", "by-language-json"]);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let mut groups: Value = serde_json::from_slice(&output.stdout).unwrap();
+    groups[0]["files"].as_array_mut().unwrap().sort_by_key(|path| path.to_string());
+    assert_eq!(
+        groups,
+        json!([
+            {"language": "typescript", "count": 2, "files": [app, util]},
+            {"language": "go", "count": 1, "files": [main]},
+        ])
+    );
+
+    let output = run(&fixture.root, &["--all", "--format", "by-language"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("typescript (2)\n"));
+
+    let output = run(&fixture.root, &["--all", "--format", "count-by-ext", "--json"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(serde_json::from_slice::<Value>(&output.stdout).unwrap(), json!({"ts": 2, "go": 1}));
+}